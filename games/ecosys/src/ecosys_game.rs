@@ -3,9 +3,10 @@ use rand::Rng;
 use rusclog::{
     debug_print,
     engine::{
-        rigid_body::{BodyId, RigidBody},
+        rigid_body::RigidBody,
         world_bounds::{BoundsBehavior, WorldBounds},
-        Collider, EngineServices, Game, GameConfig, ParticleSystem, Quad, TextRenderer,
+        BackgroundSpec, Collider, EngineServices, Game, GameConfig, ParticleSystem, Quad,
+        TextRenderer,
     },
 };
 use sokol::{
@@ -74,25 +75,21 @@ impl EcosysGame {
         let title = "Ecosys";
 
         // Dark space background
-        let bg = Quad::new(0.0, 0.0, 800.0, 600.0, Vec4::new(0.0, 0.0, 0.1, 1.0));
-        services.renderer.draw_quad(&bg);
+        services
+            .renderer
+            .draw_background(&BackgroundSpec::Color(Vec4::new(0.0, 0.0, 0.1, 1.0)));
 
         // Calculate progress
         let progress = (self.loading_timer / self.loading_duration).min(1.0);
 
-        // Loading bar background
-        let bar_bg = Quad::new(0.0, -10.0, 400.0, 40.0, Vec4::new(0.2, 0.2, 0.3, 1.0));
-        services.renderer.draw_quad(&bar_bg);
-
-        // Loading bar fill
-        let bar_fill = Quad::new(
-            -200.0 + (400.0 * progress * 0.5), // Center the fill properly
-            -10.0,
-            400.0 * progress,
-            40.0,
+        // Loading bar
+        let bar = Quad::new(0.0, -10.0, 400.0, 40.0, Vec4::ZERO);
+        services.renderer.draw_progress_bar(
+            &bar,
+            progress,
+            Vec4::new(0.2, 0.2, 0.3, 1.0),
             Vec4::new(0.0, 0.6, 1.0, 1.0),
         );
-        services.renderer.draw_quad(&bar_fill);
 
         // Title and text
         if let Some(text) = &self.text {
@@ -302,18 +299,21 @@ impl Game for EcosysGame {
         self.text = Some(TextRenderer::new("font", 16.0, 16.0, 16, 6));
 
         // Spawn grass
-        let mut rng = rand::rng();
+        let rng = &mut *services.rng;
+        let bounds = WorldBounds {
+            min: self.world_min,
+            max: self.world_max,
+        };
         for _ in 0..100 {
-            let x = rng.random_range(self.world_min.x + 10.0..self.world_max.x - 10.0);
-            let y = rng.random_range(self.world_min.y + 10.0..self.world_max.y - 10.0);
-            self.grass_patches.push(Grass::new(Vec2::new(x, y)));
+            let position = bounds.random_point_inset(rng, 10.0);
+            self.grass_patches.push(Grass::new(position));
         }
 
         // Create player
         let spawn_position = Vec2::ZERO;
         let player_collider =
             Collider::new_circle(spawn_position.x, spawn_position.y, self.player.size * 0.5);
-        let player_body = RigidBody::new_kinematic(BodyId(1000), spawn_position, player_collider);
+        let player_body = RigidBody::new_kinematic(spawn_position, player_collider);
         let body_id = services.physics.add_body(player_body);
         self.player.body_id = Some(body_id);
 
@@ -355,7 +355,7 @@ impl Game for EcosysGame {
                     // Very frequent
                     self.border_particle_timer = 0.0;
 
-                    let mut rng = rand::rng();
+                    let rng = &mut *services.rng;
 
                     // Spawn many particles per frame for thick fog
                     for _ in 0..8 {
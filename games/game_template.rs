@@ -75,7 +75,7 @@ impl Game for TemplateGame {
     
     /// Render the window.
     fn render(&mut self, renderer: &mut crate::engine::Renderer, camera: &mut crate::engine::Camera2D) {
-        renderer.begin_frame();
+        renderer.begin_frame(camera);
 
         match self.game_state {
             TemplateGameState::MainMenu => todo!(),
@@ -1,4 +1,4 @@
-use crate::engine::{Circle, Collider, Game, GameConfig, InputManager, Quad};
+use crate::engine::{BackgroundSpec, Circle, Collider, Game, GameConfig, InputManager, Quad};
 use glam::{Vec2, Vec4};
 use rand::Rng;
 use rusclog::{
@@ -15,6 +15,9 @@ use sokol::{
 };
 use std::collections::HashMap;
 
+/// Top-right screen area covered by the HUD message, clicks there don't spawn balls
+const HUD_CAPTURE_SIZE: Vec2 = Vec2::new(220.0, 60.0);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PhysicsGameState {
     InitialLoading,
@@ -60,8 +63,7 @@ impl PhysicsGame {
     }
 
     fn add_ball(&mut self, position: Vec2, services: &mut EngineServices) {
-        let ball_id = self.balls.len() as u32 + 1.0 as u32;
-        let mut rng = rand::rng();
+        let rng = &mut *services.rng;
 
         let colors = [
             Vec4::new(1.0, 0.5, 0.0, 1.0), // Orange
@@ -85,8 +87,8 @@ impl PhysicsGame {
         let circle = Circle::new(position.x, position.y, radius, color)
             .with_line(0.0)
             .with_line_color(Vec4::new(1.0, 1.0, 1.0, 1.0));
-        let collider = Collider::new_circle(position.x, position.y, radius);
-        let body = RigidBody::new_dynamic(BodyId(ball_id), position, collider, mass)
+        let collider = Collider::from(&circle);
+        let body = RigidBody::new_dynamic(position, collider, mass)
             .with_restitution(0.8)
             .with_friction(0.2);
 
@@ -108,15 +110,10 @@ impl PhysicsGame {
         );
 
         // Create collider at center position
-        let platform_collider = Collider::new_rect(
-            platform_pos.x,
-            platform_pos.y,
-            platform_size.x,
-            platform_size.y,
-        );
+        let platform_collider = Collider::from(&platform_quad);
 
         let mut platform_body =
-            RigidBody::new_static(BodyId(0), platform_pos, platform_collider).with_restitution(0.2);
+            RigidBody::new_static(platform_pos, platform_collider).with_restitution(0.2);
         // Ensure the collider position matches the body position
         platform_body.collider.position = platform_pos;
 
@@ -126,25 +123,21 @@ impl PhysicsGame {
 
     fn render_startup_loading(&mut self, services: &mut EngineServices) {
         // Dark space background
-        let bg = Quad::new(0.0, 0.0, 800.0, 600.0, Vec4::new(0.0, 0.0, 0.1, 1.0));
-        services.renderer.draw_quad(&bg);
+        services
+            .renderer
+            .draw_background(&BackgroundSpec::Color(Vec4::new(0.0, 0.0, 0.1, 1.0)));
 
         // Calculate progress
         let progress = (self.loading_timer / self.loading_duration).min(1.0);
 
-        // Loading bar background
-        let bar_bg = Quad::new(0.0, -20.0, 400.0, 40.0, Vec4::new(0.2, 0.2, 0.3, 1.0));
-        services.renderer.draw_quad(&bar_bg);
-
-        // Loading bar fill
-        let bar_fill = Quad::new(
-            -200.0 + (400.0 * progress * 0.5), // Center the fill properly
-            -20.0,
-            400.0 * progress,
-            40.0,
+        // Loading bar
+        let bar = Quad::new(0.0, -20.0, 400.0, 40.0, Vec4::ZERO);
+        services.renderer.draw_progress_bar(
+            &bar,
+            progress,
+            Vec4::new(0.2, 0.2, 0.3, 1.0),
             Vec4::new(0.0, 0.6, 1.0, 1.0),
         );
-        services.renderer.draw_quad(&bar_fill);
 
         // Title and text
         if let Some(text) = &self.text {
@@ -205,6 +198,8 @@ impl Game for PhysicsGame {
         self.new_background = true;
         services.physics.set_global_gravity(Vec2::new(0.0, -685.0));
         services.physics.set_substeps(8);
+        // Keep the bouncy ball pit from gaining energy
+        services.physics.set_energy_cap(Some(0.01));
         services.physics.set_world_bounds(
             Some(WorldBounds {
                 min: Vec2::new(-3000.0, -3000.0),
@@ -227,6 +222,17 @@ impl Game for PhysicsGame {
         debug_print!("Window size: {}x{}", sapp::width(), sapp::height());
     }
 
+    fn update_ui(&mut self, input: &mut InputManager, services: &mut EngineServices) {
+        if self.hud_msg.is_none() {
+            return;
+        }
+        let mouse = input.mouse_position();
+        let screen_width = services.camera.viewport_size().x;
+        if mouse.x >= screen_width - HUD_CAPTURE_SIZE.x && mouse.y <= HUD_CAPTURE_SIZE.y {
+            input.set_mouse_captured(true);
+        }
+    }
+
     fn update(&mut self, dt: f32, input: &InputManager, services: &mut EngineServices) {
         if self.hud_timer > 0.0 {
             self.hud_timer -= dt;
@@ -253,10 +259,10 @@ impl Game for PhysicsGame {
                     }
                 }
 
-                if input.is_mouse_button_down(sapp::Mousebutton::Left) {
+                if input.is_mouse_button_down(sapp::Mousebutton::Left) && !input.is_mouse_captured()
+                {
                     let mouse_pos = input.mouse_position();
-                    // Convert screen coordinates to world coordinates
-                    let world_pos = services.camera.screen_to_world(mouse_pos);
+                    let world_pos = services.pick_world_point(mouse_pos);
                     self.add_ball(world_pos, services);
 
                     debug_print!(
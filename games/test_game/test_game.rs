@@ -1,7 +1,7 @@
 use crate::engine::{
-    Camera2D, Circle, Collider, Game, GameConfig, InputManager,
+    BackgroundSpec, Camera2D, Circle, Collider, Game, GameConfig, InputManager,
     LoopType::{self},
-    ParticleSystem, Quad, Sprite, SpriteAnimations,
+    ParticleSystem, Quad, Sprite, SpriteAnimations, Transition,
 };
 use glam::{Vec2, Vec4};
 use rand::Rng;
@@ -11,7 +11,7 @@ use rusclog::{
         gravity::{GravityFalloff, GravityField},
         physics_world::PhysicsWorld,
         rigid_body::{BodyId, RigidBody},
-        EngineServices,
+        EngineServices, GameRng,
     },
 };
 use sokol::{
@@ -53,6 +53,9 @@ pub struct TestGame {
     hud_timer: f32,
     loading_timer: f32,
     loading_duration: f32,
+    transition: Transition,
+    /// State to switch to once the fade out has covered the screen
+    pending_state: Option<TestGameState>,
 }
 
 // Functions and functionality for the test game
@@ -96,6 +99,8 @@ impl TestGame {
             hud_timer: 0.0,
             loading_timer: 0.0,
             loading_duration: 2.0,
+            transition: Transition::default(),
+            pending_state: None,
         }
     }
 
@@ -104,7 +109,12 @@ impl TestGame {
         self.new_background = true;
     }
 
-    fn reset_game(&mut self, camera: &mut Camera2D, physics_world: &mut PhysicsWorld) {
+    fn reset_game(
+        &mut self,
+        camera: &mut Camera2D,
+        physics_world: &mut PhysicsWorld,
+        rng: &mut GameRng,
+    ) {
         // Reset game to initial state
         self.frame_count = 0;
         self.current_background = sg::Color {
@@ -122,7 +132,6 @@ impl TestGame {
         // physics_world.clear_all_bodies_except(self.player_body_id);
 
         // Regenerate asteroids
-        let mut rng = rand::rng();
         for i in 0..20 {
             let x = rng.random_range(-1000.0..=1000.0);
             let y = rng.random_range(-750.0..=750.0);
@@ -138,8 +147,8 @@ impl TestGame {
             let segments = rng.random_range(5.0..=32.0) as u32;
 
             let circle = Circle::new(x, y, radius, color).with_segments(segments);
-            let collider = Collider::new_circle(x, y, radius);
-            let mut body = RigidBody::new_static(BodyId(i as u32), Vec2::new(x, y), collider);
+            let collider = Collider::from(&circle);
+            let mut body = RigidBody::new_static(Vec2::new(x, y), collider);
 
             if i == 0 {
                 let gravity_field = GravityField::new(200.0, 300.0, GravityFalloff::Custom(0.001));
@@ -183,58 +192,45 @@ impl TestGame {
 
     fn spawn_confetti_rain(
         &self,
-        area_min: Vec2,
-        area_max: Vec2,
+        screen_width: f32,
         duration: f32,
         particle_systems: &mut HashMap<String, ParticleSystem>,
     ) {
-        // Steady rain from the top edge across the width
-        let width = area_max.x - area_min.x;
-        let top_y = area_max.y;
-
+        // Steady rain from the top edge of the screen, independent of the camera.
         // Pick a center spawn; system will randomize velocity, we randomize color via palette
-        let sys = ParticleSystem::new(
-            Vec2::new(area_min.x + width * 0.5, top_y),
-            120.0,
-            duration,
-            1.2,
-        )
-        .with_velocity_range(Vec2::new(-20.0, -160.0), Vec2::new(20.0, -100.0))
-        .with_color_palette(vec![
-            Vec4::new(1.0, 0.2, 0.2, 1.0),
-            Vec4::new(1.0, 0.6, 0.2, 1.0),
-            Vec4::new(1.0, 1.0, 0.2, 1.0),
-            Vec4::new(0.2, 0.9, 0.4, 1.0),
-            Vec4::new(0.3, 0.6, 1.0, 1.0),
-            Vec4::new(0.8, 0.4, 1.0, 1.0),
-        ])
-        .with_drag(0.0);
+        let sys = ParticleSystem::new(Vec2::new(screen_width * 0.5, 0.0), 120.0, duration, 1.2)
+            .with_screen_space(true)
+            .with_velocity_range(Vec2::new(-20.0, 100.0), Vec2::new(20.0, 160.0))
+            .with_color_palette(vec![
+                Vec4::new(1.0, 0.2, 0.2, 1.0),
+                Vec4::new(1.0, 0.6, 0.2, 1.0),
+                Vec4::new(1.0, 1.0, 0.2, 1.0),
+                Vec4::new(0.2, 0.9, 0.4, 1.0),
+                Vec4::new(0.3, 0.6, 1.0, 1.0),
+                Vec4::new(0.8, 0.4, 1.0, 1.0),
+            ])
+            .with_drag(0.0);
 
         let key = format!("confetti_{}", rand::rng().random_range(0..1_000_000));
         particle_systems.insert(key, sys);
     }
 
     fn render_startup_loading(&mut self, services: &mut EngineServices) {
-        // Dark space background - already centered at (0,0)
-        let bg = Quad::new(0.0, 0.0, 800.0, 600.0, Vec4::new(0.0, 0.0, 0.1, 1.0));
-        services.renderer.draw_quad(&bg);
+        // Dark space background
+        services
+            .renderer
+            .draw_background(&BackgroundSpec::Color(Vec4::new(0.0, 0.0, 0.1, 1.0)));
 
         let progress = (self.loading_timer / self.loading_duration).min(1.0);
 
-        // Loading bar background - already centered
-        let bar_bg = Quad::new(0.0, -20.0, 400.0, 40.0, Vec4::new(0.2, 0.2, 0.3, 1.0));
-        services.renderer.draw_quad(&bar_bg);
-
-        // Loading bar fill - needs to be positioned differently for center positioning
-        let fill_width = 400.0 * progress;
-        let bar_fill = Quad::new(
-            -200.0 + fill_width * 0.5, // Center the fill bar properly
-            -20.0,
-            fill_width,
-            40.0,
+        // Loading bar
+        let bar = Quad::new(0.0, -20.0, 400.0, 40.0, Vec4::ZERO);
+        services.renderer.draw_progress_bar(
+            &bar,
+            progress,
+            Vec4::new(0.2, 0.2, 0.3, 1.0),
             Vec4::new(0.0, 0.6, 1.0, 1.0),
         );
-        services.renderer.draw_quad(&bar_fill);
 
         // Title and text
         if let Some(text) = &self.text {
@@ -285,27 +281,21 @@ impl TestGame {
         );
         services.renderer.draw_quad(&overlay);
 
-        // Loading bar background - already centered
-        let bar_bg = Quad::new(
+        // Loading bar
+        let area_progress = self.loading_timer.min(1.0);
+        let bar = Quad::new(
             services.camera.get_position().x,
             services.camera.get_position().y - 15.0,
             300.0,
             30.0,
-            Vec4::new(0.1, 0.3, 0.1, 0.9),
+            Vec4::ZERO,
         );
-        services.renderer.draw_quad(&bar_bg);
-
-        // Loading bar fill - needs proper centering
-        let area_progress = self.loading_timer.min(1.0);
-        let fill_width = 300.0 * area_progress;
-        let bar_fill = Quad::new(
-            services.camera.get_position().x - 150.0 + fill_width * 0.5, // Center the fill
-            services.camera.get_position().y - 15.0,
-            fill_width,
-            30.0,
+        services.renderer.draw_progress_bar(
+            &bar,
+            area_progress,
+            Vec4::new(0.1, 0.3, 0.1, 0.9),
             Vec4::new(0.2, 0.8, 0.2, 1.0),
         );
-        services.renderer.draw_quad(&bar_fill);
 
         // Area loading text
         if let Some(text) = &self.text {
@@ -436,7 +426,7 @@ impl Game for TestGame {
 
         self.asteroids.clear(); // Clear any existing
         for i in 0..20 {
-            let mut rng = rand::rng();
+            let rng = &mut *services.rng;
             let x = rng.random_range(-1000.0..=1000.0);
             let y = rng.random_range(-750.0..=750.0);
 
@@ -451,8 +441,8 @@ impl Game for TestGame {
             let segments = rng.random_range(5.0..=32.0) as u32;
 
             let circle = Circle::new(x, y, radius, color).with_segments(segments);
-            let collider = Collider::new_circle(x, y, radius);
-            let mut body = RigidBody::new_static(BodyId(i as u32), Vec2::new(x, y), collider);
+            let collider = Collider::from(&circle);
+            let mut body = RigidBody::new_static(Vec2::new(x, y), collider);
 
             if i == 0 {
                 let gravity_field = GravityField::new(200.0, 300.0, GravityFalloff::Custom(0.001));
@@ -467,11 +457,10 @@ impl Game for TestGame {
         let radius = (self.player.size.x.min(self.player.size.y)) * 0.15;
         let player_collider =
             Collider::new_circle(self.player.position.x, self.player.position.y, radius);
-        let player_body =
-            RigidBody::new_dynamic(BodyId(999), self.player.position, player_collider, 1.0)
-                .with_restitution(0.05)
-                .with_friction(0.2)
-                .with_drag(0.6);
+        let player_body = RigidBody::new_dynamic(self.player.position, player_collider, 1.0)
+            .with_restitution(0.05)
+            .with_friction(0.2)
+            .with_drag(0.6);
         self.player_body_id = Some(services.physics.add_body(player_body));
 
         println!("Game initialized!");
@@ -488,6 +477,15 @@ impl Game for TestGame {
             }
         }
 
+        // Switch state behind the fade, then fade back in
+        self.transition.update(dt);
+        if self.pending_state.is_some() && self.transition.is_covered() {
+            if let Some(state) = self.pending_state.take() {
+                self.game_state = state;
+            }
+            self.transition.start_fade_in(0.3);
+        }
+
         // Handle game state transitions
         match self.game_state {
             TestGameState::InitialLoading => {
@@ -499,12 +497,13 @@ impl Game for TestGame {
             TestGameState::MainMenu => {
                 services.update_particles(dt);
 
-                if input.is_key_pressed(sapp::Keycode::Enter) {
-                    self.game_state = TestGameState::Playing;
+                if input.is_key_pressed(sapp::Keycode::Enter) && self.pending_state.is_none() {
+                    self.pending_state = Some(TestGameState::Playing);
+                    self.transition.start_fade_out(0.3);
                     println!("Starting game!");
                 }
                 if input.is_key_pressed(sapp::Keycode::Escape) {
-                    sapp::request_quit(); // Direct quit instead of SystemState
+                    services.request_quit();
                 }
             }
             TestGameState::Playing => {
@@ -674,9 +673,19 @@ impl Game for TestGame {
 
                 services.update_animations(dt, &mut vec![&mut self.player]);
 
-                // Camera follows the box with some offset
-                let target_camera_pos = self.player.position + Vec2::new(50.0, 25.0);
-                services.camera.set_position(target_camera_pos);
+                // Camera leads the ship in the direction it's moving
+                let player_velocity = self
+                    .player_body_id
+                    .and_then(|id| services.physics.get_body(id))
+                    .map(|body| body.velocity)
+                    .unwrap_or(Vec2::ZERO);
+                services.camera.follow_with_lookahead(
+                    self.player.position,
+                    player_velocity,
+                    0.3,
+                    5.0,
+                    dt,
+                );
                 services
                     .camera
                     .clamp_to_bounds(self.world_min, self.world_max);
@@ -700,7 +709,7 @@ impl Game for TestGame {
             }
             TestGameState::Dead => {
                 if input.is_key_pressed(sapp::Keycode::Enter) {
-                    self.reset_game(services.camera, services.physics);
+                    self.reset_game(services.camera, services.physics, services.rng);
                 }
                 if input.is_key_pressed(sapp::Keycode::Escape) {
                     self.game_state = TestGameState::MainMenu;
@@ -737,10 +746,9 @@ impl Game for TestGame {
                         );
                     }
 
-                    // Short confetti rain across a visible area
+                    // Short confetti rain from the top of the screen
                     self.spawn_confetti_rain(
-                        Vec2::new(-300.0, -200.0),
-                        Vec2::new(300.0, 200.0),
+                        services.renderer.screen_size().x,
                         0.8,
                         services.particles,
                     );
@@ -789,7 +797,7 @@ impl Game for TestGame {
                     self.completed_fx_started = false;
                 }
                 if input.is_key_pressed(sapp::Keycode::Enter) {
-                    self.reset_game(services.camera, services.physics);
+                    self.reset_game(services.camera, services.physics, services.rng);
                     self.completed_fx_started = false;
                 }
             }
@@ -930,6 +938,10 @@ impl Game for TestGame {
         }
     }
 
+    fn render_overlay(&mut self, services: &mut EngineServices) {
+        self.transition.render(services.renderer);
+    }
+
     // handle events that are not movement based
     fn handle_event(&mut self, event: &sapp::Event) {
         match event._type {
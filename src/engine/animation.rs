@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use glam::{Vec2, Vec4};
 
-use crate::engine::Sprite;
+use crate::engine::{collision::Collider, texture::grid_cell_uv, Sprite};
 
 #[derive(Clone, Debug)]
 pub enum LoopType {
@@ -18,6 +18,11 @@ pub struct SpriteAnimations {
     pub frames_per_row: u32,
     pub duration: f32,
     pub loop_type: LoopType,
+    /// Hitbox active on each frame, indexed by frame. Frames without one have no hitbox.
+    /// Collider positions are offsets from the sprite's position.
+    pub frame_colliders: Vec<Option<Collider>>,
+    /// Pixel size of the whole sheet. None assumes the sheet is exactly the frame grid.
+    pub sheet_size: Option<Vec2>,
 }
 
 #[derive(Clone, Debug)]
@@ -51,8 +56,52 @@ impl SpriteAnimations {
             frames_per_row,
             duration,
             loop_type,
+            frame_colliders: Vec::new(),
+            sheet_size: None,
         }
     }
+
+    /// Pixel size of the sheet texture, needed when it has more space than the frames use,
+    /// e.g. from `Renderer::texture_size`
+    pub fn with_sheet_size(mut self, sheet_size: Vec2) -> Self {
+        self.sheet_size = Some(sheet_size);
+        self
+    }
+
+    /// Pixel size of the sheet, see `sheet_size`
+    pub fn sheet_pixel_size(&self) -> Vec2 {
+        self.sheet_size.unwrap_or_else(|| {
+            let rows = self.frame_count.div_ceil(self.frames_per_row.max(1));
+            Vec2::new(self.frames_per_row as f32, rows as f32) * self.frame_size
+        })
+    }
+
+    /// UV rect of `frame` in the sheet
+    pub fn frame_uv(&self, frame: u32) -> Vec4 {
+        grid_cell_uv(self.sheet_pixel_size(), self.frame_size, self.frames_per_row, frame)
+    }
+
+    /// Make `collider` the active hitbox while `frame` is shown
+    pub fn with_frame_collider(mut self, frame: u32, collider: Collider) -> Self {
+        let index = frame as usize;
+        if self.frame_colliders.len() <= index {
+            self.frame_colliders.resize(index + 1, None);
+        }
+        self.frame_colliders[index] = Some(collider);
+        self
+    }
+
+    /// Make `collider` the active hitbox for every frame in `frames`
+    pub fn with_frame_collider_range(mut self, frames: std::ops::Range<u32>, collider: Collider) -> Self {
+        for frame in frames {
+            self = self.with_frame_collider(frame, collider);
+        }
+        self
+    }
+
+    pub fn collider_for_frame(&self, frame: u32) -> Option<&Collider> {
+        self.frame_colliders.get(frame as usize)?.as_ref()
+    }
 }
 
 impl AnimationManager {
@@ -113,22 +162,7 @@ impl AnimationManager {
                     }
                     
                     // Calculate UV coordinates for current frame
-                    let frame_width = animation.frame_size.x;
-                    let frame_height = animation.frame_size.y;
-                    
-                    let col = anim_state.current_frame % animation.frames_per_row;
-                    let row = anim_state.current_frame / animation.frames_per_row;
-                    
-                    // Assume spritesheet dimensions - you'll need actual texture size
-                    let sheet_width = animation.frames_per_row as f32 * frame_width;
-                    let sheet_height = ((animation.frame_count + animation.frames_per_row - 1) / animation.frames_per_row) as f32 * frame_height;
-                    
-                    sprite.uv = Vec4::new(
-                        col as f32 * frame_width / sheet_width,      // u
-                        row as f32 * frame_height / sheet_height,    // v
-                        frame_width / sheet_width,                   // width
-                        frame_height / sheet_height,                 // height
-                    );
+                    sprite.uv = animation.frame_uv(anim_state.current_frame);
                 }
             }
         }
@@ -148,13 +182,100 @@ impl AnimationManager {
         self.animations.insert(animation.name.clone(), animation);
     }
 
+    /// Stop and rewind to the first frame; `pause_animation` keeps the position instead
     pub fn stop_animation(&self, sprite: &mut Sprite) {
+        if let Some(ref mut anim_state) = sprite.animation_state {
+            anim_state.is_playing = false;
+            anim_state.current_frame = 0;
+            anim_state.elapsed_time = 0.0;
+            anim_state.is_reversed = false;
+        }
+    }
+
+    /// Freeze on the current frame, keeping the elapsed time so `resume_animation`
+    /// continues where it left off
+    pub fn pause_animation(&self, sprite: &mut Sprite) {
         if let Some(ref mut anim_state) = sprite.animation_state {
             anim_state.is_playing = false;
         }
     }
 
+    /// Continue a paused animation from its current frame and time.
+    /// Does nothing if the sprite has no animation; use `play_animation` to start one.
+    pub fn resume_animation(&self, sprite: &mut Sprite) {
+        if let Some(ref mut anim_state) = sprite.animation_state {
+            if anim_state.current_animation.is_some() {
+                anim_state.is_playing = true;
+            }
+        }
+    }
+
+    /// Hitbox of the frame the sprite is currently showing, if its animation defines one.
+    /// Check this after `update_sprite_animation` to enable or disable a hitbox body.
+    pub fn active_collider(&self, sprite: &Sprite) -> Option<&Collider> {
+        let anim_state = sprite.animation_state.as_ref()?;
+        let animation = self.animations.get(anim_state.current_animation.as_ref()?)?;
+        animation.collider_for_frame(anim_state.current_frame)
+    }
+
     pub fn clear_animation(&self, sprite: &mut Sprite) {
         sprite.animation_state = None;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attack_with_hitbox() -> AnimationManager {
+        let mut manager = AnimationManager::new();
+        manager.register_animation(
+            SpriteAnimations::new(
+                "attack".to_string(),
+                "hero".to_string(),
+                Vec2::new(16.0, 16.0),
+                4,
+                4,
+                0.4,
+                LoopType::Loop,
+            )
+            .with_frame_collider(2, Collider::new_rect(8.0, 0.0, 12.0, 6.0)),
+        );
+        manager
+    }
+
+    #[test]
+    fn frame_collider_is_only_active_on_its_frame() {
+        let manager = attack_with_hitbox();
+        let mut sprite = Sprite::new();
+        manager.play_animation(&mut sprite, "attack");
+
+        manager.update_sprite_animation(&mut sprite, 0.15);
+        assert!(manager.active_collider(&sprite).is_none());
+
+        manager.update_sprite_animation(&mut sprite, 0.1);
+        let hitbox = manager.active_collider(&sprite).unwrap();
+        assert_eq!(hitbox.position, Vec2::new(8.0, 0.0));
+
+        manager.update_sprite_animation(&mut sprite, 0.1);
+        assert!(manager.active_collider(&sprite).is_none());
+    }
+
+    #[test]
+    fn resume_continues_from_the_paused_frame() {
+        let manager = attack_with_hitbox();
+        let mut sprite = Sprite::new();
+        manager.play_animation(&mut sprite, "attack");
+        manager.update_sprite_animation(&mut sprite, 0.15);
+
+        manager.pause_animation(&mut sprite);
+        manager.update_sprite_animation(&mut sprite, 1.0);
+        let state = sprite.animation_state.as_ref().unwrap();
+        assert_eq!(state.current_frame, 1);
+        assert!(!state.is_playing);
+
+        manager.resume_animation(&mut sprite);
+        manager.update_sprite_animation(&mut sprite, 0.1);
+        assert_eq!(sprite.animation_state.as_ref().unwrap().current_frame, 2);
+    }
+}
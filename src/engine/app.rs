@@ -1,16 +1,21 @@
 use crate::engine::physics_world::PhysicsWorld;
 use crate::engine::{
-    toggle_collision_debug, toggle_debug_panel, toggle_debug_text, AnimationManager, Camera2D,
-    DebugOverlay, EngineServices, Game, GameConfig, InputManager, ParticleSystem, Renderer,
+    debug_flags, request_frame_step, toggle_collision_debug, toggle_debug_panel, toggle_debug_text,
+    toggle_frame_step, toggle_particle_freeze, AnimationManager, Blackboard, Camera2D,
+    DebugOverlay, EngineServices, Game, GameConfig, GameRng, InputManager, ParticleSystem,
+    Renderer, Replay, ReplayPlayer, ReplayRecorder,
 };
 use sokol::{app as sapp, gfx as sg, glue as sglue};
 use std::collections::HashMap;
+use std::error::Error;
 use std::ffi::{self, CString};
 use std::time::Instant;
 
 pub struct App<T: Game> {
     game: T,
     config: GameConfig,
+    record_path: Option<String>,
+    replay: Option<Replay>,
 }
 
 // State structure that will be passed through sokol callbacks
@@ -25,26 +30,38 @@ struct AppState<T: Game> {
     physics_world: PhysicsWorld,
     debug_overlay: Option<DebugOverlay>,
     actual_work_time: f32,
+    time_scale: f32,
+    quit_requested: bool,
+    blackboard: Blackboard,
+    rng: GameRng,
+    resizable: bool,
+    min_size: Option<(i32, i32)>,
+    recording: Option<ReplayRecorder>,
+    playback: Option<ReplayPlayer>,
 }
 
-impl<T: Game> App<T> {
-    // default config
-    pub fn new(game: T) -> Self {
-        let config = T::config();
-        Self { game, config }
-    }
-
-    pub fn run(self) {
-        // Create the state that will be passed to callbacks
+impl<T: Game> AppState<T> {
+    fn new(
+        game: T,
+        config: &GameConfig,
+        record_path: Option<&str>,
+        replay: Option<Replay>,
+    ) -> Self {
         let mut pass_action = sg::PassAction::new();
         pass_action.colors[0] = sg::ColorAttachmentAction {
             load_action: sg::LoadAction::Clear,
-            clear_value: self.config.background_color,
+            clear_value: config.background_color,
             ..Default::default()
         };
 
-        let state = Box::new(AppState {
-            game: self.game,
+        // Playback restarts the engine RNG from the recorded seed so random gameplay repeats
+        let seed = match &replay {
+            Some(replay) => replay.seed,
+            None => rand::random(),
+        };
+
+        Self {
+            game,
             pass_action,
             renderer: Renderer::new(),
             input: InputManager::new(),
@@ -54,7 +71,58 @@ impl<T: Game> App<T> {
             physics_world: PhysicsWorld::new(),
             debug_overlay: None,
             actual_work_time: 0.0,
-        });
+            time_scale: 1.0,
+            quit_requested: false,
+            blackboard: Blackboard::new(),
+            rng: GameRng::new(seed),
+            resizable: config.resizable,
+            min_size: config.min_size,
+            recording: record_path.map(|path| ReplayRecorder::new(path, seed)),
+            playback: replay.map(ReplayPlayer::new),
+        }
+    }
+
+    /// Whether the game asked to quit since the last call, clearing the request
+    fn take_quit_request(&mut self) -> bool {
+        std::mem::take(&mut self.quit_requested)
+    }
+}
+
+/// Upper bound on a single frame's dt, so a stall doesn't explode the simulation
+const MAX_FRAME_DT: f32 = 0.25;
+
+impl<T: Game> App<T> {
+    // default config
+    pub fn new(game: T) -> Self {
+        let config = T::config();
+        Self {
+            game,
+            config,
+            record_path: None,
+            replay: None,
+        }
+    }
+
+    /// Record every frame's input to `path`, saved when the app shuts down
+    pub fn record(mut self, path: &str) -> Self {
+        self.record_path = Some(path.to_string());
+        self
+    }
+
+    /// Play back a recorded session instead of live input
+    pub fn replay(mut self, path: &str) -> Result<Self, Box<dyn Error>> {
+        self.replay = Some(Replay::load(path)?);
+        Ok(self)
+    }
+
+    pub fn run(self) {
+        // Create the state that will be passed to callbacks
+        let state = Box::new(AppState::new(
+            self.game,
+            &self.config,
+            self.record_path.as_deref(),
+            self.replay,
+        ));
 
         let user_data = Box::into_raw(state) as *mut ffi::c_void;
 
@@ -118,25 +186,11 @@ extern "C" fn init<T: Game>(user_data: *mut ffi::c_void) {
         }
     }
 
-    // Set up default pass action for clearing screen
-    state.pass_action.colors[0] = sg::ColorAttachmentAction {
-        load_action: sg::LoadAction::Clear,
-        clear_value: sg::Color {
-            r: 8.0,
-            g: 0.0,
-            b: 0.0,
-            a: 0.8,
-        },
-        ..Default::default()
-    };
-
     //  Init render
     state.renderer.init();
 
     // Set initial camera viewport
-    state
-        .camera
-        .set_viewport_size(sapp::width() as f32, sapp::height() as f32);
+    fit_viewport(state, sapp::width(), sapp::height());
 
     let mut services = EngineServices {
         physics: &mut state.physics_world,
@@ -144,6 +198,10 @@ extern "C" fn init<T: Game>(user_data: *mut ffi::c_void) {
         animation: &mut state.animation_manager,
         camera: &mut state.camera,
         renderer: &mut state.renderer,
+        time_scale: &mut state.time_scale,
+        quit_requested: &mut state.quit_requested,
+        blackboard: &mut state.blackboard,
+        rng: &mut state.rng,
     };
 
     // Let the game do its initialization
@@ -157,7 +215,59 @@ extern "C" fn frame<T: Game>(user_data: *mut ffi::c_void) {
     let state = unsafe { &mut *(user_data as *mut AppState<T>) };
     // Start timing the actual work
     let work_start = Instant::now();
-    let dt = sapp::frame_duration() as f32;
+    let real_dt = (sapp::frame_duration() as f32).min(MAX_FRAME_DT);
+    let advance = debug_flags().should_advance_frame();
+
+    simulate_frame(state, real_dt, advance);
+
+    // Game rendering only queues draws; the light buffer pass has to run before the
+    // swapchain pass begins
+    state.renderer.prepare_lighting(&mut state.camera);
+
+    sg::begin_pass(&sg::Pass {
+        action: state.pass_action,
+        swapchain: sglue::swapchain(),
+        ..Default::default()
+    });
+
+    state.renderer.flush_with_lighting(&mut state.camera);
+
+    let physics_stats = state.physics_world.stats();
+    let render_stats = state.renderer.last_frame_stats();
+    if let Some(debug_overlay) = &mut state.debug_overlay {
+        debug_overlay.render(Some(&physics_stats), Some(&render_stats));
+    }
+
+    sg::end_pass();
+    sg::commit();
+
+    // Measure actual work time at the end
+    state.actual_work_time = work_start.elapsed().as_secs_f32();
+
+    if state.take_quit_request() {
+        sapp::request_quit();
+    }
+}
+
+/// The part of a frame before any GPU work: replay input, game update and queued draws.
+/// Real frame time drives UI/camera effects, scaled time drives gameplay.
+fn simulate_frame<T: Game>(state: &mut AppState<T>, mut real_dt: f32, advance: bool) {
+    // During playback, recorded input and frame time replace live ones
+    if advance {
+        if let Some(player) = &mut state.playback {
+            match player.next_frame() {
+                Some(frame) => {
+                    for input in &frame.inputs {
+                        input.apply(&mut state.input);
+                    }
+                    real_dt = frame.dt;
+                }
+                // Finished, continue on live input
+                None => state.playback = None,
+            }
+        }
+    }
+    let dt = real_dt * state.time_scale;
 
     if let Some(debug_overlay) = &mut state.debug_overlay {
         debug_overlay.update(state.actual_work_time);
@@ -169,43 +279,43 @@ extern "C" fn frame<T: Game>(user_data: *mut ffi::c_void) {
         animation: &mut state.animation_manager,
         camera: &mut state.camera,
         renderer: &mut state.renderer,
+        time_scale: &mut state.time_scale,
+        quit_requested: &mut state.quit_requested,
+        blackboard: &mut state.blackboard,
+        rng: &mut state.rng,
     };
 
-    // Game always updates and renders - no special loading path
-    state.game.update(dt, &state.input, &mut services);
-    services.update_camera_shake(dt);
+    // While frame stepping, only update when a step was requested; rendering continues
+    if advance {
+        state.game.update_ui(&mut state.input, &mut services);
+        state.game.update(dt, &state.input, &mut services);
+        services.update_camera_shake(real_dt);
+        if let Some(recorder) = &mut state.recording {
+            recorder.end_frame(real_dt);
+        }
+    }
 
     if let Some(new_color) = state.game.request_background_color_change() {
         state.pass_action.colors[0].clear_value = new_color;
     }
 
-    // Single render path
-    sg::begin_pass(&sg::Pass {
-        action: state.pass_action,
-        swapchain: sglue::swapchain(),
-        ..Default::default()
-    });
-
     state.game.render(&mut services);
-    state.renderer.flush(&mut state.camera);
+    services.render_overlay(&mut state.game);
 
-    let physics_stats = state.physics_world.stats();
-    if let Some(debug_overlay) = &mut state.debug_overlay {
-        debug_overlay.render(Some(&physics_stats));
+    // Presses between steps stay pending until the update that gets to see them
+    if advance {
+        state.input.new_frame();
     }
-
-    sg::end_pass();
-    sg::commit();
-
-    // Measure actual work time at the end
-    state.actual_work_time = work_start.elapsed().as_secs_f32();
-
-    state.input.new_frame();
 }
 
 extern "C" fn cleanup<T: Game>(user_data: *mut ffi::c_void) {
     sg::shutdown();
-    let _state = unsafe { Box::from_raw(user_data as *mut AppState<T>) };
+    let state = unsafe { Box::from_raw(user_data as *mut AppState<T>) };
+    if let Some(recorder) = &state.recording {
+        if let Err(e) = recorder.save() {
+            eprintln!("Failed to save replay: {}", e);
+        }
+    }
     // State will be dropped automatically, cleaning up the game
 }
 
@@ -228,15 +338,74 @@ extern "C" fn event<T: Game>(event: *const sapp::Event, user_data: *mut ffi::c_v
                 toggle_debug_panel();
                 return;
             }
+            sapp::Keycode::F4 => {
+                toggle_frame_step();
+                return;
+            }
+            sapp::Keycode::F5 => {
+                toggle_particle_freeze();
+                return;
+            }
+            sapp::Keycode::Period if debug_flags().is_frame_step_enabled() => {
+                request_frame_step();
+                return;
+            }
             _ => {}
         }
     }
 
+    dispatch_event(state, event);
+}
+
+/// Route a window event to the engine and the game, once debug hotkeys are handled
+fn dispatch_event<T: Game>(state: &mut AppState<T>, event: &sapp::Event) {
+    if event._type == sapp::EventType::Resized {
+        if handle_resize(state, event) {
+            state.game.handle_event(event);
+        }
+        return;
+    }
+
+    // A replay only feeds the game recorded input, live input or focus changes reaching
+    // `handle_event` would make the run diverge from the recording
+    if state.playback.is_some() && event._type != sapp::EventType::QuitRequested {
+        return;
+    }
+
     process_input_events(state, event);
     state.game.handle_event(event);
 }
 
+/// Keep the camera viewport on the new framebuffer size, returning whether the game should
+/// see the event. sokol_app can't stop the OS window from resizing, so fixed-size games
+/// still follow the framebuffer and only miss the event.
+fn handle_resize<T: Game>(state: &mut AppState<T>, event: &sapp::Event) -> bool {
+    fit_viewport(state, event.window_width, event.window_height);
+    state.resizable
+}
+
+/// Match the camera viewport to the framebuffer. With a minimum size, a smaller framebuffer
+/// zooms out instead so at least `min_size` world units stay visible.
+fn fit_viewport<T: Game>(state: &mut AppState<T>, width: i32, height: i32) {
+    state.camera.set_viewport_size(width as f32, height as f32);
+    if let Some((min_width, min_height)) = state.min_size {
+        let scale = (width as f32 / min_width as f32)
+            .min(height as f32 / min_height as f32)
+            .min(1.0);
+        state.camera.set_zoom(scale);
+    }
+}
+
 fn process_input_events<T: Game>(state: &mut AppState<T>, event: &sapp::Event) {
+    // Live input is ignored while a replay drives the input manager
+    if state.playback.is_some() {
+        return;
+    }
+
+    if let Some(recorder) = &mut state.recording {
+        recorder.record_event(event);
+    }
+
     match event._type {
         sapp::EventType::KeyDown => state.input.handle_key_down(event.key_code),
         sapp::EventType::KeyUp => state.input.handle_key_up(event.key_code),
@@ -244,11 +413,225 @@ fn process_input_events<T: Game>(state: &mut AppState<T>, event: &sapp::Event) {
         sapp::EventType::MouseDown => state.input.handle_mouse_button_down(event.mouse_button),
         sapp::EventType::MouseUp => state.input.handle_mouse_button_up(event.mouse_button),
         sapp::EventType::MouseScroll => state.input.handle_mouse_wheel(event.scroll_y),
-        sapp::EventType::Resized => {
-            state
-                .camera
-                .set_viewport_size(event.window_width as f32, event.window_height as f32);
-        }
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::DrawSpace;
+    use glam::Vec2;
+
+    /// Game that records what the engine passes it, for driving `AppState` without a window
+    #[derive(Default)]
+    struct ProbeGame {
+        update_dts: Vec<f32>,
+        /// Whether Space was reported as pressed, per update
+        space_pressed: Vec<bool>,
+        /// Request a quit during this update, counting from 1
+        quit_on_update: Option<usize>,
+        /// Game callbacks in the order the engine made them
+        calls: Vec<&'static str>,
+        /// Events passed to `handle_event`
+        events: Vec<sapp::EventType>,
+    }
+
+    impl Game for ProbeGame {
+        fn config() -> GameConfig {
+            GameConfig::new()
+        }
+
+        fn init(&mut self, _config: &GameConfig, _services: &mut EngineServices) {}
+
+        fn update(&mut self, dt: f32, input: &InputManager, services: &mut EngineServices) {
+            self.update_dts.push(dt);
+            self.space_pressed
+                .push(input.is_key_pressed(sapp::Keycode::Space));
+            self.calls.push("update");
+            let total = services.blackboard.get_int("total_updates").unwrap_or(0);
+            services.set_blackboard("total_updates", total + 1);
+            if self.quit_on_update == Some(self.update_dts.len()) {
+                services.request_quit();
+            }
+        }
+
+        fn render(&mut self, _services: &mut EngineServices) {
+            self.calls.push("render");
+        }
+
+        fn render_overlay(&mut self, services: &mut EngineServices) {
+            assert_eq!(services.renderer.draw_space(), DrawSpace::Screen);
+            self.calls.push("render_overlay");
+        }
+
+        fn handle_event(&mut self, event: &sapp::Event) {
+            self.events.push(event._type);
+        }
+    }
+
+    fn app_state(config: &GameConfig) -> AppState<ProbeGame> {
+        AppState::new(ProbeGame::default(), config, None, None)
+    }
+
+    fn resized(width: i32, height: i32) -> sapp::Event {
+        sapp::Event {
+            _type: sapp::EventType::Resized,
+            window_width: width,
+            window_height: height,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fixed_size_games_follow_the_framebuffer_but_miss_resize_events() {
+        let mut state = app_state(&GameConfig::new().with_resizable(false));
+        assert!(!handle_resize(&mut state, &resized(1024, 768)));
+        assert_eq!(state.camera.viewport_size(), Vec2::new(1024.0, 768.0));
+    }
+
+    #[test]
+    fn min_size_zooms_out_instead_of_growing_the_viewport() {
+        let mut state = app_state(&GameConfig::new().with_min_size(640, 480));
+        assert!(handle_resize(&mut state, &resized(320, 900)));
+        assert_eq!(state.camera.viewport_size(), Vec2::new(320.0, 900.0));
+        assert_eq!(state.camera.get_zoom(), 0.5);
+        assert_eq!(
+            state.camera.view_half_extents() * 2.0,
+            Vec2::new(640.0, 1800.0)
+        );
+
+        // Large enough windows draw at normal scale
+        assert!(handle_resize(&mut state, &resized(1280, 960)));
+        assert_eq!(state.camera.viewport_size(), Vec2::new(1280.0, 960.0));
+        assert_eq!(state.camera.get_zoom(), 1.0);
+    }
+
+    #[test]
+    fn scaled_dt_reaches_the_game_update() {
+        let mut state = app_state(&GameConfig::new());
+        state.time_scale = 0.5;
+        simulate_frame(&mut state, 0.1, true);
+
+        // Frame stepping without a requested step skips the update
+        simulate_frame(&mut state, 0.1, false);
+
+        state.time_scale = 0.0;
+        simulate_frame(&mut state, 0.1, true);
+        assert_eq!(state.game.update_dts, vec![0.05, 0.0]);
+    }
+
+    fn key_down(key: sapp::Keycode) -> sapp::Event {
+        sapp::Event {
+            _type: sapp::EventType::KeyDown,
+            key_code: key,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn presses_between_frame_steps_reach_the_next_stepped_update() {
+        let mut state = app_state(&GameConfig::new());
+        simulate_frame(&mut state, 0.1, true);
+
+        // Pressed while frame stepping holds the simulation
+        process_input_events(&mut state, &key_down(sapp::Keycode::Space));
+        simulate_frame(&mut state, 0.1, false);
+        simulate_frame(&mut state, 0.1, false);
+
+        simulate_frame(&mut state, 0.1, true);
+        simulate_frame(&mut state, 0.1, true);
+        assert_eq!(state.game.space_pressed, vec![false, true, false]);
+    }
+
+    #[test]
+    fn live_events_during_playback_dont_reach_the_game() {
+        let config = GameConfig::new();
+        let mut live = AppState::new(ProbeGame::default(), &config, Some("unused.json"), None);
+        dispatch_event(&mut live, &key_down(sapp::Keycode::Space));
+        simulate_frame(&mut live, 0.1, true);
+        simulate_frame(&mut live, 0.1, true);
+        assert_eq!(live.game.events.len(), 1);
+
+        let replay = live.recording.as_ref().unwrap().replay().clone();
+        let mut replayed = AppState::new(ProbeGame::default(), &config, None, Some(replay));
+        let unfocused = sapp::Event {
+            _type: sapp::EventType::Unfocused,
+            ..Default::default()
+        };
+        dispatch_event(&mut replayed, &unfocused);
+        dispatch_event(&mut replayed, &key_down(sapp::Keycode::Escape));
+        simulate_frame(&mut replayed, 0.1, true);
+        simulate_frame(&mut replayed, 0.1, true);
+
+        assert!(replayed.game.events.is_empty());
+        assert_eq!(replayed.game.space_pressed, live.game.space_pressed);
+        assert_eq!(replayed.input.snapshot(), live.input.snapshot());
+    }
+
+    #[test]
+    fn quit_requested_during_update_is_forwarded_once() {
+        let mut state = app_state(&GameConfig::new());
+        state.game.quit_on_update = Some(2);
+
+        simulate_frame(&mut state, 0.1, true);
+        assert!(!state.take_quit_request());
+
+        simulate_frame(&mut state, 0.1, true);
+        assert!(state.take_quit_request());
+        // Forwarded once, not again on later frames
+        assert!(!state.take_quit_request());
+    }
+
+    #[test]
+    fn blackboard_values_survive_a_game_reset() {
+        let mut state = app_state(&GameConfig::new());
+        simulate_frame(&mut state, 0.1, true);
+        simulate_frame(&mut state, 0.1, true);
+
+        // A reset replaces the game, the engine keeps its blackboard
+        state.game = ProbeGame::default();
+        simulate_frame(&mut state, 0.1, true);
+        assert_eq!(state.game.update_dts.len(), 1);
+        assert_eq!(state.blackboard.get_int("total_updates"), Some(3));
+    }
+
+    #[test]
+    fn pass_action_clears_to_the_configured_background() {
+        let background = sg::Color {
+            r: 0.1,
+            g: 0.2,
+            b: 0.3,
+            a: 1.0,
+        };
+        let mut state = app_state(&GameConfig::new().with_background(background));
+        let clear_color = |state: &AppState<ProbeGame>| {
+            let color = state.pass_action.colors[0].clear_value;
+            [color.r, color.g, color.b, color.a]
+        };
+        assert_eq!(clear_color(&state), [0.1, 0.2, 0.3, 1.0]);
+
+        // Kept across frames unless the game asks for a change
+        simulate_frame(&mut state, 0.1, true);
+        assert_eq!(clear_color(&state), [0.1, 0.2, 0.3, 1.0]);
+    }
+
+    #[test]
+    fn render_overlay_runs_after_render_every_frame() {
+        let mut state = app_state(&GameConfig::new());
+        simulate_frame(&mut state, 0.1, true);
+        // Paused frames still render
+        simulate_frame(&mut state, 0.1, false);
+
+        assert_eq!(
+            state.game.calls,
+            [
+                "update",
+                "render",
+                "render_overlay",
+                "render",
+                "render_overlay"
+            ]
+        );
+    }
+}
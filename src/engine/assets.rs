@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// List of assets to load, read from a JSON file such as
+/// `{ "textures": { "player": "player.png", "font": "font.png" } }`.
+/// Relative paths are resolved against the manifest's own directory.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssetManifest {
+    /// Texture name -> image path
+    #[serde(default)]
+    pub textures: BTreeMap<String, String>,
+    #[serde(skip)]
+    base_dir: PathBuf,
+}
+
+impl AssetManifest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a manifest from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+        let mut manifest: Self = serde_json::from_str(&contents)?;
+        manifest.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(manifest)
+    }
+
+    pub fn with_texture(mut self, name: &str, path: &str) -> Self {
+        self.textures.insert(name.to_string(), path.to_string());
+        self
+    }
+
+    /// Directory that relative paths are resolved against
+    pub fn with_base_dir<P: AsRef<Path>>(mut self, base_dir: P) -> Self {
+        self.base_dir = base_dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Full path of an entry, resolving relative paths against the manifest directory
+    pub fn resolve_path(&self, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.base_dir.join(path)
+        }
+    }
+
+    pub fn asset_count(&self) -> usize {
+        self.textures.len()
+    }
+}
+
+/// Outcome of loading every asset in a manifest
+#[derive(Clone, Debug, Default)]
+pub struct AssetLoadReport {
+    pub loaded: Vec<String>,
+    /// (asset name, error message)
+    pub failed: Vec<(String, String)>,
+}
+
+impl AssetLoadReport {
+    pub fn total(&self) -> usize {
+        self.loaded.len() + self.failed.len()
+    }
+
+    pub fn all_loaded(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
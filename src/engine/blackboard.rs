@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+/// A value stored on the blackboard
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlackboardValue {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Text(String),
+}
+
+impl From<bool> for BlackboardValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for BlackboardValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<i32> for BlackboardValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value as i64)
+    }
+}
+
+impl From<f32> for BlackboardValue {
+    fn from(value: f32) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for BlackboardValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for BlackboardValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+/// Key/value store owned by the engine that outlives game resets and state changes,
+/// e.g. for best times or total score across runs of a level
+#[derive(Clone, Debug, Default)]
+pub struct Blackboard {
+    values: HashMap<String, BlackboardValue>,
+}
+
+impl Blackboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set<V: Into<BlackboardValue>>(&mut self, key: &str, value: V) {
+        self.values.insert(key.to_string(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&BlackboardValue> {
+        self.values.get(key)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.values.get(key)? {
+            BlackboardValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        match self.values.get(key)? {
+            BlackboardValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Float value, ints are converted
+    pub fn get_float(&self, key: &str) -> Option<f32> {
+        match self.values.get(key)? {
+            BlackboardValue::Float(value) => Some(*value),
+            BlackboardValue::Int(value) => Some(*value as f32),
+            _ => None,
+        }
+    }
+
+    pub fn get_text(&self, key: &str) -> Option<&str> {
+        match self.values.get(key)? {
+            BlackboardValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<BlackboardValue> {
+        self.values.remove(key)
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
@@ -1,6 +1,24 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
-
 use glam::{Mat4, Vec2};
+use rand::Rng;
+
+/// Largest zoom change a single wheel notch can apply (50%)
+const MAX_ZOOM_STEP_PER_NOTCH: f32 = 0.5;
+
+/// New random shake directions per second, interpolated between so shake looks
+/// the same at any frame rate
+const SHAKE_FREQUENCY: f32 = 30.0;
+/// Trauma lost per second
+const TRAUMA_DECAY: f32 = 1.0;
+
+/// Which way +Y points on screen for world-space drawing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CoordinateSpace {
+    /// +Y is up, like math and physics (the default)
+    #[default]
+    YUp,
+    /// +Y is down, like screen pixels and most tile editors
+    YDown,
+}
 
 pub struct Camera2D {
     pub position: Vec2, // World position the camera is looking at
@@ -11,9 +29,20 @@ pub struct Camera2D {
     shake_intensity: f32,
     shake_duration: f32,
     shake_timer: f32,
+    /// 0..=1, shake offset grows with its square
+    trauma: f32,
+    max_trauma_offset: f32,
+    /// Time since the last shake sample, in 1 / SHAKE_FREQUENCY units
+    shake_phase: f32,
+    /// Samples the offset is interpolated between, each within the unit square
+    shake_from: Vec2,
+    shake_to: Vec2,
 
     view_projection: Mat4,
 
+    pixel_snap: bool,
+    coordinate_space: CoordinateSpace,
+
     // Internal state
     transform_dirty: bool,
     viewport_width: f32,
@@ -31,42 +60,56 @@ impl Camera2D {
             shake_intensity: 0.0,
             shake_duration: 0.0,
             shake_timer: 0.0,
+            trauma: 0.0,
+            max_trauma_offset: 20.0,
+            shake_phase: 0.0,
+            shake_from: Vec2::ZERO,
+            shake_to: Vec2::ZERO,
             view_projection: Mat4::IDENTITY,
+            pixel_snap: false,
+            coordinate_space: CoordinateSpace::YUp,
             transform_dirty: true,
             viewport_width: 800.0, // Default size
             viewport_height: 600.0,
         }
     }
 
-    // Engine calls this each frame
-    pub fn update_shake(&mut self, dt: f32) {
-        if self.shake_timer > 0.0 {
-            self.shake_timer -= dt;
-
-            // Calculate shake strength (fades out over time)
-            let shake_strength = (self.shake_timer / self.shake_duration) * self.shake_intensity;
+    /// Engine calls this each frame with its seeded RNG, so replays shake the same way
+    pub fn update_shake(&mut self, dt: f32, rng: &mut impl Rng) {
+        self.shake_timer = (self.shake_timer - dt).max(0.0);
+        self.trauma = (self.trauma - TRAUMA_DECAY * dt).max(0.0);
 
-            // Random shake offset
-            let mut hasher = DefaultHasher::new();
-            let shake_timer = (self.shake_timer as f32 * 1000.0) as u32;
-            shake_timer.hash(&mut hasher);
-            self.position.x.to_bits().hash(&mut hasher);
-            let hash = hasher.finish();
-
-            let random_angle = ((hash & 0xFFFF) as f32 / 65535.0) * 2.0 * std::f32::consts::PI;
-            self.shake_offset = Vec2::new(
-                random_angle.cos() * shake_strength,
-                random_angle.sin() * shake_strength,
-            );
-
-            self.transform_dirty = true; // Need to recalculate matrix
+        let shake_strength = if self.shake_duration > 0.0 {
+            (self.shake_timer / self.shake_duration) * self.shake_intensity
         } else {
+            0.0
+        };
+        let strength = shake_strength + self.trauma * self.trauma * self.max_trauma_offset;
+
+        if strength <= 0.0 {
             // No more shake
             if self.shake_offset != Vec2::ZERO {
                 self.shake_offset = Vec2::ZERO;
                 self.transform_dirty = true;
             }
+            return;
+        }
+
+        // Advance through fixed-rate random samples, however many dt covers
+        self.shake_phase += dt * SHAKE_FREQUENCY;
+        if self.shake_phase >= 1.0 {
+            let steps = self.shake_phase.floor();
+            self.shake_phase -= steps;
+            self.shake_from = if steps > 1.0 {
+                Vec2::new(rng.random_range(-1.0..=1.0), rng.random_range(-1.0..=1.0))
+            } else {
+                self.shake_to
+            };
+            self.shake_to = Vec2::new(rng.random_range(-1.0..=1.0), rng.random_range(-1.0..=1.0));
         }
+
+        self.shake_offset = self.shake_from.lerp(self.shake_to, self.shake_phase) * strength;
+        self.transform_dirty = true; // Need to recalculate matrix
     }
 
     // Engine calls this when window size changes
@@ -92,17 +135,26 @@ impl Camera2D {
         let half_width = self.viewport_width * 0.5 / self.zoom;
         let half_height = self.viewport_height * 0.5 / self.zoom;
 
+        // Bottom and top swap in YDown so +Y world maps to the bottom of the screen
+        let (bottom, top) = match self.coordinate_space {
+            CoordinateSpace::YUp => (-half_height, half_height),
+            CoordinateSpace::YDown => (half_height, -half_height),
+        };
         let projection = Mat4::orthographic_rh(
             -half_width,
             half_width, // left, right
-            -half_height,
-            half_height, // bottom, top (flipped for screen space)
+            bottom,
+            top,
             -1.0,
             1.0, // near, far
         );
 
         // Create view matrix (camera transform)
-        let effective_position = self.position + self.shake_offset; // ADD shake offset
+        let mut effective_position = self.position + self.shake_offset; // ADD shake offset
+        if self.pixel_snap {
+            // Round to whole screen pixels so textures don't shimmer at sub-pixel offsets
+            effective_position = (effective_position * self.zoom).round() / self.zoom;
+        }
         let translation = Mat4::from_translation(glam::Vec3::new(
             -effective_position.x,
             -effective_position.y,
@@ -124,28 +176,62 @@ impl Camera2D {
             self.update_matrices();
         }
 
+        let inverse_vp = self.view_projection.inverse();
+        self.screen_to_world_with(&inverse_vp, screen_pos)
+    }
+
+    pub fn world_to_screen(&mut self, world_pos: Vec2) -> Vec2 {
+        if self.transform_dirty {
+            self.update_matrices();
+        }
+
+        self.world_to_screen_with(&self.view_projection, world_pos)
+    }
+
+    /// Convert many screen points at once, inverting the matrix only once
+    pub fn screen_to_world_batch(&mut self, screen_points: &[Vec2]) -> Vec<Vec2> {
+        if self.transform_dirty {
+            self.update_matrices();
+        }
+
+        let inverse_vp = self.view_projection.inverse();
+        screen_points
+            .iter()
+            .map(|&p| self.screen_to_world_with(&inverse_vp, p))
+            .collect()
+    }
+
+    /// Convert many world points at once
+    pub fn world_to_screen_batch(&mut self, world_points: &[Vec2]) -> Vec<Vec2> {
+        if self.transform_dirty {
+            self.update_matrices();
+        }
+
+        world_points
+            .iter()
+            .map(|&p| self.world_to_screen_with(&self.view_projection, p))
+            .collect()
+    }
+
+    fn screen_to_world_with(&self, inverse_vp: &Mat4, screen_pos: Vec2) -> Vec2 {
         // Convert screen coordinates to normalized device coordinates (-1 to 1)
         let ndc_x = (screen_pos.x / self.viewport_width) * 2.0 - 1.0;
         let ndc_y = -((screen_pos.y / self.viewport_height) * 2.0 - 1.0); // ADD negative sign here
 
         // Transform by inverse view-projection matrix
-        let inverse_vp = self.view_projection.inverse();
-        let world_pos_4d = inverse_vp * glam::Vec4::new(ndc_x, ndc_y, 0.0, 1.0);
+        let world_pos_4d = *inverse_vp * glam::Vec4::new(ndc_x, ndc_y, 0.0, 1.0);
 
         Vec2::new(world_pos_4d.x, world_pos_4d.y)
     }
 
-    pub fn world_to_screen(&mut self, world_pos: Vec2) -> Vec2 {
-        if self.transform_dirty {
-            self.update_matrices();
-        }
-
+    fn world_to_screen_with(&self, view_projection: &Mat4, world_pos: Vec2) -> Vec2 {
         // Transform world position by view-projection matrix
-        let clip_pos = self.view_projection * glam::Vec4::new(world_pos.x, world_pos.y, 0.0, 1.0);
+        let clip_pos = *view_projection * glam::Vec4::new(world_pos.x, world_pos.y, 0.0, 1.0);
 
-        // Convert from normalized device coordinates to screen coordinates
+        // Convert from normalized device coordinates to screen coordinates.
+        // Screen y grows downward, mirroring the ndc_y flip in screen_to_world_with.
         let screen_x = (clip_pos.x + 1.0) * 0.5 * self.viewport_width;
-        let screen_y = (clip_pos.y + 1.0) * 0.5 * self.viewport_height;
+        let screen_y = (1.0 - clip_pos.y) * 0.5 * self.viewport_height;
 
         Vec2::new(screen_x, screen_y)
     }
@@ -165,6 +251,54 @@ impl Camera2D {
         }
     }
 
+    /// Change zoom while keeping the world point under `screen_pos` fixed
+    pub fn zoom_to_point(&mut self, zoom: f32, screen_pos: Vec2) {
+        let anchor = self.screen_to_world(screen_pos);
+        self.set_zoom(zoom);
+        let moved = self.screen_to_world(screen_pos);
+        self.move_by(anchor - moved);
+    }
+
+    /// Cursor-anchored wheel zoom, each notch scales zoom by `1 + zoom_speed`
+    pub fn handle_zoom_input(
+        &mut self,
+        wheel_delta: f32,
+        zoom_speed: f32,
+        cursor_screen_pos: Vec2,
+    ) {
+        if wheel_delta == 0.0 {
+            return;
+        }
+        let step = zoom_speed.clamp(0.0, MAX_ZOOM_STEP_PER_NOTCH);
+        let factor = (1.0 + step).powf(wheel_delta);
+        self.zoom_to_point(self.zoom * factor, cursor_screen_pos);
+    }
+
+    /// Snap the view to whole screen pixels, for crisp pixel art with a moving camera
+    pub fn set_pixel_snap(&mut self, enabled: bool) {
+        if self.pixel_snap != enabled {
+            self.pixel_snap = enabled;
+            self.transform_dirty = true;
+        }
+    }
+
+    pub fn is_pixel_snap(&self) -> bool {
+        self.pixel_snap
+    }
+
+    /// Direction of world +Y on screen. `screen_to_world`, `world_to_screen` and world-space
+    /// text all follow it, so games don't need their own flips.
+    pub fn set_coordinate_space(&mut self, space: CoordinateSpace) {
+        if self.coordinate_space != space {
+            self.coordinate_space = space;
+            self.transform_dirty = true;
+        }
+    }
+
+    pub fn coordinate_space(&self) -> CoordinateSpace {
+        self.coordinate_space
+    }
+
     pub fn set_rotation(&mut self, rotation: f32) {
         if self.rotation != rotation {
             self.rotation = rotation;
@@ -179,6 +313,41 @@ impl Camera2D {
         self.shake_timer = duration;
     }
 
+    /// Add trauma (clamped to 0..=1) that decays over time. Shake grows with trauma
+    /// squared, so small hits barely shake and big ones stack up.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Offset in world units at full trauma, 20 by default
+    pub fn set_max_trauma_offset(&mut self, offset: f32) {
+        self.max_trauma_offset = offset.max(0.0);
+    }
+
+    /// Current shake offset applied to the view
+    pub fn shake_offset(&self) -> Vec2 {
+        self.shake_offset
+    }
+
+    /// Exponentially ease toward a point ahead of a moving target.
+    /// Aims at `target_pos + target_velocity * lookahead_factor`, higher `smoothing` follows tighter.
+    pub fn follow_with_lookahead(
+        &mut self,
+        target_pos: Vec2,
+        target_velocity: Vec2,
+        lookahead_factor: f32,
+        smoothing: f32,
+        dt: f32,
+    ) {
+        let aim = target_pos + target_velocity * lookahead_factor;
+        let t = 1.0 - (-smoothing.max(0.0) * dt).exp();
+        self.set_position(self.position.lerp(aim, t));
+    }
+
     // Camera movement methods
     pub fn move_by(&mut self, delta: Vec2) {
         self.set_position(self.position + delta);
@@ -205,6 +374,11 @@ impl Camera2D {
         self.rotation
     }
 
+    /// Viewport size in pixels
+    pub fn viewport_size(&self) -> Vec2 {
+        Vec2::new(self.viewport_width, self.viewport_height)
+    }
+
     pub fn view_half_extents(&self) -> Vec2 {
         Vec2::new(
             self.viewport_width * 0.5 / self.zoom,
@@ -242,4 +416,182 @@ impl Camera2D {
         let half = self.view_half_extents();
         (self.position - half, self.position + half)
     }
+
+    /// Center on the bounding box of `points` and zoom so it fits the viewport
+    pub fn frame_points(&mut self, points: &[Vec2], padding: f32) {
+        let Some(&first) = points.first() else {
+            return;
+        };
+
+        let (min, max) = points
+            .iter()
+            .fold((first, first), |(min, max), &p| (min.min(p), max.max(p)));
+
+        let size = (max - min + Vec2::splat(padding * 2.0)).max(Vec2::splat(f32::EPSILON));
+        let zoom = (self.viewport_width / size.x).min(self.viewport_height / size.y);
+
+        self.set_position((min + max) * 0.5);
+        self.set_zoom(zoom);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::GameRng;
+
+    #[test]
+    fn shake_decays_to_zero_over_its_duration_at_any_step_size() {
+        let duration = 0.5;
+        for dt in [1.0 / 240.0, 1.0 / 60.0, 1.0 / 13.0, 0.2] {
+            let mut camera = Camera2D::new();
+            let mut rng = GameRng::new(7);
+            camera.add_shake(10.0, duration);
+
+            let steps = (duration / dt).ceil() as usize;
+            let mut peak = 0.0f32;
+            for _ in 0..steps {
+                camera.update_shake(dt, &mut rng);
+                peak = peak.max(camera.shake_offset().length());
+            }
+            assert!(peak > 0.0, "no shake at dt {dt}");
+            assert!(peak <= 10.0 * 2.0f32.sqrt(), "peak {peak} at dt {dt}");
+
+            // One more step covers float error in the accumulated timer
+            camera.update_shake(dt, &mut rng);
+            assert_eq!(camera.shake_offset(), Vec2::ZERO, "dt {dt}");
+        }
+    }
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(
+            (actual - expected).length() < 1e-3,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn frame_points_zooms_out_for_far_apart_points() {
+        let mut camera = Camera2D::new();
+        camera.frame_points(&[Vec2::new(90.0, 0.0), Vec2::new(110.0, 10.0)], 10.0);
+        let near_zoom = camera.zoom;
+        assert_near(camera.position, Vec2::new(100.0, 5.0));
+
+        camera.frame_points(&[Vec2::new(-1000.0, 0.0), Vec2::new(1000.0, 0.0)], 0.0);
+        assert!(camera.zoom < near_zoom);
+        // 2000 units wide in an 800 pixel viewport
+        assert!((camera.zoom - 0.4).abs() < 1e-6);
+        assert_near(camera.position, Vec2::ZERO);
+    }
+
+    #[test]
+    fn wheel_zoom_keeps_the_point_under_the_cursor() {
+        let mut camera = Camera2D::new();
+        camera.set_position(Vec2::new(30.0, -20.0));
+        let cursor = Vec2::new(600.0, 150.0);
+        let anchor = camera.screen_to_world(cursor);
+
+        camera.handle_zoom_input(1.0, 0.25, cursor);
+        assert!((camera.zoom - 1.25).abs() < 1e-6);
+        assert_near(camera.screen_to_world(cursor), anchor);
+
+        // A single notch never zooms more than the per-notch limit
+        camera.handle_zoom_input(1.0, 5.0, cursor);
+        assert!((camera.zoom - 1.25 * 1.5).abs() < 1e-5);
+        assert_near(camera.screen_to_world(cursor), anchor);
+    }
+
+    #[test]
+    fn batch_conversions_match_single_point_calls() {
+        let mut camera = Camera2D::new();
+        camera.set_position(Vec2::new(120.0, -45.0));
+        camera.set_zoom(1.7);
+        camera.set_rotation(0.3);
+        let points = [
+            Vec2::ZERO,
+            Vec2::new(400.0, 300.0),
+            Vec2::new(-33.5, 812.25),
+        ];
+
+        let screen = camera.world_to_screen_batch(&points);
+        let world = camera.screen_to_world_batch(&points);
+        for (i, &point) in points.iter().enumerate() {
+            assert_eq!(screen[i], camera.world_to_screen(point));
+            assert_eq!(world[i], camera.screen_to_world(point));
+        }
+    }
+
+    #[test]
+    fn world_screen_round_trip_under_rotation_zoom_and_position() {
+        let points = [
+            Vec2::ZERO,
+            Vec2::new(250.0, -80.0),
+            Vec2::new(-640.5, 480.0),
+        ];
+        for (position, zoom, rotation) in [
+            (Vec2::ZERO, 1.0, 0.0),
+            (Vec2::new(300.0, -150.0), 0.5, 0.7),
+            (Vec2::new(-42.0, 17.5), 3.0, -2.4),
+        ] {
+            let mut camera = Camera2D::new();
+            camera.set_position(position);
+            camera.set_zoom(zoom);
+            camera.set_rotation(rotation);
+            for &point in &points {
+                let screen = camera.world_to_screen(point);
+                assert_near(camera.screen_to_world(screen), point);
+            }
+            // The camera position is always the viewport center
+            assert_near(camera.world_to_screen(position), Vec2::new(400.0, 300.0));
+        }
+    }
+
+    #[test]
+    fn lookahead_aims_ahead_of_a_moving_target() {
+        let target = Vec2::new(100.0, 50.0);
+        let mut still = Camera2D::new();
+        still.set_position(target);
+        still.follow_with_lookahead(target, Vec2::ZERO, 0.5, 10.0, 1.0 / 60.0);
+        assert_near(still.position, target);
+
+        let mut leading = Camera2D::new();
+        leading.set_position(target);
+        let velocity = Vec2::new(200.0, 0.0);
+        for _ in 0..120 {
+            leading.follow_with_lookahead(target, velocity, 0.5, 10.0, 1.0 / 60.0);
+        }
+        // Settles on target + velocity * lookahead_factor, ahead in the direction of travel
+        assert_near(leading.position, Vec2::new(200.0, 50.0));
+    }
+
+    #[test]
+    fn pixel_snap_keeps_the_view_translation_on_whole_pixels() {
+        let mut camera = Camera2D::new();
+        camera.set_position(Vec2::new(10.3, -4.6));
+        camera.set_zoom(2.0);
+        let half_viewport = camera.viewport_size() * 0.5;
+        let pixel_translation = |camera: &mut Camera2D| {
+            let matrix = camera.get_view_projection_matrix();
+            Vec2::new(matrix.w_axis.x, matrix.w_axis.y) * half_viewport
+        };
+        let is_integral = |v: Vec2| (v - v.round()).abs().max_element() < 1e-3;
+
+        assert!(!is_integral(pixel_translation(&mut camera)));
+        camera.set_pixel_snap(true);
+        assert!(is_integral(pixel_translation(&mut camera)));
+    }
+
+    #[test]
+    fn y_up_puts_points_above_the_camera_in_the_top_half() {
+        let mut camera = Camera2D::new();
+        camera.set_position(Vec2::new(50.0, 50.0));
+        let above = Vec2::new(50.0, 150.0);
+
+        let screen = camera.world_to_screen(above);
+        assert!(screen.y < 300.0, "{screen:?}");
+        assert_near(screen, Vec2::new(400.0, 200.0));
+
+        camera.set_coordinate_space(CoordinateSpace::YDown);
+        assert_near(camera.world_to_screen(above), Vec2::new(400.0, 400.0));
+    }
 }
@@ -45,6 +45,17 @@ impl Collider {
             is_trigger: false,
         }
     }
+
+    /// Whether `point` is inside the shape, edges included
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        let offset = point - self.position;
+        match self.shape {
+            CollisionShape::Rectangle { width, height } => {
+                offset.x.abs() <= width * 0.5 && offset.y.abs() <= height * 0.5
+            }
+            CollisionShape::Circle { radius } => offset.length_squared() <= radius * radius,
+        }
+    }
 }
 
 pub fn check_collision(a: &Collider, b: &Collider) -> bool {
@@ -89,6 +100,21 @@ pub fn check_collision_with_point(a: &Collider, b: &Collider) -> CollisionResult
     }
 }
 
+/// Contact points between two colliders.
+/// Rectangle pairs give the two ends of the overlapping edge, other pairs a single point.
+pub fn contact_manifold(a: &Collider, b: &Collider) -> Vec<Vec2> {
+    match (&a.shape, &b.shape) {
+        (CollisionShape::Rectangle { width: w1, height: h1 },
+         CollisionShape::Rectangle { width: w2, height: h2 }) => {
+            aabb_vs_aabb_manifold(a.position, *w1, *h1, b.position, *w2, *h2)
+        },
+        _ => {
+            let result = check_collision_with_point(a, b);
+            if result.collided { vec![result.contact_point] } else { Vec::new() }
+        },
+    }
+}
+
 fn aabb_vs_aabb(pos1: Vec2, w1: f32, h1: f32, pos2: Vec2, w2: f32, h2: f32) -> bool {
     // Convert from center position to min/max bounds
     let min1 = Vec2::new(pos1.x - w1 / 2.0, pos1.y - h1 / 2.0);
@@ -145,15 +171,43 @@ fn aabb_vs_aabb_with_point(pos1: Vec2, w1: f32, h1: f32, pos2: Vec2, w2: f32, h2
     }
 }
 
+fn aabb_vs_aabb_manifold(pos1: Vec2, w1: f32, h1: f32, pos2: Vec2, w2: f32, h2: f32) -> Vec<Vec2> {
+    let min1 = Vec2::new(pos1.x - w1 / 2.0, pos1.y - h1 / 2.0);
+    let max1 = Vec2::new(pos1.x + w1 / 2.0, pos1.y + h1 / 2.0);
+    let min2 = Vec2::new(pos2.x - w2 / 2.0, pos2.y - h2 / 2.0);
+    let max2 = Vec2::new(pos2.x + w2 / 2.0, pos2.y + h2 / 2.0);
+
+    let left = min1.x.max(min2.x);
+    let right = max1.x.min(max2.x);
+    let bottom = min1.y.max(min2.y);
+    let top = max1.y.min(max2.y);
+
+    if left >= right || bottom >= top {
+        return Vec::new();
+    }
+
+    // The shallow axis is the collision normal, contacts lie along the other axis
+    if right - left < top - bottom {
+        let x = (left + right) * 0.5;
+        vec![Vec2::new(x, bottom), Vec2::new(x, top)]
+    } else {
+        let y = (bottom + top) * 0.5;
+        vec![Vec2::new(left, y), Vec2::new(right, y)]
+    }
+}
+
 fn circle_vs_circle_with_point(pos1: Vec2, r1: f32, pos2: Vec2, r2: f32) -> CollisionResult {
     let distance_sq = (pos1 - pos2).length_squared();
     let radius_sum = r1 + r2;
     let collided = distance_sq <= radius_sum * radius_sum;
     
     if collided {
-        // Contact point is along the line between centers
-        let direction = (pos2 - pos1).normalize();
-        let contact_point = pos1 + direction * r1;
+        // Contact point is the middle of the overlap along the line between centers.
+        // Concentric circles have no defined direction, so fall back to +Y.
+        let distance = distance_sq.sqrt();
+        let direction = (pos2 - pos1).try_normalize().unwrap_or(Vec2::Y);
+        let penetration = radius_sum - distance;
+        let contact_point = pos1 + direction * (r1 - penetration * 0.5);
         CollisionResult::hit(contact_point)
     } else {
         CollisionResult::none()
@@ -176,4 +230,33 @@ fn aabb_vs_circle_with_point(rect_pos: Vec2, width: f32, height: f32, circle_pos
     } else {
         CollisionResult::none()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deep_unequal_circle_contact_is_on_the_overlap_midline() {
+        let big = Collider::new_circle(0.0, 0.0, 10.0);
+        let small = Collider::new_circle(2.0, 0.0, 4.0);
+
+        let result = check_collision_with_point(&big, &small);
+        assert!(result.collided);
+        // Penetration 12, so half of it back from the big circle's edge
+        assert_eq!(result.contact_point, Vec2::new(4.0, 0.0));
+        assert!(big.contains_point(result.contact_point));
+        assert!(small.contains_point(result.contact_point));
+    }
+
+    #[test]
+    fn concentric_circle_contact_is_finite() {
+        let outer = Collider::new_circle(5.0, 5.0, 3.0);
+        let inner = Collider::new_circle(5.0, 5.0, 1.0);
+
+        let result = check_collision_with_point(&outer, &inner);
+        assert!(result.collided);
+        assert!(result.contact_point.is_finite());
+        assert_eq!(result.contact_point, Vec2::new(5.0, 6.0));
+    }
+}
@@ -1,14 +1,19 @@
 // src/engine/debug.rs
 
 use sokol::{app as sapp, debugtext as sdtx};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 use crate::engine::physics_world::PhysicsStats;
+use crate::engine::RenderStats;
 
 pub struct DebugFlags {
     pub debug_text: AtomicBool,
     pub collision: AtomicBool,
     pub show_debug_panel: AtomicBool,
+    pub frame_step: AtomicBool,
+    pub step_requested: AtomicBool,
+    pub step_count: AtomicU32,
+    pub particles_frozen: AtomicBool,
 }
 
 impl DebugFlags {
@@ -17,6 +22,10 @@ impl DebugFlags {
             debug_text: AtomicBool::new(false),
             collision: AtomicBool::new(false),
             show_debug_panel: AtomicBool::new(false),
+            frame_step: AtomicBool::new(false),
+            step_requested: AtomicBool::new(false),
+            step_count: AtomicU32::new(0),
+            particles_frozen: AtomicBool::new(false),
         }
     }
 
@@ -43,12 +52,61 @@ impl DebugFlags {
     pub fn is_debug_panel_visible(&self) -> bool {
         self.show_debug_panel.load(Ordering::Relaxed)
     }
+
+    /// Enabling frame stepping resets the step counter
+    pub fn set_frame_step(&self, enabled: bool) {
+        self.frame_step.store(enabled, Ordering::Relaxed);
+        self.step_requested.store(false, Ordering::Relaxed);
+        if enabled {
+            self.step_count.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_frame_step_enabled(&self) -> bool {
+        self.frame_step.load(Ordering::Relaxed)
+    }
+
+    /// Ask for a single update while frame stepping
+    pub fn request_step(&self) {
+        self.step_requested.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the game should update this frame.
+    /// Always true unless frame stepping is on, then consumes a pending step request.
+    pub fn should_advance_frame(&self) -> bool {
+        if !self.is_frame_step_enabled() {
+            return true;
+        }
+        if self.step_requested.swap(false, Ordering::Relaxed) {
+            self.step_count.fetch_add(1, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+
+    /// Number of single steps taken since frame stepping was enabled
+    pub fn step_count(&self) -> u32 {
+        self.step_count.load(Ordering::Relaxed)
+    }
+
+    /// Frozen particles keep rendering but stop simulating, independent of game pause
+    pub fn set_particles_frozen(&self, frozen: bool) {
+        self.particles_frozen.store(frozen, Ordering::Relaxed);
+    }
+
+    pub fn are_particles_frozen(&self) -> bool {
+        self.particles_frozen.load(Ordering::Relaxed)
+    }
 }
 
 static DEBUG_FLAGS: DebugFlags = DebugFlags {
     debug_text: AtomicBool::new(false),
     collision: AtomicBool::new(false),
     show_debug_panel: AtomicBool::new(false),
+    frame_step: AtomicBool::new(false),
+    step_requested: AtomicBool::new(false),
+    step_count: AtomicU32::new(0),
+    particles_frozen: AtomicBool::new(false),
 };
 
 pub fn debug_flags() -> &'static DebugFlags {
@@ -83,7 +141,11 @@ impl DebugOverlay {
         }
     }
 
-    pub fn render(&mut self, physics_stats: Option<&PhysicsStats>) {
+    pub fn render(
+        &mut self,
+        physics_stats: Option<&PhysicsStats>,
+        render_stats: Option<&RenderStats>,
+    ) {
         if !debug_flags().is_debug_panel_visible() {
             return;
         }
@@ -128,6 +190,19 @@ impl DebugOverlay {
                     "Kinetic Energy: {:.1}\n",
                     stats.total_kinetic_energy
                 ));
+                if stats.profiling_enabled {
+                    sdtx::puts(&format!("Integration: {}us\n", stats.integration_us));
+                    sdtx::puts(&format!("Detection: {}us\n", stats.detection_us));
+                    sdtx::puts(&format!("Resolution: {}us\n", stats.resolution_us));
+                }
+            }
+
+            if let Some(stats) = render_stats {
+                sdtx::puts("\n=== RENDER ===\n");
+                sdtx::puts(&format!("Draw Calls: {}\n", stats.draw_calls));
+                sdtx::puts(&format!("Batches: {}\n", stats.batches));
+                sdtx::puts(&format!("Vertices: {}\n", stats.vertices));
+                sdtx::puts(&format!("Indices: {}\n", stats.indices));
             }
 
             sdtx::puts("\n");
@@ -138,11 +213,23 @@ impl DebugOverlay {
             if debug_flags().is_collision_enabled() {
                 sdtx::puts("Collision Debug: ON\n");
             }
+            if debug_flags().are_particles_frozen() {
+                sdtx::puts("Particles: FROZEN\n");
+            }
+            if debug_flags().is_frame_step_enabled() {
+                sdtx::puts(&format!(
+                    "Frame Step: ON (step {})\n",
+                    debug_flags().step_count()
+                ));
+            }
 
             sdtx::puts("\nHotkeys:\n");
             sdtx::puts("F1: Toggle Debug Text\n");
             sdtx::puts("F2: Toggle Collision\n");
             sdtx::puts("F3: Toggle This Panel\n");
+            sdtx::puts("F4: Toggle Frame Step\n");
+            sdtx::puts(".: Step One Frame\n");
+            sdtx::puts("F5: Freeze Particles\n");
         }
 
         sdtx::draw();
@@ -183,6 +270,18 @@ pub fn toggle_debug_panel() {
     println!("Debug panel: {}", if !current { "ON" } else { "OFF" });
 }
 
+/// Toggle frame-step mode, where the game only updates when a step is requested
+pub fn toggle_frame_step() {
+    let current = DEBUG_FLAGS.is_frame_step_enabled();
+    DEBUG_FLAGS.set_frame_step(!current);
+    println!("Frame step: {}", if !current { "ON" } else { "OFF" });
+}
+
+/// Advance one frame while frame-step mode is on
+pub fn request_frame_step() {
+    DEBUG_FLAGS.request_step();
+}
+
 /// Set debug text flag
 pub fn set_debug_text(enabled: bool) {
     DEBUG_FLAGS.set_debug_text(enabled);
@@ -197,3 +296,20 @@ pub fn set_collision_debug(enabled: bool) {
 pub fn set_debug_panel_visible(enabled: bool) {
     DEBUG_FLAGS.set_show_debug_panel(enabled);
 }
+
+/// Set frame-step mode
+pub fn set_frame_step(enabled: bool) {
+    DEBUG_FLAGS.set_frame_step(enabled);
+}
+
+/// Toggle freezing every particle system
+pub fn toggle_particle_freeze() {
+    let current = DEBUG_FLAGS.are_particles_frozen();
+    DEBUG_FLAGS.set_particles_frozen(!current);
+    println!("Particle freeze: {}", if !current { "ON" } else { "OFF" });
+}
+
+/// Set the global particle freeze flag
+pub fn set_particles_frozen(frozen: bool) {
+    DEBUG_FLAGS.set_particles_frozen(frozen);
+}
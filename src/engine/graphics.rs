@@ -1,8 +1,11 @@
-use glam::{Vec2, Vec4};
+use glam::{Mat4, Vec2, Vec4};
 use sokol::gfx as sg;
 use std::{collections::HashMap, mem};
 
-use crate::engine::{AnimationState, Camera2D, Particle, TextureManager};
+use crate::engine::{
+    lighting::LightBuffer, AnimationState, AssetLoadReport, AssetManifest, Camera2D, Collider,
+    CollisionShape, CoordinateSpace, Light, Particle, ParticleRenderMode, TextureManager,
+};
 
 #[repr(C)]
 pub struct Vertex {
@@ -12,8 +15,10 @@ pub struct Vertex {
 }
 
 #[repr(C)]
-struct Uniforms {
-    mvp: [[f32; 4]; 4],
+pub(crate) struct Uniforms {
+    pub(crate) mvp: [[f32; 4]; 4],
+    /// Multiplies every vertex color (and the texel in additive shaders)
+    pub(crate) ambient: [f32; 4],
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -22,12 +27,90 @@ pub enum PrimitiveType {
     Lines,
 }
 
+/// Coordinate space that draws are submitted in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawSpace {
+    /// World units, transformed by the camera
+    World,
+    /// Screen pixels with the origin at the top-left, unaffected by the camera
+    Screen,
+}
+
+/// How a sprite's color is combined with its texture
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TintMode {
+    /// Texture color times `color`, the usual tint that can only darken
+    #[default]
+    Multiply,
+    /// Texture color plus `color.rgb`, for hit flashes and glows; alpha still multiplies
+    Additive,
+}
+
+/// Full-screen background drawn behind everything else in the frame
+#[derive(Clone, Debug)]
+pub enum BackgroundSpec {
+    Color(Vec4),
+    VerticalGradient {
+        top: Vec4,
+        bottom: Vec4,
+    },
+    /// Texture stretched over the viewport, multiplied by `tint`
+    Texture {
+        texture_name: String,
+        tint: Vec4,
+    },
+}
+
+/// Repeating background layer that scrolls slower (or faster) than the world
+#[derive(Clone, Debug)]
+pub struct ParallaxLayer {
+    pub texture_name: String,
+    /// How far the layer scrolls per unit of camera movement, 0 stays fixed, 1 moves with the world
+    pub scroll_factor: Vec2,
+    /// World size of one repeat of the texture
+    pub tile_size: Vec2,
+    pub tint: Vec4,
+}
+
+impl ParallaxLayer {
+    pub fn new(texture_name: &str, scroll_factor: Vec2, tile_size: Vec2) -> Self {
+        Self {
+            texture_name: texture_name.to_string(),
+            scroll_factor,
+            tile_size,
+            tint: Vec4::ONE,
+        }
+    }
+
+    pub fn with_tint(mut self, tint: Vec4) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    /// How far the layer has scrolled for a camera at `camera_position`
+    pub fn scroll_offset(&self, camera_position: Vec2) -> Vec2 {
+        camera_position * self.scroll_factor
+    }
+
+    /// UV rect (u, v, width, height) covering the view `(min, max)` for a camera at `camera_position`
+    pub fn uv_for_view(&self, camera_position: Vec2, min: Vec2, max: Vec2) -> Vec4 {
+        let tile_size = self.tile_size.max(Vec2::splat(f32::EPSILON));
+        // The quad follows the camera, so shift the texture back by the part it shouldn't follow
+        let origin = min - camera_position + self.scroll_offset(camera_position);
+        let uv_min = origin / tile_size;
+        let uv_size = (max - min) / tile_size;
+        Vec4::new(uv_min.x, uv_min.y, uv_size.x, uv_size.y)
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct Quad {
     pub position: Vec2,
     pub size: Vec2,
     pub color: Vec4,
     pub outline_only: bool,
+    /// Rotation in radians around the center
+    pub rotation: f32,
 }
 
 impl Quad {
@@ -37,6 +120,7 @@ impl Quad {
             size: Vec2::new(width, height),
             color,
             outline_only: false,
+            rotation: 0.0,
         }
     }
 
@@ -44,18 +128,74 @@ impl Quad {
         self.outline_only = true;
         self
     }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Corners in draw order (min x/min y, max x/min y, max x/max y, min x/max y before rotation)
+    pub fn corners(&self) -> [Vec2; 4] {
+        let half = self.size * 0.5;
+        let local = [
+            Vec2::new(-half.x, -half.y),
+            Vec2::new(half.x, -half.y),
+            Vec2::new(half.x, half.y),
+            Vec2::new(-half.x, half.y),
+        ];
+        let rotation = Vec2::from_angle(self.rotation);
+        local.map(|corner| self.position + rotation.rotate(corner))
+    }
+
+    /// World-space (min, max) bounds, covering all rotated corners
+    pub fn aabb(&self) -> (Vec2, Vec2) {
+        rotated_rect_aabb(self.position, self.size, self.rotation)
+    }
+
+    /// Set the alpha channel, keeping RGB
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.color.w = alpha;
+        self
+    }
+
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.color.w = alpha;
+    }
+
+    /// Left-aligned part of this quad covering `progress` (clamped to 0..=1) of its width
+    pub fn progress_fill(&self, progress: f32, color: Vec4) -> Quad {
+        let width = self.size.x * progress.clamp(0.0, 1.0);
+        Quad {
+            position: Vec2::new(
+                self.position.x - (self.size.x - width) * 0.5,
+                self.position.y,
+            ),
+            size: Vec2::new(width, self.size.y),
+            color,
+            outline_only: false,
+            rotation: 0.0,
+        }
+    }
 }
 
+const PIXELS_PER_CIRCLE_SEGMENT: f32 = 8.0;
+const MIN_CIRCLE_SEGMENTS: u32 = 3;
+const MAX_CIRCLE_SEGMENTS: u32 = 64;
+
 #[derive(Copy, Clone)]
 pub struct Circle {
     pub center: Vec2,
     pub radius: f32,
     pub color: Vec4,
     pub segments: u32, // Number of triangles to approximate the circle
+    /// Derive segments from the on-screen radius instead of `segments`
+    pub auto_segments: bool,
     pub outline_only: bool,
     pub show_line: bool,
     pub line_angle: f32,
     pub line_color: Vec4,
+    /// Fill with an anti-aliased distance-field edge instead of a triangle fan
+    pub smooth_edge: bool,
 }
 
 impl Circle {
@@ -65,10 +205,12 @@ impl Circle {
             radius,
             color,
             segments: 32, // Default to 32 segments for smooth appearance,
+            auto_segments: true,
             outline_only: false,
             show_line: false,
             line_angle: 0.0,
             line_color: color,
+            smooth_edge: false,
         }
     }
 
@@ -79,9 +221,26 @@ impl Circle {
 
     pub fn with_segments(mut self, segments: u32) -> Self {
         self.segments = segments.max(3); // Minimum 3 segments for a triangle
+        self.auto_segments = false;
+        self
+    }
+
+    /// Toggle deriving the segment count from radius and camera zoom
+    pub fn auto_segments(mut self, enabled: bool) -> Self {
+        self.auto_segments = enabled;
         self
     }
 
+    /// Segments used when drawn at `zoom`: about one per 8 on-screen pixels of circumference
+    pub fn segment_count(&self, zoom: f32) -> u32 {
+        if !self.auto_segments {
+            return self.segments;
+        }
+        let circumference = 2.0 * std::f32::consts::PI * self.radius * zoom;
+        ((circumference / PIXELS_PER_CIRCLE_SEGMENT).ceil() as u32)
+            .clamp(MIN_CIRCLE_SEGMENTS, MAX_CIRCLE_SEGMENTS)
+    }
+
     pub fn with_line(mut self, angle: f32) -> Self {
         self.show_line = true;
         self.line_angle = angle;
@@ -92,6 +251,44 @@ impl Circle {
         self.line_color = color;
         self
     }
+
+    /// Draw the fill as a quad shaded by a signed-distance shader, giving a smooth edge
+    /// at any size without MSAA or extra segments. Outlines still use line segments.
+    pub fn with_smooth_edge(mut self, smooth: bool) -> Self {
+        self.smooth_edge = smooth;
+        self
+    }
+
+    /// World-space (min, max) bounds
+    pub fn aabb(&self) -> (Vec2, Vec2) {
+        let extent = Vec2::splat(self.radius.abs());
+        (self.center - extent, self.center + extent)
+    }
+}
+
+/// Bounds of a `size` rectangle centered on `center` and rotated by `rotation` radians
+fn rotated_rect_aabb(center: Vec2, size: Vec2, rotation: f32) -> (Vec2, Vec2) {
+    let (sin, cos) = rotation.sin_cos();
+    let half = size.abs() * 0.5;
+    let extent = Vec2::new(
+        half.x * cos.abs() + half.y * sin.abs(),
+        half.x * sin.abs() + half.y * cos.abs(),
+    );
+    (center - extent, center + extent)
+}
+
+/// Rectangle collider matching the quad's position and size
+impl From<&Quad> for Collider {
+    fn from(quad: &Quad) -> Self {
+        Collider::new_rect(quad.position.x, quad.position.y, quad.size.x, quad.size.y)
+    }
+}
+
+/// Circle collider matching the circle's center and radius
+impl From<&Circle> for Collider {
+    fn from(circle: &Circle) -> Self {
+        Collider::new_circle(circle.center.x, circle.center.y, circle.radius)
+    }
 }
 
 #[derive(Clone)]
@@ -106,6 +303,11 @@ pub struct Sprite {
     pub animation_state: Option<AnimationState>,
     pub flip_x: bool,
     pub flip_y: bool,
+    /// Target alpha and speed (alpha per second) of an ongoing fade
+    pub fade_target: Option<(f32, f32)>,
+    /// How many times the texture repeats across the sprite, (1, 1) stretches it once
+    pub tiling: Vec2,
+    pub tint_mode: TintMode,
 }
 
 impl Sprite {
@@ -121,6 +323,9 @@ impl Sprite {
             animation_state: None,
             flip_x: false,
             flip_y: false,
+            fade_target: None,
+            tiling: Vec2::ONE,
+            tint_mode: TintMode::Multiply,
         }
     }
 
@@ -160,6 +365,47 @@ impl Sprite {
         self
     }
 
+    /// Repeat the texture `tiling.x` by `tiling.y` times instead of stretching it
+    pub fn with_tiling(mut self, tiling: Vec2) -> Self {
+        self.tiling = tiling;
+        self
+    }
+
+    /// Additive tints add `color.rgb` to the texture, so (0, 0, 0) leaves it unchanged
+    pub fn with_tint_mode(mut self, tint_mode: TintMode) -> Self {
+        self.tint_mode = tint_mode;
+        self
+    }
+
+    /// Largest size inside `max_size` with the aspect ratio of `source_size`, e.g. the
+    /// texture's pixel size from `Renderer::texture_size`
+    pub fn fit_to(&mut self, max_size: Vec2, source_size: Vec2) {
+        if source_size.x <= 0.0 || source_size.y <= 0.0 {
+            return;
+        }
+        let scale = (max_size.x / source_size.x).min(max_size.y / source_size.y);
+        self.size = source_size * scale.max(0.0);
+    }
+
+    /// World-space (min, max) bounds, covering all rotated corners
+    pub fn aabb(&self) -> (Vec2, Vec2) {
+        rotated_rect_aabb(self.position, self.size, self.rotation)
+    }
+
+    pub fn is_tiled(&self) -> bool {
+        self.tiling != Vec2::ONE
+    }
+
+    /// UV rectangle actually drawn, (u, v, width, height) scaled by the tiling
+    pub fn tiled_uv(&self) -> Vec4 {
+        Vec4::new(
+            self.uv.x,
+            self.uv.y,
+            self.uv.z * self.tiling.x,
+            self.uv.w * self.tiling.y,
+        )
+    }
+
     pub fn with_flip_x(mut self, flip: bool) -> Self {
         self.flip_x = flip;
         self
@@ -170,20 +416,128 @@ impl Sprite {
         self
     }
 
+    /// Set the alpha channel, keeping RGB
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.color.w = alpha;
+        self
+    }
+
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.color.w = alpha;
+    }
+
+    pub fn alpha(&self) -> f32 {
+        self.color.w
+    }
+
+    /// Move alpha toward `target_alpha` by `speed * dt`, returns true once reached
+    pub fn fade_to(&mut self, target_alpha: f32, dt: f32, speed: f32) -> bool {
+        let step = speed.abs() * dt;
+        let diff = target_alpha - self.color.w;
+        if diff.abs() <= step {
+            self.color.w = target_alpha;
+            true
+        } else {
+            self.color.w += step * diff.signum();
+            false
+        }
+    }
+
+    /// Start a fade that is advanced by `EngineServices::update_animations`
+    pub fn start_fade(&mut self, target_alpha: f32, speed: f32) {
+        self.fade_target = Some((target_alpha, speed));
+    }
+
+    pub fn is_fading(&self) -> bool {
+        self.fade_target.is_some()
+    }
+
+    /// Advance an ongoing fade, clearing it once the target is reached
+    pub fn update_fade(&mut self, dt: f32) {
+        if let Some((target, speed)) = self.fade_target {
+            if self.fade_to(target, dt, speed) {
+                self.fade_target = None;
+            }
+        }
+    }
+
+    /// Rectangle collider covering the rendered sprite (rotation is ignored)
+    pub fn collider(&self) -> Collider {
+        Collider::new_rect(self.position.x, self.position.y, self.size.x, self.size.y)
+    }
+
     pub fn change_texture(&mut self, texture_name: String) {
         self.texture_name = texture_name;
     }
 }
 
+/// Fragment shading for a batch beyond what its texture and tint mode select
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Material {
+    Default,
+    /// Quads shaded as anti-aliased circles inscribed in their UV square
+    SdfCircle,
+}
+
+/// Which pipeline a batch is drawn with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PipelineKind {
+    Lines,
+    SdfCircle,
+    Textured,
+    Additive,
+    Colored,
+}
+
 struct DrawBatch {
     texture: sg::Image,
     start_index: usize,
     index_count: usize,
     primitive_type: PrimitiveType,
+    sort_key: f32,
+    space: DrawSpace,
+    /// Sample with repeat wrapping, for tiled sprites
+    repeat: bool,
+    tint_mode: TintMode,
+    material: Material,
+}
+
+/// Anything that can draw itself, so mixed collections render with `Renderer::draw_all`
+pub trait Renderable {
+    fn draw(&self, renderer: &mut Renderer);
+}
+
+impl Renderable for Quad {
+    fn draw(&self, renderer: &mut Renderer) {
+        renderer.draw_quad(self);
+    }
+}
+
+impl Renderable for Circle {
+    fn draw(&self, renderer: &mut Renderer) {
+        renderer.draw_circle(self);
+    }
+}
+
+impl Renderable for Sprite {
+    fn draw(&self, renderer: &mut Renderer) {
+        renderer.draw_sprite(self);
+    }
+}
+
+/// Rendering statistics collected during the last flush
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderStats {
+    pub draw_calls: usize,
+    pub batches: usize,
+    pub vertices: usize,
+    pub indices: usize,
 }
 
 pub struct Renderer {
     textured_pipeline: sg::Pipeline,
+    additive_pipeline: sg::Pipeline,
+    sdf_circle_pipeline: sg::Pipeline,
     colored_pipeline: sg::Pipeline,
     line_pipeline: sg::Pipeline,
     bind: sg::Bindings,
@@ -192,9 +546,25 @@ pub struct Renderer {
     texture_manager: TextureManager,
     batches: Vec<DrawBatch>,
     sampler: sg::Sampler,
+    repeat_sampler: sg::Sampler,
+    repeat_texture: bool,
+    tint_mode: TintMode,
+    material: Material,
+    /// World-space tint for day/night cycles, white leaves colors unchanged
+    ambient_color: Vec4,
+    lighting: LightBuffer,
+    force_unbatched: bool,
     vbuf_size: usize,
     ibuf_size: usize,
     view_cache: HashMap<u32, sg::View>,
+    stats: RenderStats,
+    sort_key: f32,
+    draw_space: DrawSpace,
+    screen_size: Vec2,
+    camera_zoom: f32,
+    /// Camera's visible world AABB captured in `begin_frame`
+    view_bounds: (Vec2, Vec2),
+    coordinate_space: CoordinateSpace,
 }
 
 /// Implementation for new, init, flush.
@@ -203,6 +573,8 @@ impl Renderer {
     pub fn new() -> Self {
         Self {
             textured_pipeline: sg::Pipeline::default(),
+            additive_pipeline: sg::Pipeline::default(),
+            sdf_circle_pipeline: sg::Pipeline::default(),
             colored_pipeline: sg::Pipeline::default(),
             line_pipeline: sg::Pipeline::default(),
             bind: sg::Bindings::default(),
@@ -211,9 +583,23 @@ impl Renderer {
             texture_manager: TextureManager::new(),
             batches: Vec::new(),
             sampler: sg::Sampler::default(),
+            repeat_sampler: sg::Sampler::default(),
+            repeat_texture: false,
+            tint_mode: TintMode::Multiply,
+            material: Material::Default,
+            ambient_color: Vec4::ONE,
+            lighting: LightBuffer::new(),
+            force_unbatched: false,
             vbuf_size: 0,
             ibuf_size: 0,
             view_cache: HashMap::new(),
+            stats: RenderStats::default(),
+            sort_key: 0.0,
+            draw_space: DrawSpace::World,
+            screen_size: Vec2::new(800.0, 600.0),
+            camera_zoom: 1.0,
+            view_bounds: (Vec2::splat(f32::NEG_INFINITY), Vec2::splat(f32::INFINITY)),
+            coordinate_space: CoordinateSpace::YUp,
         }
     }
 
@@ -229,720 +615,257 @@ impl Renderer {
             ..Default::default()
         });
 
-        // Platform-specific shader compilation
-        let (texture_shader, colored_shader) = if cfg!(target_os = "windows") {
-            // HLSL shaders for Windows/D3D11
-            let textured_vs_source = "
-    cbuffer uniforms : register(b0) {
-        float4x4 mvp;
-    };
+        // Same filtering, but tiles UVs outside 0..1 for tiled sprites
+        self.repeat_sampler = sg::make_sampler(&sg::SamplerDesc {
+            min_filter: sg::Filter::Nearest,
+            mag_filter: sg::Filter::Nearest,
+            wrap_u: sg::Wrap::Repeat,
+            wrap_v: sg::Wrap::Repeat,
+            ..Default::default()
+        });
 
-    struct vs_in {
-        float2 position : POSITION;
-        float2 texcoord : TEXCOORD;
-        float4 color    : COLOR;
-    };
+        let sources = shader_sources();
+        let texture_shader = make_shader(sources.textured_vs, sources.textured_fs, true);
+        let additive_shader = make_shader(sources.textured_vs, sources.additive_fs, true);
+        let colored_shader = make_shader(sources.color_vs, sources.color_fs, false);
+        let sdf_circle_shader = make_shader(sources.textured_vs, sources.sdf_circle_fs, false);
 
-    struct vs_out {
-        float4 position : SV_Position;
-        float2 texcoord : TEXCOORD;
-        float4 color    : COLOR;
-    };
+        // Create pipelines
+        self.textured_pipeline =
+            make_pipeline(texture_shader, sg::PrimitiveType::Triangles, alpha_blend());
+        self.additive_pipeline =
+            make_pipeline(additive_shader, sg::PrimitiveType::Triangles, alpha_blend());
+        self.sdf_circle_pipeline = make_pipeline(
+            sdf_circle_shader,
+            sg::PrimitiveType::Triangles,
+            alpha_blend(),
+        );
+        self.colored_pipeline =
+            make_pipeline(colored_shader, sg::PrimitiveType::Triangles, alpha_blend());
+        self.line_pipeline = make_pipeline(colored_shader, sg::PrimitiveType::Lines, alpha_blend());
+        self.lighting.init(colored_shader, texture_shader);
 
-    vs_out main(vs_in inp) {
-        vs_out outp;
-        outp.position = mul(mvp, float4(inp.position, 0.0, 1.0));
-        outp.texcoord = inp.texcoord;
-        outp.color = inp.color;
-        return outp;
-    }
-    \0";
+        let initial_vtx_count = 1000usize;
+        let initial_idx_count = 1500usize;
 
-            let textured_fs_source = "
-    Texture2D tex : register(t0);
-    SamplerState smp : register(s0);
+        let vbuf_size_bytes = initial_vtx_count * mem::size_of::<Vertex>();
+        let ibuf_size_bytes = initial_idx_count * mem::size_of::<u16>();
 
-    struct ps_in {
-        float4 position : SV_Position;
-        float2 texcoord : TEXCOORD;
-        float4 color : COLOR;
-    };
+        let vbuf = sg::make_buffer(&sg::BufferDesc {
+            size: vbuf_size_bytes,
+            usage: sg::BufferUsage {
+                vertex_buffer: true,
+                stream_update: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
 
-    float4 main(ps_in inp) : SV_Target0 {
-        float4 tex_color = tex.Sample(smp, inp.texcoord);
-        return tex_color * inp.color;
-    }
-    \0";
+        let ibuf = sg::make_buffer(&sg::BufferDesc {
+            size: ibuf_size_bytes,
+            usage: sg::BufferUsage {
+                index_buffer: true,
+                stream_update: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
 
-            let color_vs_source = "
-    cbuffer uniforms : register(b0) {
-        float4x4 mvp;
-    };
+        self.bind.vertex_buffers[0] = vbuf;
+        self.bind.index_buffer = ibuf;
+        self.vbuf_size = vbuf_size_bytes;
+        self.ibuf_size = ibuf_size_bytes;
+        self.bind.samplers[0] = self.sampler;
 
-    struct vs_in {
-        float2 position : POSITION;
-        float2 texcoord : TEXCOORD;
-        float4 color    : COLOR;
-    };
+        println!("Renderer initialized with shaders and buffers");
+    }
 
-    struct vs_out {
-        float4 position : SV_Position;
-        float4 color    : COLOR;
-    };
+    /// Start a frame for `camera`: clears queued draws and picks up the camera's viewport,
+    /// zoom and visible bounds so draws can cull and pick level of detail
+    pub fn begin_frame(&mut self, camera: &Camera2D) {
+        let viewport = camera.viewport_size();
+        self.set_screen_size(viewport.x, viewport.y);
+        self.set_camera_zoom(camera.get_zoom());
+        self.view_bounds = camera.visible_aabb();
+        self.coordinate_space = camera.coordinate_space();
 
-    vs_out main(vs_in inp) {
-        vs_out outp;
-        outp.position = mul(mvp, float4(inp.position, 0.0, 1.0));
-        outp.color = inp.color;
-        return outp;
+        self.vertices.clear();
+        self.indices.clear();
+        self.batches.clear();
+        self.lighting.clear();
+        self.sort_key = 0.0;
+        self.draw_space = DrawSpace::World;
     }
-    \0";
 
-            let color_fs_source = "
-    struct ps_in {
-        float4 position : SV_Position;
-        float4 color : COLOR;
-    };
+    /// Space that following draws are in, `DrawSpace::World` by default
+    pub fn set_draw_space(&mut self, space: DrawSpace) {
+        self.draw_space = space;
+    }
 
-    float4 main(ps_in inp) : SV_Target0 {
-        return inp.color;
+    pub fn draw_space(&self) -> DrawSpace {
+        self.draw_space
     }
-    \0";
 
-            let texture_shader = sg::make_shader(&sg::ShaderDesc {
-                vertex_func: sg::ShaderFunction {
-                    source: textured_vs_source.as_ptr() as *const i8,
-                    ..Default::default()
-                },
-                fragment_func: sg::ShaderFunction {
-                    source: textured_fs_source.as_ptr() as *const i8,
-                    ..Default::default()
-                },
-                attrs: [
-                    sg::ShaderVertexAttr {
-                        hlsl_sem_name: "POSITION\0".as_ptr() as *const i8,
-                        hlsl_sem_index: 0,
-                        ..Default::default()
-                    },
-                    sg::ShaderVertexAttr {
-                        hlsl_sem_name: "TEXCOORD\0".as_ptr() as *const i8,
-                        hlsl_sem_index: 0,
-                        ..Default::default()
-                    },
-                    sg::ShaderVertexAttr {
-                        hlsl_sem_name: "COLOR\0".as_ptr() as *const i8,
-                        hlsl_sem_index: 0,
-                        ..Default::default()
-                    },
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                ],
-                uniform_blocks: [
-                    sg::ShaderUniformBlock {
-                        stage: sg::ShaderStage::Vertex,
-                        size: mem::size_of::<Uniforms>() as u32,
-                        hlsl_register_b_n: 0,
-                        ..Default::default()
-                    },
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                ],
-                views: [
-                    sg::ShaderView {
-                        texture: sg::ShaderTextureView {
-                            stage: sg::ShaderStage::Fragment,
-                            image_type: sg::ImageType::Dim2,
-                            sample_type: sg::ImageSampleType::Float,
-                            multisampled: false,
-                            hlsl_register_t_n: 0,
-                            msl_texture_n: 0,
-                            wgsl_group1_binding_n: 0,
-                        },
-                        storage_buffer: sg::ShaderStorageBufferView::default(),
-                        storage_image: sg::ShaderStorageImageView::default(),
-                    },
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                ],
-                samplers: [
-                    sg::ShaderSampler {
-                        stage: sg::ShaderStage::Fragment,
-                        sampler_type: sg::SamplerType::Filtering,
-                        hlsl_register_s_n: 0,
-                        ..Default::default()
-                    },
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                ],
-                texture_sampler_pairs: [
-                    sg::ShaderTextureSamplerPair {
-                        stage: sg::ShaderStage::Fragment,
-                        view_slot: 0,
-                        sampler_slot: 0,
-                        glsl_name: std::ptr::null(),
-                    },
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                ],
-                ..Default::default()
-            });
+    #[cfg(test)]
+    pub(crate) fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
 
-            let colored_shader = sg::make_shader(&sg::ShaderDesc {
-                vertex_func: sg::ShaderFunction {
-                    source: color_vs_source.as_ptr() as *const i8,
-                    ..Default::default()
-                },
-                fragment_func: sg::ShaderFunction {
-                    source: color_fs_source.as_ptr() as *const i8,
-                    ..Default::default()
-                },
-                attrs: [
-                    sg::ShaderVertexAttr {
-                        hlsl_sem_name: "POSITION\0".as_ptr() as *const i8,
-                        hlsl_sem_index: 0,
-                        ..Default::default()
-                    },
-                    sg::ShaderVertexAttr {
-                        hlsl_sem_name: "TEXCOORD\0".as_ptr() as *const i8,
-                        hlsl_sem_index: 0,
-                        ..Default::default()
-                    },
-                    sg::ShaderVertexAttr {
-                        hlsl_sem_name: "COLOR\0".as_ptr() as *const i8,
-                        hlsl_sem_index: 0,
-                        ..Default::default()
-                    },
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                    sg::ShaderVertexAttr::default(),
-                ],
-                uniform_blocks: [
-                    sg::ShaderUniformBlock {
-                        stage: sg::ShaderStage::Vertex,
-                        size: mem::size_of::<Uniforms>() as u32,
-                        hlsl_register_b_n: 0,
-                        ..Default::default()
-                    },
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                ],
-                ..Default::default()
-            });
+    #[cfg(test)]
+    pub(crate) fn batch_spaces(&self) -> Vec<DrawSpace> {
+        self.batches.iter().map(|batch| batch.space).collect()
+    }
 
-            (texture_shader, colored_shader)
-        } else {
-            // GLSL shaders for Linux/macOS/OpenGL
-            let textured_vs_source = "
-    #version 330
+    /// Following draws are UI in screen pixels, (0,0) at the top-left, ignoring the camera
+    pub fn begin_ui(&mut self) {
+        self.draw_space = DrawSpace::Screen;
+    }
 
-    uniform mat4 mvp;
+    /// Return to world-space drawing after `begin_ui`
+    pub fn end_ui(&mut self) {
+        self.draw_space = DrawSpace::World;
+    }
 
-    layout(location = 0) in vec2 position;
-    layout(location = 1) in vec2 texcoord;
-    layout(location = 2) in vec4 color;
+    /// Viewport size in pixels used by screen-space draws
+    pub fn set_screen_size(&mut self, width: f32, height: f32) {
+        self.screen_size = Vec2::new(width, height);
+    }
 
-    out vec2 uv;
-    out vec4 color0;
+    pub fn screen_size(&self) -> Vec2 {
+        self.screen_size
+    }
 
-    void main() {
-        gl_Position = mvp * vec4(position, 0.0, 1.0);
-        uv = texcoord;
-        color0 = color;
+    /// Camera zoom used for level of detail, e.g. auto circle segments
+    pub fn set_camera_zoom(&mut self, zoom: f32) {
+        self.camera_zoom = zoom;
     }
-    \0";
-
-            let textured_fs_source = "
-    #version 330
-
-    uniform sampler2D tex;
-
-    in vec2 uv;
-    in vec4 color0;
-
-    out vec4 frag_color;
 
-    void main() {
-        frag_color = texture(tex, uv) * color0;
+    /// Whether +Y points up on screen for the current draw space: the camera's
+    /// `CoordinateSpace` in world space, always false in screen space
+    pub fn is_y_up(&self) -> bool {
+        self.draw_space == DrawSpace::World && self.coordinate_space == CoordinateSpace::YUp
     }
-    \0";
-
-            let color_vs_source = "
-    #version 330
 
-    uniform mat4 mvp;
+    /// World-space (min, max) the camera sees this frame, as of `begin_frame`
+    pub fn view_bounds(&self) -> (Vec2, Vec2) {
+        self.view_bounds
+    }
 
-    layout(location = 0) in vec2 position;
-    layout(location = 1) in vec2 texcoord;
-    layout(location = 2) in vec4 color;
+    /// Whether a world-space AABB overlaps this frame's view, for culling draws
+    pub fn is_in_view(&self, min: Vec2, max: Vec2) -> bool {
+        let (view_min, view_max) = self.view_bounds;
+        min.x <= view_max.x && max.x >= view_min.x && min.y <= view_max.y && max.y >= view_min.y
+    }
 
-    out vec4 color0;
+    /// Resolve a circle's segment count for the current draw space and zoom
+    fn circle_segments(&self, circle: &Circle) -> u32 {
+        let zoom = match self.draw_space {
+            DrawSpace::World => self.camera_zoom,
+            DrawSpace::Screen => 1.0,
+        };
+        circle.segment_count(zoom)
+    }
 
-    void main() {
-        gl_Position = mvp * vec4(position, 0.0, 1.0);
-        color0 = color;
+    /// Sort key applied to following draws; batches are drawn in ascending key order.
+    /// Draws with equal keys keep submission order, e.g. use world Y for top-down depth.
+    pub fn set_sort_key(&mut self, sort_key: f32) {
+        self.sort_key = sort_key;
     }
-    \0";
 
-            let color_fs_source = "
-    #version 330
+    pub fn sort_key(&self) -> f32 {
+        self.sort_key
+    }
 
-    in vec4 color0;
-    out vec4 frag_color;
+    pub fn flush(&mut self, camera: &mut Camera2D) {
+        if !self.upload_frame() {
+            return;
+        }
 
-    void main() {
-        frag_color = color0;
+        let (uniforms, screen_uniforms) = self.frame_uniforms(camera, self.ambient_color);
+        self.draw_batches(0..self.batches.len(), &uniforms, &screen_uniforms);
     }
-    \0";
-
-            let texture_shader = sg::make_shader(&sg::ShaderDesc {
-                vertex_func: sg::ShaderFunction {
-                    source: textured_vs_source.as_ptr() as *const i8,
-                    ..Default::default()
-                },
-                fragment_func: sg::ShaderFunction {
-                    source: textured_fs_source.as_ptr() as *const i8,
-                    ..Default::default()
-                },
-                uniform_blocks: [
-                    sg::ShaderUniformBlock {
-                        stage: sg::ShaderStage::Vertex,
-                        size: mem::size_of::<Uniforms>() as u32,
-                        glsl_uniforms: [
-                            sg::GlslShaderUniform {
-                                glsl_name: "mvp\0".as_ptr() as *const i8,
-                                _type: sg::UniformType::Mat4,
-                                array_count: 1,
-                            },
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                        ],
-                        ..Default::default()
-                    },
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                ],
-                views: [
-                    sg::ShaderView {
-                        texture: sg::ShaderTextureView {
-                            stage: sg::ShaderStage::Fragment,
-                            image_type: sg::ImageType::Dim2,
-                            sample_type: sg::ImageSampleType::Float,
-                            multisampled: false,
-                            ..Default::default()
-                        },
-                        storage_buffer: sg::ShaderStorageBufferView::default(),
-                        storage_image: sg::ShaderStorageImageView::default(),
-                    },
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                    sg::ShaderView::default(),
-                ],
-                samplers: [
-                    sg::ShaderSampler {
-                        stage: sg::ShaderStage::Fragment,
-                        sampler_type: sg::SamplerType::Filtering,
-                        ..Default::default()
-                    },
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                    sg::ShaderSampler::default(),
-                ],
-                texture_sampler_pairs: [
-                    sg::ShaderTextureSamplerPair {
-                        stage: sg::ShaderStage::Fragment,
-                        view_slot: 0,
-                        sampler_slot: 0,
-                        glsl_name: "tex\0".as_ptr() as *const i8,
-                    },
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                    sg::ShaderTextureSamplerPair::default(),
-                ],
-                ..Default::default()
-            });
 
-            let colored_shader = sg::make_shader(&sg::ShaderDesc {
-                vertex_func: sg::ShaderFunction {
-                    source: color_vs_source.as_ptr() as *const i8,
-                    ..Default::default()
-                },
-                fragment_func: sg::ShaderFunction {
-                    source: color_fs_source.as_ptr() as *const i8,
-                    ..Default::default()
-                },
-                uniform_blocks: [
-                    sg::ShaderUniformBlock {
-                        stage: sg::ShaderStage::Vertex,
-                        size: mem::size_of::<Uniforms>() as u32,
-                        glsl_uniforms: [
-                            sg::GlslShaderUniform {
-                                glsl_name: "mvp\0".as_ptr() as *const i8,
-                                _type: sg::UniformType::Mat4,
-                                array_count: 1,
-                            },
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                            sg::GlslShaderUniform::default(),
-                        ],
-                        ..Default::default()
-                    },
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                    sg::ShaderUniformBlock::default(),
-                ],
-                ..Default::default()
-            });
+    /// Queue a light for this frame; lights are cleared by `begin_frame`
+    pub fn add_light(&mut self, light: Light) {
+        self.lighting.add(light);
+    }
 
-            (texture_shader, colored_shader)
-        };
+    pub fn lights(&self) -> &[Light] {
+        self.lighting.lights()
+    }
 
-        // Vertex layout (same for all platforms)
-        let vertex_layout = sg::VertexLayoutState {
-            attrs: [
-                sg::VertexAttrState {
-                    buffer_index: 0,
-                    offset: 0,
-                    format: sg::VertexFormat::Float2,
-                },
-                sg::VertexAttrState {
-                    buffer_index: 0,
-                    offset: 8,
-                    format: sg::VertexFormat::Float2,
-                },
-                sg::VertexAttrState {
-                    buffer_index: 0,
-                    offset: 16,
-                    format: sg::VertexFormat::Float4,
-                },
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-                sg::VertexAttrState::default(),
-            ],
-            buffers: [
-                sg::VertexBufferLayoutState {
-                    stride: mem::size_of::<Vertex>() as i32,
-                    step_func: sg::VertexStep::PerVertex,
-                    step_rate: 1,
-                },
-                sg::VertexBufferLayoutState::default(),
-                sg::VertexBufferLayoutState::default(),
-                sg::VertexBufferLayoutState::default(),
-                sg::VertexBufferLayoutState::default(),
-                sg::VertexBufferLayoutState::default(),
-                sg::VertexBufferLayoutState::default(),
-                sg::VertexBufferLayoutState::default(),
-            ],
-        };
+    pub fn has_lights(&self) -> bool {
+        !self.lighting.lights().is_empty()
+    }
 
-        // Create pipelines
-        self.textured_pipeline = sg::make_pipeline(&sg::PipelineDesc {
-            shader: texture_shader,
-            layout: vertex_layout,
-            index_type: sg::IndexType::Uint16,
-            primitive_type: sg::PrimitiveType::Triangles,
-            cull_mode: sg::CullMode::None,
-            depth: sg::DepthState {
-                write_enabled: false,
-                compare: sg::CompareFunc::Always,
-                ..Default::default()
-            },
-            colors: [
-                sg::ColorTargetState {
-                    blend: sg::BlendState {
-                        enabled: true,
-                        src_factor_rgb: sg::BlendFactor::SrcAlpha,
-                        dst_factor_rgb: sg::BlendFactor::OneMinusSrcAlpha,
-                        src_factor_alpha: sg::BlendFactor::One,
-                        dst_factor_alpha: sg::BlendFactor::OneMinusSrcAlpha,
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                },
-                sg::ColorTargetState::default(),
-                sg::ColorTargetState::default(),
-                sg::ColorTargetState::default(),
-            ],
-            ..Default::default()
-        });
+    /// Render this frame's lights into the light buffer, cleared to the ambient color.
+    /// Runs its own render pass, so it must be called before the frame's main pass begins.
+    pub fn prepare_lighting(&mut self, camera: &mut Camera2D) {
+        let view_proj = camera.get_view_projection_matrix();
+        self.lighting
+            .render(view_proj, camera.viewport_size(), self.ambient_color);
+    }
 
-        self.colored_pipeline = sg::make_pipeline(&sg::PipelineDesc {
-            shader: colored_shader,
-            layout: vertex_layout,
-            index_type: sg::IndexType::Uint16,
-            primitive_type: sg::PrimitiveType::Triangles,
-            cull_mode: sg::CullMode::None,
-            depth: sg::DepthState {
-                write_enabled: false,
-                compare: sg::CompareFunc::Always,
-                ..Default::default()
-            },
-            colors: [
-                sg::ColorTargetState {
-                    blend: sg::BlendState {
-                        enabled: true,
-                        src_factor_rgb: sg::BlendFactor::SrcAlpha,
-                        dst_factor_rgb: sg::BlendFactor::OneMinusSrcAlpha,
-                        src_factor_alpha: sg::BlendFactor::One,
-                        dst_factor_alpha: sg::BlendFactor::OneMinusSrcAlpha,
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                },
-                sg::ColorTargetState::default(),
-                sg::ColorTargetState::default(),
-                sg::ColorTargetState::default(),
-            ],
-            ..Default::default()
-        });
+    /// `flush` that multiplies the light buffer over the scene. World-space batches and
+    /// backgrounds (screen-space batches at the lowest sort key, see `draw_background`) are
+    /// drawn in sort order and lit. Every other screen-space batch (HUD) is drawn after the
+    /// composite, unlit and on top of the world whatever its sort key. The ambient color
+    /// comes from the light buffer instead of the vertex tint. Falls back to `flush` when
+    /// `prepare_lighting` didn't run with lights this frame.
+    pub fn flush_with_lighting(&mut self, camera: &mut Camera2D) {
+        if !self.lighting.is_prepared() {
+            self.flush(camera);
+            return;
+        }
 
-        self.line_pipeline = sg::make_pipeline(&sg::PipelineDesc {
-            shader: colored_shader,
-            layout: vertex_layout,
-            index_type: sg::IndexType::Uint16,
-            primitive_type: sg::PrimitiveType::Lines,
-            cull_mode: sg::CullMode::None,
-            depth: sg::DepthState {
-                write_enabled: false,
-                compare: sg::CompareFunc::Always,
-                ..Default::default()
-            },
-            colors: [
-                sg::ColorTargetState {
-                    blend: sg::BlendState {
-                        enabled: true,
-                        src_factor_rgb: sg::BlendFactor::SrcAlpha,
-                        dst_factor_rgb: sg::BlendFactor::OneMinusSrcAlpha,
-                        src_factor_alpha: sg::BlendFactor::One,
-                        dst_factor_alpha: sg::BlendFactor::OneMinusSrcAlpha,
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                },
-                sg::ColorTargetState::default(),
-                sg::ColorTargetState::default(),
-                sg::ColorTargetState::default(),
-            ],
-            ..Default::default()
-        });
+        let uploaded = self.upload_frame();
+        let (uniforms, screen_uniforms) = self.frame_uniforms(camera, Vec4::ONE);
+        let (lit, unlit) = self.lighting_passes();
 
-        let initial_vtx_count = 1000usize;
-        let initial_idx_count = 1500usize;
+        if uploaded {
+            self.draw_batches(lit, &uniforms, &screen_uniforms);
+        }
+        if self.lighting.composite(&screen_uniforms, self.sampler) {
+            self.stats.draw_calls += 1;
+        }
+        if uploaded {
+            self.draw_batches(unlit, &uniforms, &screen_uniforms);
+        }
+    }
 
-        let vbuf_size_bytes = initial_vtx_count * mem::size_of::<Vertex>();
-        let ibuf_size_bytes = initial_idx_count * mem::size_of::<u16>();
+    /// Indices of the sorted batches drawn before the light composite (lit) and after it
+    fn lighting_passes(&self) -> (Vec<usize>, Vec<usize>) {
+        (0..self.batches.len()).partition(|&index| {
+            let batch = &self.batches[index];
+            batch.space == DrawSpace::World || batch.sort_key == f32::NEG_INFINITY
+        })
+    }
 
-        let vbuf = sg::make_buffer(&sg::BufferDesc {
-            size: vbuf_size_bytes,
-            usage: sg::BufferUsage {
-                vertex_buffer: true,
-                stream_update: true,
-                ..Default::default()
-            },
-            ..Default::default()
-        });
+    /// Back-to-front by sort key; stable so equal keys keep submission order
+    fn sort_batches(&mut self) {
+        self.batches
+            .sort_by(|a, b| a.sort_key.total_cmp(&b.sort_key));
+    }
 
-        let ibuf = sg::make_buffer(&sg::BufferDesc {
-            size: ibuf_size_bytes,
-            usage: sg::BufferUsage {
-                index_buffer: true,
-                stream_update: true,
-                ..Default::default()
-            },
+    /// Stats for the geometry queued so far, before any draw calls
+    fn queued_stats(&self) -> RenderStats {
+        RenderStats {
+            batches: self.batches.len(),
+            vertices: self.vertices.len(),
+            indices: self.indices.len(),
             ..Default::default()
-        });
-
-        self.bind.vertex_buffers[0] = vbuf;
-        self.bind.index_buffer = ibuf;
-        self.vbuf_size = vbuf_size_bytes;
-        self.ibuf_size = ibuf_size_bytes;
-        self.bind.samplers[0] = self.sampler;
-
-        println!("Renderer initialized with shaders and buffers");
+        }
     }
 
-    pub fn begin_frame(&mut self) {
-        self.vertices.clear();
-        self.indices.clear();
-        self.batches.clear();
-    }
+    /// Reset stats, sort batches and upload this frame's geometry.
+    /// Returns false when there is nothing to draw.
+    fn upload_frame(&mut self) -> bool {
+        self.stats = self.queued_stats();
+
+        self.sort_batches();
 
-    pub fn flush(&mut self, camera: &mut Camera2D) {
         if self.vertices.is_empty() {
-            return;
+            return false;
         }
 
         let vertex_bytes = self.vertices.len() * mem::size_of::<Vertex>();
@@ -1007,40 +930,83 @@ impl Renderer {
             },
         );
 
-        // Setup uniforms
+        true
+    }
+
+    /// World and screen-space uniforms for this frame
+    fn frame_uniforms(&self, camera: &mut Camera2D, ambient: Vec4) -> (Uniforms, Uniforms) {
         let view_proj = camera.get_view_projection_matrix();
         let uniforms = Uniforms {
             mvp: view_proj.to_cols_array_2d(),
+            ambient: ambient.to_array(),
         };
 
-        // Draw all batches
-        for batch in &self.batches {
-            // Select pipeline based on whether we're using textures
-            let uses_texture = batch.texture.id != self.texture_manager.get_white_texture().id;
-            let pipeline = match (batch.primitive_type, uses_texture) {
-                (PrimitiveType::Lines, _) => self.line_pipeline,
-                (PrimitiveType::Triangles, true) => self.textured_pipeline,
-                (PrimitiveType::Triangles, false) => self.colored_pipeline,
+        // Screen space: pixels, origin top-left, y down
+        let screen = camera.viewport_size();
+        let screen_uniforms = Uniforms {
+            mvp: Mat4::orthographic_rh(0.0, screen.x, screen.y, 0.0, -1.0, 1.0).to_cols_array_2d(),
+            ambient: [1.0; 4],
+        };
+
+        (uniforms, screen_uniforms)
+    }
+
+    /// Select the pipeline based on primitive, material, texture and tint mode
+    fn pipeline_kind(&self, batch: &DrawBatch) -> PipelineKind {
+        let uses_texture = batch.texture.id != self.texture_manager.get_white_texture().id;
+        match (batch.primitive_type, uses_texture) {
+            (PrimitiveType::Lines, _) => PipelineKind::Lines,
+            (PrimitiveType::Triangles, _) if batch.material == Material::SdfCircle => {
+                PipelineKind::SdfCircle
+            }
+            (PrimitiveType::Triangles, true) => match batch.tint_mode {
+                TintMode::Multiply => PipelineKind::Textured,
+                TintMode::Additive => PipelineKind::Additive,
+            },
+            (PrimitiveType::Triangles, false) => PipelineKind::Colored,
+        }
+    }
+
+    /// Draw the sorted batches at `indices`
+    fn draw_batches(
+        &mut self,
+        indices: impl IntoIterator<Item = usize>,
+        uniforms: &Uniforms,
+        screen_uniforms: &Uniforms,
+    ) {
+        for index in indices {
+            let batch = &self.batches[index];
+            let pipeline = match self.pipeline_kind(batch) {
+                PipelineKind::Lines => self.line_pipeline,
+                PipelineKind::SdfCircle => self.sdf_circle_pipeline,
+                PipelineKind::Textured => self.textured_pipeline,
+                PipelineKind::Additive => self.additive_pipeline,
+                PipelineKind::Colored => self.colored_pipeline,
             };
 
             // Bind texture and sampler
-            let view = if let Some(&cached_view) = self.view_cache.get(&batch.texture.id) {
-                cached_view
-            } else {
-                let new_view = sg::make_view(&sg::ViewDesc {
+            let view = Self::cached_view(&mut self.view_cache, batch.texture, |image| {
+                sg::make_view(&sg::ViewDesc {
                     texture: sg::TextureViewDesc {
-                        image: batch.texture,
+                        image,
                         ..Default::default()
                     },
                     ..Default::default()
-                });
-                self.view_cache.insert(batch.texture.id, new_view);
-                new_view
-            };
+                })
+            });
 
             self.bind.views[0] = view;
 
-            self.bind.samplers[0] = self.sampler;
+            self.bind.samplers[0] = if batch.repeat {
+                self.repeat_sampler
+            } else {
+                self.sampler
+            };
+
+            let batch_uniforms = match batch.space {
+                DrawSpace::World => uniforms,
+                DrawSpace::Screen => screen_uniforms,
+            };
 
             // Apply pipeline and bindings
             sg::apply_pipeline(pipeline);
@@ -1048,16 +1014,42 @@ impl Renderer {
             sg::apply_uniforms(
                 0,
                 &sg::Range {
-                    ptr: &uniforms as *const _ as *const _,
+                    ptr: batch_uniforms as *const _ as *const _,
                     size: mem::size_of::<Uniforms>(),
                 },
             );
 
             // Draw this batch
             sg::draw(batch.start_index, batch.index_count, 1);
+            self.stats.draw_calls += 1;
         }
     }
 
+    /// Multiply the color of everything drawn in world space by `color`, e.g. to darken
+    /// the scene at night. Screen-space draws (HUD) are not tinted. Defaults to white.
+    pub fn set_ambient_color(&mut self, color: Vec4) {
+        self.ambient_color = color;
+    }
+
+    pub fn ambient_color(&self) -> Vec4 {
+        self.ambient_color
+    }
+
+    /// Debug option: give every primitive its own batch and draw call, so render stats
+    /// show true object counts and sort order issues are easier to spot. Off by default.
+    pub fn set_force_unbatched(&mut self, unbatched: bool) {
+        self.force_unbatched = unbatched;
+    }
+
+    pub fn is_force_unbatched(&self) -> bool {
+        self.force_unbatched
+    }
+
+    /// Statistics from the most recent flush
+    pub fn last_frame_stats(&self) -> RenderStats {
+        self.stats
+    }
+
     fn add_batch(&mut self, texture: sg::Image, start_index: usize, index_count: usize) {
         self.add_batch_with_type(texture, start_index, index_count, PrimitiveType::Triangles);
     }
@@ -1070,11 +1062,16 @@ impl Renderer {
         primitive_type: PrimitiveType,
     ) {
         // Check if we can merge with the last batch (same texture AND same primitive type)
-        if let Some(last_batch) = self.batches.last_mut() {
+        if let Some(last_batch) = self.batches.last_mut().filter(|_| !self.force_unbatched) {
             // Only merge if EVERYTHING matches: texture, primitive type, AND indices are contiguous
             if last_batch.texture.id == texture.id &&
                last_batch.primitive_type as u8 == primitive_type as u8 &&  // Exact match
-               last_batch.start_index + last_batch.index_count == start_index
+               last_batch.start_index + last_batch.index_count == start_index &&
+               last_batch.sort_key == self.sort_key &&
+               last_batch.space == self.draw_space &&
+               last_batch.repeat == self.repeat_texture &&
+               last_batch.tint_mode == self.tint_mode &&
+               last_batch.material == self.material
             {
                 last_batch.index_count += index_count;
                 return;
@@ -1087,6 +1084,11 @@ impl Renderer {
             start_index,
             index_count,
             primitive_type,
+            sort_key: self.sort_key,
+            space: self.draw_space,
+            repeat: self.repeat_texture,
+            tint_mode: self.tint_mode,
+            material: self.material,
         });
     }
 }
@@ -1097,34 +1099,17 @@ impl Renderer {
         let start_vertex = self.vertices.len() as u16;
         let start_index = self.indices.len();
 
-        let x1 = quad.position.x - quad.size.x * 0.5;
-        let y1 = quad.position.y - quad.size.y * 0.5;
-        let x2 = quad.position.x + quad.size.x * 0.5;
-        let y2 = quad.position.y + quad.size.y * 0.5;
-
         let color = [quad.color.x, quad.color.y, quad.color.z, quad.color.w];
+        let texcoords = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
 
         // Add vertices (same for both filled and outline)
-        self.vertices.push(Vertex {
-            pos: [x1, y1],
-            texcoord: [0.0, 0.0],
-            color,
-        });
-        self.vertices.push(Vertex {
-            pos: [x2, y1],
-            texcoord: [1.0, 0.0],
-            color,
-        });
-        self.vertices.push(Vertex {
-            pos: [x2, y2],
-            texcoord: [1.0, 1.0],
-            color,
-        });
-        self.vertices.push(Vertex {
-            pos: [x1, y2],
-            texcoord: [0.0, 1.0],
-            color,
-        });
+        for (corner, texcoord) in quad.corners().into_iter().zip(texcoords) {
+            self.vertices.push(Vertex {
+                pos: [corner.x, corner.y],
+                texcoord,
+                color,
+            });
+        }
 
         if quad.outline_only {
             // Line indices: connect the 4 corners in a loop
@@ -1166,7 +1151,14 @@ impl Renderer {
     }
 
     pub fn draw_circle(&mut self, circle: &Circle) {
-        if circle.outline_only {
+        let circle = &Circle {
+            segments: self.circle_segments(circle),
+            ..*circle
+        };
+
+        if circle.smooth_edge && !circle.outline_only {
+            self.draw_smooth_circle_fill(circle);
+        } else if circle.outline_only {
             let start_vertex = self.vertices.len() as u16;
             let start_index = self.indices.len();
             let color = [
@@ -1250,83 +1242,355 @@ impl Renderer {
         }
 
         if circle.show_line {
-            let start_vertex = self.vertices.len() as u16;
-            let start_index = self.indices.len();
-
-            // Calculate end point on the circle edge
-            let end_x = circle.center.x + circle.line_angle.cos() * circle.radius;
-            let end_y = circle.center.y + circle.line_angle.sin() * circle.radius;
-
-            let line_color = [
-                circle.line_color.x,
-                circle.line_color.y,
-                circle.line_color.z,
-                circle.line_color.w,
-            ];
+            self.draw_circle_line(circle);
+        }
+    }
 
-            // Add vertices for the line (center and edge point)
+    /// Fill as one quad covering the circle; the SDF shader cuts out the anti-aliased disc
+    fn draw_smooth_circle_fill(&mut self, circle: &Circle) {
+        let start_vertex = self.vertices.len() as u16;
+        let start_index = self.indices.len();
+        let color = circle.color.to_array();
+        let r = circle.radius;
+        let corners = [
+            ([-r, -r], [0.0, 0.0]),
+            ([r, -r], [1.0, 0.0]),
+            ([r, r], [1.0, 1.0]),
+            ([-r, r], [0.0, 1.0]),
+        ];
+        for ([x, y], texcoord) in corners {
             self.vertices.push(Vertex {
-                pos: [circle.center.x, circle.center.y],
-                texcoord: [0.5, 0.5],
-                color: line_color,
+                pos: [circle.center.x + x, circle.center.y + y],
+                texcoord,
+                color,
             });
+        }
+        self.indices.extend_from_slice(&[
+            start_vertex,
+            start_vertex + 1,
+            start_vertex + 2,
+            start_vertex,
+            start_vertex + 2,
+            start_vertex + 3,
+        ]);
 
-            self.vertices.push(Vertex {
-                pos: [end_x, end_y],
-                texcoord: [0.5, 0.5],
-                color: line_color,
-            });
+        self.material = Material::SdfCircle;
+        self.add_batch_with_type(
+            self.texture_manager.get_white_texture(),
+            start_index,
+            6,
+            PrimitiveType::Triangles,
+        );
+        self.material = Material::Default;
+    }
 
-            // Add indices for the line
-            self.indices
-                .extend_from_slice(&[start_vertex, start_vertex + 1]);
+    /// Draw a slice of quads, reserving buffer space for all of them up front
+    pub fn draw_quads(&mut self, quads: &[Quad]) {
+        let index_count: usize = quads
+            .iter()
+            .map(|q| if q.outline_only { 8 } else { 6 })
+            .sum();
+        self.vertices.reserve(quads.len() * 4);
+        self.indices.reserve(index_count);
+
+        for quad in quads {
+            self.draw_quad(quad);
+        }
+    }
 
-            self.add_batch_with_type(
-                self.texture_manager.get_white_texture(),
-                start_index,
-                2,
-                PrimitiveType::Lines,
-            );
+    /// Draw every item in order, same as calling `draw` on each
+    pub fn draw_all(&mut self, items: &[&dyn Renderable]) {
+        for item in items {
+            item.draw(self);
         }
     }
 
-    pub fn draw_sprite(&mut self, sprite: &Sprite) {
-        let start_vertex = self.vertices.len() as u16;
-        let start_index = self.indices.len();
+    /// Draw a slice of circles, reserving buffer space for all of them up front
+    pub fn draw_circles(&mut self, circles: &[Circle]) {
+        let (vertex_count, index_count) =
+            circles.iter().fold((0, 0), |(vertices, indices), circle| {
+                let segments = self.circle_segments(circle) as usize;
+                let line = if circle.show_line { 2 } else { 0 };
+                let (v, i) = if circle.outline_only {
+                    (segments, segments * 2)
+                } else {
+                    (segments + 1, segments * 3)
+                };
+                (vertices + v + line, indices + i + line)
+            });
+        self.vertices.reserve(vertex_count);
+        self.indices.reserve(index_count);
 
-        // Determine which texture to use
-        // let texture = sprite.texture.unwrap_or(self.texture_manager.get_white_texture());
-        let texture = self
-            .get_texture(&sprite.texture_name)
-            .unwrap_or(self.texture_manager.get_white_texture());
+        for circle in circles {
+            self.draw_circle(circle);
+        }
+    }
 
-        // Create 4 vertices for the sprite quad
-        let half_size = sprite.size * 0.5;
-        let cos_rot = sprite.rotation.cos();
-        let sin_rot = sprite.rotation.sin();
+    /// Outline of a collider's shape at its position, e.g. to debug hitbox placement
+    pub fn draw_collider(&mut self, collider: &Collider, color: Vec4) {
+        self.draw_collider_rotated(collider, color, 0.0);
+    }
 
-        let local_positions = [
-            Vec2::new(-half_size.x, -half_size.y), // Top-left
-            Vec2::new(half_size.x, -half_size.y),  // Top-right
-            Vec2::new(half_size.x, half_size.y),   // Bottom-right
-            Vec2::new(-half_size.x, half_size.y),  // Bottom-left
-        ];
+    /// `draw_collider` with rectangles rotated by `rotation` radians around their center
+    pub fn draw_collider_rotated(&mut self, collider: &Collider, color: Vec4, rotation: f32) {
+        let position = collider.position;
+        match collider.shape {
+            CollisionShape::Rectangle { width, height } => {
+                let outline = Quad::new(position.x, position.y, width, height, color)
+                    .with_outline()
+                    .with_rotation(rotation);
+                self.draw_quad(&outline);
+            }
+            CollisionShape::Circle { radius } => {
+                let outline = Circle::new(position.x, position.y, radius, color).with_outline();
+                self.draw_circle(&outline);
+            }
+        }
+    }
 
-        let mut uvs = [
-            Vec2::new(sprite.uv.x, sprite.uv.y),               // Top-left UV
-            Vec2::new(sprite.uv.x + sprite.uv.z, sprite.uv.y), // Top-right UV
-            Vec2::new(sprite.uv.x + sprite.uv.z, sprite.uv.y + sprite.uv.w), // Bottom-right UV
-            Vec2::new(sprite.uv.x, sprite.uv.y + sprite.uv.w), // Bottom-left UV
-        ];
+    /// Progress or health bar: `rect` in `bg_color` with the left `progress` (0..=1) of it
+    /// filled with `fill_color`. Always axis-aligned.
+    pub fn draw_progress_bar(
+        &mut self,
+        rect: &Quad,
+        progress: f32,
+        bg_color: Vec4,
+        fill_color: Vec4,
+    ) {
+        let background = Quad {
+            color: bg_color,
+            outline_only: false,
+            rotation: 0.0,
+            ..*rect
+        };
+        self.draw_quad(&background);
 
-        // Apply flipping by swapping UV coordinates
-        if sprite.flip_x {
-            uvs.swap(0, 1); // Swap top-left with top-right
-            uvs.swap(2, 3); // Swap bottom-right with bottom-left
+        let fill = rect.progress_fill(progress, fill_color);
+        if fill.size.x > 0.0 {
+            self.draw_quad(&fill);
         }
-        if sprite.flip_y {
-            uvs.swap(0, 3); // Swap top-left with bottom-left
-            uvs.swap(1, 2); // Swap top-right with bottom-right
+    }
+
+    /// `draw_progress_bar` with a border of `border_thickness` drawn inside the edges, on top of the fill
+    pub fn draw_progress_bar_outlined(
+        &mut self,
+        rect: &Quad,
+        progress: f32,
+        bg_color: Vec4,
+        fill_color: Vec4,
+        border_color: Vec4,
+        border_thickness: f32,
+    ) {
+        self.draw_progress_bar(rect, progress, bg_color, fill_color);
+        self.draw_quad_bordered(rect, Vec4::ZERO, border_color, border_thickness);
+    }
+
+    /// Filled quad with a border of `border_thickness` drawn inside its edges.
+    /// Fill and border are all triangles, so they share one batch. Always axis-aligned.
+    pub fn draw_quad_bordered(
+        &mut self,
+        rect: &Quad,
+        fill_color: Vec4,
+        border_color: Vec4,
+        border_thickness: f32,
+    ) {
+        let fill = Quad {
+            color: fill_color,
+            outline_only: false,
+            rotation: 0.0,
+            ..*rect
+        };
+        self.draw_quad(&fill);
+
+        let size = rect.size;
+        let thickness = border_thickness.clamp(0.0, size.x.min(size.y) * 0.5);
+        if thickness <= 0.0 {
+            return;
+        }
+
+        let pos = rect.position;
+        let half = size * 0.5;
+        let inner_height = size.y - thickness * 2.0;
+        let edges = [
+            // Top and bottom span the full width, left and right fill the gap between them
+            Quad::new(
+                pos.x,
+                pos.y + half.y - thickness * 0.5,
+                size.x,
+                thickness,
+                border_color,
+            ),
+            Quad::new(
+                pos.x,
+                pos.y - half.y + thickness * 0.5,
+                size.x,
+                thickness,
+                border_color,
+            ),
+            Quad::new(
+                pos.x - half.x + thickness * 0.5,
+                pos.y,
+                thickness,
+                inner_height,
+                border_color,
+            ),
+            Quad::new(
+                pos.x + half.x - thickness * 0.5,
+                pos.y,
+                thickness,
+                inner_height,
+                border_color,
+            ),
+        ];
+        for edge in &edges {
+            self.draw_quad(edge);
+        }
+    }
+
+    /// Filled circle with a ring of `border_thickness` along the inside of its edge
+    pub fn draw_circle_bordered(
+        &mut self,
+        circle: &Circle,
+        fill_color: Vec4,
+        border_color: Vec4,
+        border_thickness: f32,
+    ) {
+        let fill = Circle {
+            color: fill_color,
+            outline_only: false,
+            show_line: false,
+            ..*circle
+        };
+        self.draw_circle(&fill);
+
+        let circle = &Circle {
+            segments: self.circle_segments(circle),
+            ..*circle
+        };
+        let thickness = border_thickness.clamp(0.0, circle.radius);
+        if thickness > 0.0 {
+            let start_vertex = self.vertices.len() as u16;
+            let start_index = self.indices.len();
+            let color = [
+                border_color.x,
+                border_color.y,
+                border_color.z,
+                border_color.w,
+            ];
+            let inner_radius = circle.radius - thickness;
+
+            // Outer and inner vertex for each segment
+            for i in 0..circle.segments {
+                let angle = (i as f32 / circle.segments as f32) * 2.0 * std::f32::consts::PI;
+                let dir = Vec2::new(angle.cos(), angle.sin());
+                for radius in [circle.radius, inner_radius] {
+                    let p = circle.center + dir * radius;
+                    self.vertices.push(Vertex {
+                        pos: [p.x, p.y],
+                        texcoord: [0.5, 0.5],
+                        color,
+                    });
+                }
+            }
+
+            for i in 0..circle.segments {
+                let next = (i + 1) % circle.segments;
+                let outer = start_vertex + (i * 2) as u16;
+                let inner = outer + 1;
+                let next_outer = start_vertex + (next * 2) as u16;
+                let next_inner = next_outer + 1;
+                self.indices
+                    .extend_from_slice(&[outer, next_outer, inner, inner, next_outer, next_inner]);
+            }
+
+            self.add_batch_with_type(
+                self.texture_manager.get_white_texture(),
+                start_index,
+                circle.segments as usize * 6,
+                PrimitiveType::Triangles,
+            );
+        }
+
+        if circle.show_line {
+            self.draw_circle_line(circle);
+        }
+    }
+
+    /// Line from a circle's center to its edge at `line_angle`
+    fn draw_circle_line(&mut self, circle: &Circle) {
+        let start_vertex = self.vertices.len() as u16;
+        let start_index = self.indices.len();
+
+        // Calculate end point on the circle edge
+        let end_x = circle.center.x + circle.line_angle.cos() * circle.radius;
+        let end_y = circle.center.y + circle.line_angle.sin() * circle.radius;
+
+        let line_color = [
+            circle.line_color.x,
+            circle.line_color.y,
+            circle.line_color.z,
+            circle.line_color.w,
+        ];
+
+        // Add vertices for the line (center and edge point)
+        self.vertices.push(Vertex {
+            pos: [circle.center.x, circle.center.y],
+            texcoord: [0.5, 0.5],
+            color: line_color,
+        });
+
+        self.vertices.push(Vertex {
+            pos: [end_x, end_y],
+            texcoord: [0.5, 0.5],
+            color: line_color,
+        });
+
+        // Add indices for the line
+        self.indices
+            .extend_from_slice(&[start_vertex, start_vertex + 1]);
+
+        self.add_batch_with_type(
+            self.texture_manager.get_white_texture(),
+            start_index,
+            2,
+            PrimitiveType::Lines,
+        );
+    }
+
+    pub fn draw_sprite(&mut self, sprite: &Sprite) {
+        let start_vertex = self.vertices.len() as u16;
+        let start_index = self.indices.len();
+
+        let texture = self.texture_manager.resolve_texture(&sprite.texture_name);
+
+        // Create 4 vertices for the sprite quad
+        let half_size = sprite.size * 0.5;
+        let cos_rot = sprite.rotation.cos();
+        let sin_rot = sprite.rotation.sin();
+
+        let local_positions = [
+            Vec2::new(-half_size.x, -half_size.y), // Top-left
+            Vec2::new(half_size.x, -half_size.y),  // Top-right
+            Vec2::new(half_size.x, half_size.y),   // Bottom-right
+            Vec2::new(-half_size.x, half_size.y),  // Bottom-left
+        ];
+
+        let uv = sprite.tiled_uv();
+        let mut uvs = [
+            Vec2::new(uv.x, uv.y),               // Top-left UV
+            Vec2::new(uv.x + uv.z, uv.y),        // Top-right UV
+            Vec2::new(uv.x + uv.z, uv.y + uv.w), // Bottom-right UV
+            Vec2::new(uv.x, uv.y + uv.w),        // Bottom-left UV
+        ];
+
+        // Apply flipping by swapping UV coordinates
+        if sprite.flip_x {
+            uvs.swap(0, 1); // Swap top-left with top-right
+            uvs.swap(2, 3); // Swap bottom-right with bottom-left
+        }
+        if sprite.flip_y {
+            uvs.swap(0, 3); // Swap top-left with bottom-left
+            uvs.swap(1, 2); // Swap top-right with bottom-right
         }
 
         let color = [
@@ -1370,7 +1634,123 @@ impl Renderer {
             start_vertex + 3,
         ];
         self.indices.extend_from_slice(&indices);
+
+        self.repeat_texture = sprite.is_tiled();
+        self.tint_mode = sprite.tint_mode;
+        self.add_batch(texture, start_index, 6);
+        self.repeat_texture = false;
+        self.tint_mode = TintMode::Multiply;
+    }
+
+    /// Textured rectangle without sprite state, `uv` is (u, v, width, height).
+    /// Follows `rect.rotation`; the quad is always filled, `outline_only` is ignored.
+    pub fn draw_textured_quad(&mut self, rect: &Quad, texture_name: &str, uv: Vec4, color: Vec4) {
+        let texture = self.texture_manager.resolve_texture(texture_name);
+
+        let start_vertex = self.vertices.len() as u16;
+        let start_index = self.indices.len();
+
+        let color = [color.x, color.y, color.z, color.w];
+
+        // Same corner/UV layout as draw_sprite
+        let texcoords = [
+            Vec2::new(uv.x, uv.y),
+            Vec2::new(uv.x + uv.z, uv.y),
+            Vec2::new(uv.x + uv.z, uv.y + uv.w),
+            Vec2::new(uv.x, uv.y + uv.w),
+        ];
+        for (pos, texcoord) in rect.corners().into_iter().zip(texcoords) {
+            self.vertices.push(Vertex {
+                pos: [pos.x, pos.y],
+                texcoord: [texcoord.x, texcoord.y],
+                color,
+            });
+        }
+
+        self.indices.extend_from_slice(&[
+            start_vertex,
+            start_vertex + 1,
+            start_vertex + 2,
+            start_vertex,
+            start_vertex + 2,
+            start_vertex + 3,
+        ]);
+        self.add_batch(texture, start_index, 6);
+    }
+
+    /// Fill the camera's view with a repeating layer offset by its scroll factor.
+    /// Uses the current draw order, so set a low sort key to keep it behind the scene.
+    pub fn draw_parallax(&mut self, layer: &ParallaxLayer, camera: &Camera2D) {
+        let (min, max) = camera.visible_aabb();
+        let uv = layer.uv_for_view(camera.position, min, max);
+        let rect = Quad::new(
+            (min.x + max.x) * 0.5,
+            (min.y + max.y) * 0.5,
+            max.x - min.x,
+            max.y - min.y,
+            layer.tint,
+        );
+
+        let previous_space = self.draw_space;
+        self.draw_space = DrawSpace::World;
+        self.repeat_texture = true;
+        self.draw_textured_quad(&rect, &layer.texture_name, uv, layer.tint);
+        self.repeat_texture = false;
+        self.draw_space = previous_space;
+    }
+
+    /// Fill the whole viewport behind the scene, regardless of camera position or zoom
+    pub fn draw_background(&mut self, spec: &BackgroundSpec) {
+        let (texture, top, bottom) = match spec {
+            BackgroundSpec::Color(color) => {
+                (self.texture_manager.get_white_texture(), *color, *color)
+            }
+            BackgroundSpec::VerticalGradient { top, bottom } => {
+                (self.texture_manager.get_white_texture(), *top, *bottom)
+            }
+            BackgroundSpec::Texture { texture_name, tint } => (
+                self.texture_manager.resolve_texture(texture_name),
+                *tint,
+                *tint,
+            ),
+        };
+
+        // Drawn in screen space with the lowest sort key so it's always behind
+        let previous_space = self.draw_space;
+        let previous_key = self.sort_key;
+        self.draw_space = DrawSpace::Screen;
+        self.sort_key = f32::NEG_INFINITY;
+
+        let start_vertex = self.vertices.len() as u16;
+        let start_index = self.indices.len();
+        let size = self.screen_size;
+
+        let corners = [
+            (Vec2::ZERO, Vec2::new(0.0, 0.0), top),
+            (Vec2::new(size.x, 0.0), Vec2::new(1.0, 0.0), top),
+            (size, Vec2::new(1.0, 1.0), bottom),
+            (Vec2::new(0.0, size.y), Vec2::new(0.0, 1.0), bottom),
+        ];
+        for (pos, uv, color) in corners {
+            self.vertices.push(Vertex {
+                pos: [pos.x, pos.y],
+                texcoord: [uv.x, uv.y],
+                color: [color.x, color.y, color.z, color.w],
+            });
+        }
+
+        self.indices.extend_from_slice(&[
+            start_vertex,
+            start_vertex + 1,
+            start_vertex + 2,
+            start_vertex,
+            start_vertex + 2,
+            start_vertex + 3,
+        ]);
         self.add_batch(texture, start_index, 6);
+
+        self.draw_space = previous_space;
+        self.sort_key = previous_key;
     }
 
     // ADD texture loading method:
@@ -1386,19 +1766,1140 @@ impl Renderer {
         self.texture_manager.get_texture(name)
     }
 
+    /// Size in pixels of a loaded texture
+    pub fn texture_size(&self, name: &str) -> Option<Vec2> {
+        self.texture_manager
+            .texture_size(name)
+            .map(|(width, height)| Vec2::new(width as f32, height as f32))
+    }
+
+    /// Resize `sprite` to fit inside `max_size` with the aspect ratio of the texture region
+    /// its UVs select. Returns false, leaving the sprite alone, if its texture isn't loaded.
+    pub fn fit_sprite_to(&self, sprite: &mut Sprite, max_size: Vec2) -> bool {
+        let Some(texture_size) = self.texture_size(&sprite.texture_name) else {
+            return false;
+        };
+        let source_size = texture_size * Vec2::new(sprite.uv.z, sprite.uv.w).abs();
+        sprite.fit_to(max_size, source_size);
+        true
+    }
+
+    /// Panic when drawing a texture that was never loaded instead of showing the
+    /// magenta checkerboard placeholder
+    pub fn set_strict_textures(&mut self, strict: bool) {
+        self.texture_manager.set_strict(strict);
+    }
+
+    pub fn is_strict_textures(&self) -> bool {
+        self.texture_manager.is_strict()
+    }
+
+    /// Free a texture's GPU memory. Returns false if no texture has that name.
+    pub fn unload_texture(&mut self, name: &str) -> bool {
+        match self.texture_manager.unload_texture(name) {
+            Some(texture) => {
+                self.invalidate_view(texture);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Free every loaded texture
+    pub fn unload_all_textures(&mut self) {
+        for texture in self.texture_manager.unload_all() {
+            self.invalidate_view(texture);
+        }
+    }
+
+    /// Destroy every cached texture view, e.g. on a scene change. Views are recreated
+    /// on demand the next time a texture is drawn.
+    pub fn clear_view_cache(&mut self) {
+        for view in self.take_cached_views() {
+            sg::destroy_view(view);
+        }
+    }
+
+    /// Empty the view cache, returning the views for the caller to destroy
+    fn take_cached_views(&mut self) -> Vec<sg::View> {
+        self.view_cache.drain().map(|(_, view)| view).collect()
+    }
+
+    /// View of `texture` from the cache, made with `make_view` the first time it's drawn
+    fn cached_view(
+        cache: &mut HashMap<u32, sg::View>,
+        texture: sg::Image,
+        make_view: impl FnOnce(sg::Image) -> sg::View,
+    ) -> sg::View {
+        *cache
+            .entry(texture.id)
+            .or_insert_with(|| make_view(texture))
+    }
+
+    /// Number of cached texture views
+    pub fn cached_view_count(&self) -> usize {
+        self.view_cache.len()
+    }
+
+    /// Drop the cached view of a destroyed image so a reused id can't pick it up
+    fn invalidate_view(&mut self, texture: sg::Image) {
+        if let Some(view) = self.view_cache.remove(&texture.id) {
+            sg::destroy_view(view);
+        }
+    }
+
+    /// Load a manifest file and every texture it lists
+    pub fn load_manifest(
+        &mut self,
+        path: &str,
+    ) -> Result<AssetLoadReport, Box<dyn std::error::Error>> {
+        let manifest = AssetManifest::load(path)?;
+        Ok(self.load_manifest_assets(&manifest))
+    }
+
+    /// Load every texture in a manifest, recording per-asset success or failure
+    pub fn load_manifest_assets(&mut self, manifest: &AssetManifest) -> AssetLoadReport {
+        let mut report = AssetLoadReport::default();
+        for (name, path) in &manifest.textures {
+            let full_path = manifest.resolve_path(path);
+            match self.load_texture(name, &full_path.to_string_lossy()) {
+                Ok(_) => report.loaded.push(name.clone()),
+                Err(e) => report.failed.push((name.clone(), e.to_string())),
+            }
+        }
+        report
+    }
+
     pub fn draw_particle(&mut self, particle: &Particle) {
-        let size = 4.0;
+        self.draw_particle_as(particle, &ParticleRenderMode::Quad);
+    }
+
+    /// Draw a particle with its size and color, fading out over its lifetime
+    pub fn draw_particle_as(&mut self, particle: &Particle, mode: &ParticleRenderMode) {
+        let size = particle.size;
         let alpha = particle.lifetime / particle.max_lifetime;
         let color = Vec4::new(particle.color.x, particle.color.y, particle.color.z, alpha);
 
         // Use center positioning
-        let quad = Quad::new(
-            particle.position.x, // Center X
-            particle.position.y, // Center Y
-            size,
-            size,
-            color,
+        let (x, y) = (particle.position.x, particle.position.y);
+        match mode {
+            ParticleRenderMode::Quad => self.draw_quad(&Quad::new(x, y, size, size, color)),
+            ParticleRenderMode::Circle => {
+                self.draw_circle(&Circle::new(x, y, size * 0.5, color));
+            }
+            ParticleRenderMode::Textured(texture_name) => self.draw_textured_quad(
+                &Quad::new(x, y, size, size, Vec4::ONE),
+                texture_name,
+                Vec4::new(0.0, 0.0, 1.0, 1.0),
+                color,
+            ),
+        }
+    }
+}
+
+/// Shader sources for one backend, every string is NUL terminated
+struct ShaderSources {
+    textured_vs: &'static str,
+    textured_fs: &'static str,
+    /// Textured fragment shader that adds the vertex color instead of multiplying
+    additive_fs: &'static str,
+    /// Untextured fragment shader drawing an anti-aliased disc inscribed in the UV square
+    sdf_circle_fs: &'static str,
+    color_vs: &'static str,
+    color_fs: &'static str,
+}
+
+/// HLSL shaders for Windows/D3D11
+const HLSL_SOURCES: ShaderSources = ShaderSources {
+    textured_vs: "
+    cbuffer uniforms : register(b0) {
+        float4x4 mvp;
+        float4 ambient;
+    };
+
+    struct vs_in {
+        float2 position : POSITION;
+        float2 texcoord : TEXCOORD;
+        float4 color    : COLOR;
+    };
+
+    struct vs_out {
+        float4 position : SV_Position;
+        float2 texcoord : TEXCOORD;
+        float4 color    : COLOR;
+        float4 ambient  : TEXCOORD1;
+    };
+
+    vs_out main(vs_in inp) {
+        vs_out outp;
+        outp.position = mul(mvp, float4(inp.position, 0.0, 1.0));
+        outp.texcoord = inp.texcoord;
+        outp.color = inp.color * ambient;
+        outp.ambient = ambient;
+        return outp;
+    }
+    \0",
+    textured_fs: "
+    Texture2D tex : register(t0);
+    SamplerState smp : register(s0);
+
+    struct ps_in {
+        float4 position : SV_Position;
+        float2 texcoord : TEXCOORD;
+        float4 color : COLOR;
+    };
+
+    float4 main(ps_in inp) : SV_Target0 {
+        float4 tex_color = tex.Sample(smp, inp.texcoord);
+        return tex_color * inp.color;
+    }
+    \0",
+    additive_fs: "
+    Texture2D tex : register(t0);
+    SamplerState smp : register(s0);
+
+    struct ps_in {
+        float4 position : SV_Position;
+        float2 texcoord : TEXCOORD;
+        float4 color : COLOR;
+        float4 ambient : TEXCOORD1;
+    };
+
+    float4 main(ps_in inp) : SV_Target0 {
+        float4 tex_color = tex.Sample(smp, inp.texcoord);
+        float3 rgb = tex_color.rgb * inp.ambient.rgb + inp.color.rgb;
+        return float4(min(rgb, 1.0), tex_color.a * inp.color.a);
+    }
+    \0",
+    sdf_circle_fs: "
+    struct ps_in {
+        float4 position : SV_Position;
+        float2 texcoord : TEXCOORD;
+        float4 color : COLOR;
+    };
+
+    float4 main(ps_in inp) : SV_Target0 {
+        float dist = length(inp.texcoord * 2.0 - 1.0);
+        float edge = max(fwidth(dist), 0.0001);
+        float coverage = 1.0 - smoothstep(1.0 - edge, 1.0, dist);
+        return float4(inp.color.rgb, inp.color.a * coverage);
+    }
+    \0",
+    color_vs: "
+    cbuffer uniforms : register(b0) {
+        float4x4 mvp;
+        float4 ambient;
+    };
+
+    struct vs_in {
+        float2 position : POSITION;
+        float2 texcoord : TEXCOORD;
+        float4 color    : COLOR;
+    };
+
+    struct vs_out {
+        float4 position : SV_Position;
+        float4 color    : COLOR;
+    };
+
+    vs_out main(vs_in inp) {
+        vs_out outp;
+        outp.position = mul(mvp, float4(inp.position, 0.0, 1.0));
+        outp.color = inp.color * ambient;
+        return outp;
+    }
+    \0",
+    color_fs: "
+    struct ps_in {
+        float4 position : SV_Position;
+        float4 color : COLOR;
+    };
+
+    float4 main(ps_in inp) : SV_Target0 {
+        return inp.color;
+    }
+    \0",
+};
+
+/// GLSL shaders for Linux/macOS/OpenGL
+const GLSL_SOURCES: ShaderSources = ShaderSources {
+    textured_vs: "
+    #version 330
+
+    uniform mat4 mvp;
+    uniform vec4 ambient;
+
+    layout(location = 0) in vec2 position;
+    layout(location = 1) in vec2 texcoord;
+    layout(location = 2) in vec4 color;
+
+    out vec2 uv;
+    out vec4 color0;
+    out vec4 ambient0;
+
+    void main() {
+        gl_Position = mvp * vec4(position, 0.0, 1.0);
+        uv = texcoord;
+        color0 = color * ambient;
+        ambient0 = ambient;
+    }
+    \0",
+    textured_fs: "
+    #version 330
+
+    uniform sampler2D tex;
+
+    in vec2 uv;
+    in vec4 color0;
+
+    out vec4 frag_color;
+
+    void main() {
+        frag_color = texture(tex, uv) * color0;
+    }
+    \0",
+    additive_fs: "
+    #version 330
+
+    uniform sampler2D tex;
+
+    in vec2 uv;
+    in vec4 color0;
+    in vec4 ambient0;
+
+    out vec4 frag_color;
+
+    void main() {
+        vec4 tex_color = texture(tex, uv);
+        vec3 rgb = tex_color.rgb * ambient0.rgb + color0.rgb;
+        frag_color = vec4(min(rgb, 1.0), tex_color.a * color0.a);
+    }
+    \0",
+    sdf_circle_fs: "
+    #version 330
+
+    in vec2 uv;
+    in vec4 color0;
+
+    out vec4 frag_color;
+
+    void main() {
+        float dist = length(uv * 2.0 - 1.0);
+        float edge = max(fwidth(dist), 0.0001);
+        float coverage = 1.0 - smoothstep(1.0 - edge, 1.0, dist);
+        frag_color = vec4(color0.rgb, color0.a * coverage);
+    }
+    \0",
+    color_vs: "
+    #version 330
+
+    uniform mat4 mvp;
+    uniform vec4 ambient;
+
+    layout(location = 0) in vec2 position;
+    layout(location = 1) in vec2 texcoord;
+    layout(location = 2) in vec4 color;
+
+    out vec4 color0;
+
+    void main() {
+        gl_Position = mvp * vec4(position, 0.0, 1.0);
+        color0 = color * ambient;
+    }
+    \0",
+    color_fs: "
+    #version 330
+
+    in vec4 color0;
+    out vec4 frag_color;
+
+    void main() {
+        frag_color = color0;
+    }
+    \0",
+};
+
+/// Sources for the backend this build targets
+fn shader_sources() -> &'static ShaderSources {
+    if cfg!(target_os = "windows") {
+        &HLSL_SOURCES
+    } else {
+        &GLSL_SOURCES
+    }
+}
+
+/// Build a shader using the engine's vertex layout and `Uniforms` block.
+/// `textured` adds the texture and sampler binding in slot 0.
+fn make_shader(vs_source: &str, fs_source: &str, textured: bool) -> sg::Shader {
+    let hlsl = cfg!(target_os = "windows");
+
+    let mut desc = sg::ShaderDesc {
+        vertex_func: sg::ShaderFunction {
+            source: vs_source.as_ptr() as *const i8,
+            ..Default::default()
+        },
+        fragment_func: sg::ShaderFunction {
+            source: fs_source.as_ptr() as *const i8,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    if hlsl {
+        for (attr, semantic) in desc
+            .attrs
+            .iter_mut()
+            .zip(["POSITION\0", "TEXCOORD\0", "COLOR\0"])
+        {
+            attr.hlsl_sem_name = semantic.as_ptr() as *const i8;
+            attr.hlsl_sem_index = 0;
+        }
+    }
+
+    desc.uniform_blocks[0] = sg::ShaderUniformBlock {
+        stage: sg::ShaderStage::Vertex,
+        size: mem::size_of::<Uniforms>() as u32,
+        hlsl_register_b_n: 0,
+        ..Default::default()
+    };
+    if !hlsl {
+        desc.uniform_blocks[0].glsl_uniforms[0] = sg::GlslShaderUniform {
+            glsl_name: "mvp\0".as_ptr() as *const i8,
+            _type: sg::UniformType::Mat4,
+            array_count: 1,
+        };
+        desc.uniform_blocks[0].glsl_uniforms[1] = sg::GlslShaderUniform {
+            glsl_name: c"ambient".as_ptr(),
+            _type: sg::UniformType::Float4,
+            array_count: 1,
+        };
+    }
+
+    if textured {
+        desc.views[0].texture = sg::ShaderTextureView {
+            stage: sg::ShaderStage::Fragment,
+            image_type: sg::ImageType::Dim2,
+            sample_type: sg::ImageSampleType::Float,
+            multisampled: false,
+            ..Default::default()
+        };
+        desc.samplers[0] = sg::ShaderSampler {
+            stage: sg::ShaderStage::Fragment,
+            sampler_type: sg::SamplerType::Filtering,
+            ..Default::default()
+        };
+        desc.texture_sampler_pairs[0] = sg::ShaderTextureSamplerPair {
+            stage: sg::ShaderStage::Fragment,
+            view_slot: 0,
+            sampler_slot: 0,
+            glsl_name: if hlsl {
+                std::ptr::null()
+            } else {
+                "tex\0".as_ptr() as *const i8
+            },
+        };
+    }
+
+    sg::make_shader(&desc)
+}
+
+/// Standard alpha blending used by every pipeline
+fn alpha_blend() -> sg::BlendState {
+    sg::BlendState {
+        enabled: true,
+        src_factor_rgb: sg::BlendFactor::SrcAlpha,
+        dst_factor_rgb: sg::BlendFactor::OneMinusSrcAlpha,
+        src_factor_alpha: sg::BlendFactor::One,
+        dst_factor_alpha: sg::BlendFactor::OneMinusSrcAlpha,
+        ..Default::default()
+    }
+}
+
+/// Pipeline for `Vertex` data with 16-bit indices and no depth testing
+fn make_pipeline(
+    shader: sg::Shader,
+    primitive_type: sg::PrimitiveType,
+    blend: sg::BlendState,
+) -> sg::Pipeline {
+    sg::make_pipeline(&pipeline_desc(shader, primitive_type, blend))
+}
+
+/// Descriptor behind `make_pipeline`, for pipelines that need extra tweaks
+pub(crate) fn pipeline_desc(
+    shader: sg::Shader,
+    primitive_type: sg::PrimitiveType,
+    blend: sg::BlendState,
+) -> sg::PipelineDesc {
+    let mut layout = sg::VertexLayoutState::default();
+    layout.attrs[0] = sg::VertexAttrState {
+        buffer_index: 0,
+        offset: 0,
+        format: sg::VertexFormat::Float2,
+    };
+    layout.attrs[1] = sg::VertexAttrState {
+        buffer_index: 0,
+        offset: 8,
+        format: sg::VertexFormat::Float2,
+    };
+    layout.attrs[2] = sg::VertexAttrState {
+        buffer_index: 0,
+        offset: 16,
+        format: sg::VertexFormat::Float4,
+    };
+    layout.buffers[0] = sg::VertexBufferLayoutState {
+        stride: mem::size_of::<Vertex>() as i32,
+        step_func: sg::VertexStep::PerVertex,
+        step_rate: 1,
+    };
+
+    let mut desc = sg::PipelineDesc {
+        shader,
+        layout,
+        index_type: sg::IndexType::Uint16,
+        primitive_type,
+        cull_mode: sg::CullMode::None,
+        depth: sg::DepthState {
+            write_enabled: false,
+            compare: sg::CompareFunc::Always,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    desc.colors[0].blend = blend;
+    desc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, SQRT_2};
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(
+            (actual - expected).length() < 1e-4,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn rotated_rect_aabb_unrotated_matches_the_rect() {
+        let (min, max) = rotated_rect_aabb(Vec2::new(10.0, 20.0), Vec2::new(40.0, 20.0), 0.0);
+        assert_near(min, Vec2::new(-10.0, 10.0));
+        assert_near(max, Vec2::new(30.0, 30.0));
+    }
+
+    #[test]
+    fn rotated_rect_aabb_quarter_turn_swaps_extents() {
+        let (min, max) = rotated_rect_aabb(Vec2::ZERO, Vec2::new(40.0, 20.0), FRAC_PI_2);
+        assert_near(min, Vec2::new(-10.0, -20.0));
+        assert_near(max, Vec2::new(10.0, 20.0));
+    }
+
+    #[test]
+    fn rotated_rect_aabb_eighth_turn_covers_the_corners() {
+        let (min, max) = rotated_rect_aabb(Vec2::ZERO, Vec2::new(10.0, 10.0), FRAC_PI_4);
+        let half_diagonal = 5.0 * SQRT_2;
+        assert_near(min, Vec2::splat(-half_diagonal));
+        assert_near(max, Vec2::splat(half_diagonal));
+    }
+
+    /// Renderer with fake loaded textures, enough for draws that never reach the GPU
+    fn renderer_with_textures(textures: &[(&str, u32)]) -> Renderer {
+        let mut renderer = Renderer::new();
+        for &(name, id) in textures {
+            renderer
+                .texture_manager
+                .insert_texture(name, sg::Image { id }, (16, 16));
+        }
+        renderer
+    }
+
+    #[test]
+    fn each_texture_run_gets_its_own_draw_range() {
+        let mut renderer = renderer_with_textures(&[("a", 1), ("b", 2)]);
+        let rect = Quad::new(0.0, 0.0, 10.0, 10.0, Vec4::ONE);
+        let uv = Vec4::new(0.0, 0.0, 1.0, 1.0);
+
+        renderer.draw_textured_quad(&rect, "a", uv, Vec4::ONE);
+        renderer.draw_textured_quad(&rect, "a", uv, Vec4::ONE);
+        renderer.draw_textured_quad(&rect, "b", uv, Vec4::ONE);
+
+        let ranges: Vec<_> = renderer
+            .batches
+            .iter()
+            .map(|batch| (batch.texture.id, batch.start_index, batch.index_count))
+            .collect();
+        assert_eq!(ranges, vec![(1, 0, 12), (2, 12, 6)]);
+    }
+
+    #[test]
+    fn lights_clear_each_frame_and_select_the_composite_path() {
+        let mut renderer = Renderer::new();
+        let camera = Camera2D::new();
+        let viewport = camera.viewport_size();
+        renderer.begin_frame(&camera);
+        assert!(!renderer.lighting.needs_render(viewport));
+
+        renderer.add_light(Light::new(Vec2::ZERO, 100.0, Vec4::ONE));
+        assert!(renderer.has_lights());
+        assert!(renderer.lighting.needs_render(viewport));
+
+        renderer.begin_frame(&camera);
+        assert!(!renderer.has_lights());
+        assert!(!renderer.lighting.needs_render(viewport));
+        assert!(!renderer.lighting.is_prepared());
+    }
+
+    #[test]
+    fn lighting_leaves_screen_space_draws_unlit_whatever_their_order() {
+        let mut renderer = Renderer::new();
+        let quad = Quad::new(0.0, 0.0, 10.0, 10.0, Vec4::ONE);
+
+        renderer.draw_background(&BackgroundSpec::Color(Vec4::ONE));
+        renderer.begin_ui();
+        renderer.draw_quad(&quad);
+        renderer.end_ui();
+        renderer.draw_quad(&quad);
+        renderer.set_sort_key(1.0);
+        renderer.begin_ui();
+        renderer.draw_quad(&quad);
+        renderer.end_ui();
+        renderer.set_sort_key(2.0);
+        renderer.draw_quad(&quad);
+
+        renderer.sort_batches();
+        let (lit, unlit) = renderer.lighting_passes();
+        // Background and world quads under the light buffer, both HUD quads on top
+        assert_eq!(lit, vec![0, 2, 4]);
+        assert_eq!(unlit, vec![1, 3]);
+    }
+
+    #[test]
+    fn additive_sprites_select_the_additive_pipeline() {
+        let mut renderer = renderer_with_textures(&[("glow", 3)]);
+        let sprite = Sprite::new().with_texture_name("glow".to_string());
+
+        renderer.draw_sprite(&sprite.clone().with_tint_mode(TintMode::Additive));
+        renderer.draw_sprite(&sprite);
+        renderer.draw_quad(&Quad::new(0.0, 0.0, 10.0, 10.0, Vec4::ONE));
+
+        let kinds: Vec<_> = renderer
+            .batches
+            .iter()
+            .map(|batch| renderer.pipeline_kind(batch))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                PipelineKind::Additive,
+                PipelineKind::Textured,
+                PipelineKind::Colored
+            ]
+        );
+    }
+
+    #[test]
+    fn textured_quad_batches_under_its_texture_and_follows_rotation() {
+        let mut renderer = renderer_with_textures(&[("tiles", 4)]);
+        let rect = Quad::new(0.0, 0.0, 20.0, 10.0, Vec4::ONE).with_rotation(FRAC_PI_2);
+        let uv = Vec4::new(0.5, 0.0, 0.5, 1.0);
+
+        renderer.draw_textured_quad(&rect, "tiles", uv, Vec4::ONE);
+
+        assert_eq!(renderer.batches.len(), 1);
+        assert_eq!(renderer.batches[0].texture.id, 4);
+        assert_eq!(
+            renderer.pipeline_kind(&renderer.batches[0]),
+            PipelineKind::Textured
+        );
+        // First corner is (min x, min y) before rotation, with the uv origin
+        let first = &renderer.vertices[0];
+        assert_near(Vec2::from(first.pos), Vec2::new(5.0, -10.0));
+        assert_eq!(first.texcoord, [0.5, 0.0]);
+    }
+
+    #[test]
+    fn same_texture_quads_share_one_batch() {
+        let mut renderer = Renderer::new();
+        for i in 0..5 {
+            renderer.draw_quad(&Quad::new(i as f32 * 20.0, 0.0, 10.0, 10.0, Vec4::ONE));
+        }
+
+        let stats = renderer.queued_stats();
+        assert_eq!(stats.batches, 1);
+        assert_eq!(stats.vertices, 20);
+        assert_eq!(stats.indices, 30);
+        assert_eq!(stats.draw_calls, 0);
+    }
+
+    #[test]
+    fn with_alpha_only_changes_the_w_channel() {
+        let color = Vec4::new(0.2, 0.4, 0.6, 1.0);
+        let quad = Quad::new(0.0, 0.0, 10.0, 10.0, color).with_alpha(0.25);
+        assert_eq!(quad.color, Vec4::new(0.2, 0.4, 0.6, 0.25));
+
+        let mut sprite = Sprite::new().with_color(color).with_alpha(0.5);
+        assert_eq!(sprite.color, Vec4::new(0.2, 0.4, 0.6, 0.5));
+
+        // Fading only moves alpha too, and stops at the target
+        sprite.start_fade(0.0, 1.0);
+        sprite.update_fade(0.3);
+        assert_eq!(sprite.color.truncate(), color.truncate());
+        sprite.update_fade(0.3);
+        assert_eq!(sprite.alpha(), 0.0);
+        assert!(!sprite.is_fading());
+    }
+
+    #[test]
+    fn keyed_draws_sort_back_to_front() {
+        let mut renderer = Renderer::new();
+        let quad = Quad::new(0.0, 0.0, 10.0, 10.0, Vec4::ONE);
+        for key in [2.0, 0.5, 1.0] {
+            renderer.set_sort_key(key);
+            renderer.draw_quad(&quad);
+        }
+
+        renderer.sort_batches();
+        let order: Vec<_> = renderer
+            .batches
+            .iter()
+            .map(|batch| (batch.sort_key, batch.start_index))
+            .collect();
+        assert_eq!(order, vec![(0.5, 6), (1.0, 12), (2.0, 0)]);
+    }
+
+    #[test]
+    fn background_covers_the_visible_area_wherever_the_camera_is() {
+        let mut camera = Camera2D::new();
+        camera.set_position(Vec2::new(500.0, -200.0));
+        camera.set_zoom(2.0);
+        let mut renderer = Renderer::new();
+        renderer.begin_frame(&camera);
+
+        renderer.draw_background(&BackgroundSpec::Color(Vec4::ONE));
+
+        assert_eq!(renderer.batches[0].space, DrawSpace::Screen);
+        let world: Vec<Vec2> = renderer
+            .vertices
+            .iter()
+            .map(|vertex| camera.screen_to_world(Vec2::from(vertex.pos)))
+            .collect();
+        let min = world.iter().fold(Vec2::MAX, |min, &p| min.min(p));
+        let max = world.iter().fold(Vec2::MIN, |max, &p| max.max(p));
+        let (view_min, view_max) = camera.visible_aabb();
+        assert!((min - view_min).abs().max_element() < 1e-2, "{min:?}");
+        assert!((max - view_max).abs().max_element() < 1e-2, "{max:?}");
+    }
+
+    #[test]
+    fn ui_quads_stay_in_the_screen_corner_whatever_the_camera() {
+        for position in [Vec2::ZERO, Vec2::new(-350.0, 1200.0)] {
+            let mut camera = Camera2D::new();
+            camera.set_position(position);
+            camera.set_zoom(3.0);
+            let mut renderer = Renderer::new();
+            renderer.begin_frame(&camera);
+
+            renderer.begin_ui();
+            renderer.draw_quad(&Quad::new(5.0, 5.0, 10.0, 10.0, Vec4::ONE));
+            renderer.end_ui();
+
+            let (_, screen_uniforms) = renderer.frame_uniforms(&mut camera, Vec4::ONE);
+            let mvp = Mat4::from_cols_array_2d(&screen_uniforms.mvp);
+            let corner = Vec2::from(renderer.vertices[0].pos);
+            let clip = mvp * Vec4::new(corner.x, corner.y, 0.0, 1.0);
+            // Top-left of normalized device coordinates
+            assert_near(Vec2::new(clip.x, clip.y), Vec2::new(-1.0, 1.0));
+        }
+    }
+
+    fn collider_aabb(collider: &Collider) -> (Vec2, Vec2) {
+        let extent = match collider.shape {
+            CollisionShape::Rectangle { width, height } => Vec2::new(width, height) * 0.5,
+            CollisionShape::Circle { radius } => Vec2::splat(radius),
+        };
+        (collider.position - extent, collider.position + extent)
+    }
+
+    #[test]
+    fn shape_colliders_have_the_shapes_bounds() {
+        let quad = Quad::new(10.0, 20.0, 30.0, 40.0, Vec4::ONE);
+        assert_eq!(collider_aabb(&Collider::from(&quad)), quad.aabb());
+
+        let circle = Circle::new(-5.0, 7.0, 12.0, Vec4::ONE);
+        assert_eq!(collider_aabb(&Collider::from(&circle)), circle.aabb());
+
+        let sprite = Sprite::new()
+            .with_position(Vec2::new(100.0, 50.0))
+            .with_size(Vec2::new(64.0, 32.0));
+        assert_eq!(
+            collider_aabb(&sprite.collider()),
+            (Vec2::new(68.0, 34.0), Vec2::new(132.0, 66.0))
+        );
+    }
+
+    #[test]
+    fn bordered_draws_produce_fill_and_border_geometry() {
+        let fill = Vec4::new(0.2, 0.2, 0.2, 1.0);
+        let border = Vec4::new(1.0, 1.0, 0.0, 1.0);
+        let mut renderer = Renderer::new();
+
+        renderer.draw_quad_bordered(&Quad::new(0.0, 0.0, 100.0, 40.0, fill), fill, border, 4.0);
+        // One fill quad and four edge quads
+        assert_eq!(renderer.vertices.len(), 20);
+        assert_eq!(renderer.indices.len(), 30);
+        let colors: Vec<[f32; 4]> = renderer.vertices.iter().map(|v| v.color).collect();
+        assert!(colors[..4].iter().all(|&c| c == fill.to_array()));
+        assert!(colors[4..].iter().all(|&c| c == border.to_array()));
+
+        let mut renderer = Renderer::new();
+        let circle = Circle::new(0.0, 0.0, 10.0, fill).with_segments(8);
+        renderer.draw_circle_bordered(&circle, fill, border, 2.0);
+        // The ring is an outer and inner vertex per segment, after the fill
+        let ring = &renderer.vertices[renderer.vertices.len() - 16..];
+        assert!(renderer.vertices.len() > 16);
+        assert!(ring.iter().all(|v| v.color == border.to_array()));
+        let radii: Vec<f32> = ring[..2]
+            .iter()
+            .map(|v| Vec2::from(v.pos).length())
+            .collect();
+        assert_eq!(radii, vec![10.0, 8.0]);
+    }
+
+    #[test]
+    fn auto_segments_follow_the_on_screen_radius() {
+        let small = Circle::new(0.0, 0.0, 2.0, Vec4::ONE);
+        let medium = Circle::new(0.0, 0.0, 20.0, Vec4::ONE);
+        let large = Circle::new(0.0, 0.0, 75.0, Vec4::ONE);
+
+        // About one segment per 8 pixels of circumference, clamped to 3..=64
+        assert_eq!(small.segment_count(1.0), 3);
+        assert_eq!(medium.segment_count(1.0), 16);
+        assert_eq!(medium.segment_count(2.0), 32);
+        assert_eq!(large.segment_count(1.0), 59);
+        assert_eq!(large.segment_count(4.0), 64);
+
+        // An explicit count ignores zoom
+        assert_eq!(large.with_segments(12).segment_count(4.0), 12);
+    }
+
+    #[test]
+    fn manifest_load_reports_each_entry() {
+        let dir = std::env::temp_dir().join(format!("rusclog_manifest_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("assets.json");
+        std::fs::write(
+            &path,
+            r#"{ "textures": { "player": "player.png", "missing": "missing.png" } }"#,
+        )
+        .unwrap();
+
+        // "player" is already resident, so only "missing" touches the filesystem
+        let mut renderer = renderer_with_textures(&[("player", 1)]);
+        let report = renderer.load_manifest(&path.to_string_lossy());
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let report = report.unwrap();
+        assert_eq!(report.total(), 2);
+        assert_eq!(report.loaded, vec!["player".to_string()]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "missing");
+        assert!(!report.all_loaded());
+    }
+
+    /// Vertices (position, texcoord, color), indices and batch count queued so far
+    fn geometry(renderer: &Renderer) -> (Vec<[f32; 8]>, Vec<u16>, usize) {
+        let vertices = renderer
+            .vertices
+            .iter()
+            .map(|v| {
+                let [x, y] = v.pos;
+                let [u, w] = v.texcoord;
+                let [r, g, b, a] = v.color;
+                [x, y, u, w, r, g, b, a]
+            })
+            .collect();
+        (vertices, renderer.indices.clone(), renderer.batches.len())
+    }
+
+    #[test]
+    fn bulk_draws_match_individual_draws() {
+        let quads = [
+            Quad::new(0.0, 0.0, 10.0, 20.0, Vec4::ONE),
+            Quad::new(30.0, -5.0, 4.0, 4.0, Vec4::new(1.0, 0.0, 0.0, 1.0)).with_outline(),
+            Quad::new(-12.0, 8.0, 6.0, 2.0, Vec4::new(0.0, 1.0, 0.0, 0.5)),
+        ];
+        let circles = [
+            Circle::new(0.0, 0.0, 5.0, Vec4::ONE).with_segments(8),
+            Circle::new(20.0, 20.0, 12.0, Vec4::new(0.0, 0.0, 1.0, 1.0)),
+        ];
+
+        let mut bulk = Renderer::new();
+        bulk.draw_quads(&quads);
+        bulk.draw_circles(&circles);
+
+        let mut individual = Renderer::new();
+        for quad in &quads {
+            individual.draw_quad(quad);
+        }
+        for circle in &circles {
+            individual.draw_circle(circle);
+        }
+
+        assert_eq!(geometry(&bulk), geometry(&individual));
+    }
+
+    #[test]
+    fn tiled_sprites_repeat_past_the_unit_uv_range() {
+        let mut renderer = renderer_with_textures(&[("tiles", 5)]);
+        let sprite = Sprite::new()
+            .with_texture_name("tiles".to_string())
+            .with_size(Vec2::new(300.0, 200.0))
+            .with_tiling(Vec2::new(3.0, 2.0));
+        renderer.draw_sprite(&sprite);
+
+        let max_uv = renderer
+            .vertices
+            .iter()
+            .fold(Vec2::ZERO, |max, v| max.max(Vec2::from(v.texcoord)));
+        assert_eq!(max_uv, Vec2::new(3.0, 2.0));
+        assert!(renderer.batches[0].repeat);
+
+        // Untiled sprites stay within 0..1 and clamp
+        renderer.draw_sprite(&sprite.with_tiling(Vec2::ONE));
+        assert!(renderer.vertices[4..]
+            .iter()
+            .all(|v| v.texcoord.iter().all(|&c| (0.0..=1.0).contains(&c))));
+        assert!(!renderer.batches[1].repeat);
+    }
+
+    #[test]
+    fn half_speed_parallax_scrolls_half_as_far() {
+        let layer = ParallaxLayer::new("hills", Vec2::splat(0.5), Vec2::splat(100.0));
+        let first_uv = |camera: &Camera2D| {
+            let mut renderer = renderer_with_textures(&[("hills", 6)]);
+            renderer.draw_parallax(&layer, camera);
+            Vec2::from(renderer.vertices[0].texcoord)
+        };
+
+        let mut camera = Camera2D::new();
+        let start = first_uv(&camera);
+        camera.set_position(Vec2::new(200.0, -100.0));
+        let moved = first_uv(&camera);
+
+        // A world-locked layer would shift by the full camera move, 2 and -1 tiles
+        assert_near(moved - start, Vec2::new(1.0, -0.5));
+        assert_eq!(
+            layer.scroll_offset(camera.position),
+            Vec2::new(100.0, -50.0)
+        );
+    }
+
+    #[test]
+    fn rotated_collider_outline_follows_the_rotation() {
+        let mut renderer = Renderer::new();
+        let collider = Collider::new_rect(5.0, 5.0, 20.0, 10.0);
+        renderer.draw_collider_rotated(&collider, Vec4::ONE, FRAC_PI_2);
+
+        let expected = [
+            Vec2::new(10.0, -5.0),
+            Vec2::new(10.0, 15.0),
+            Vec2::new(0.0, 15.0),
+            Vec2::new(0.0, -5.0),
+        ];
+        assert_eq!(renderer.vertices.len(), 4);
+        for (vertex, corner) in renderer.vertices.iter().zip(expected) {
+            assert_near(Vec2::from(vertex.pos), corner);
+        }
+        assert_eq!(
+            renderer.batches[0].primitive_type as u8,
+            PrimitiveType::Lines as u8
+        );
+    }
+
+    #[test]
+    fn force_unbatched_gives_each_draw_its_own_batch() {
+        let quads: Vec<Quad> = (0..5)
+            .map(|i| Quad::new(i as f32 * 20.0, 0.0, 10.0, 10.0, Vec4::ONE))
+            .collect();
+
+        let mut renderer = Renderer::new();
+        renderer.draw_quads(&quads);
+        assert_eq!(renderer.batches.len(), 1);
+
+        let mut renderer = Renderer::new();
+        renderer.set_force_unbatched(true);
+        renderer.draw_quads(&quads);
+        assert_eq!(renderer.batches.len(), quads.len());
+        assert!(renderer.batches.iter().all(|batch| batch.index_count == 6));
+    }
+
+    #[test]
+    fn half_progress_fills_the_left_half() {
+        let bar = Quad::new(100.0, 20.0, 200.0, 10.0, Vec4::ONE);
+        let fill_color = Vec4::new(0.0, 1.0, 0.0, 1.0);
+        let mut renderer = Renderer::new();
+        renderer.draw_progress_bar(&bar, 0.5, Vec4::ZERO, fill_color);
+
+        // Background first, then the fill
+        let (bar_min, bar_max) = bar.aabb();
+        let fill = &renderer.vertices[4..];
+        assert_eq!(fill.len(), 4);
+        assert!(fill.iter().all(|v| v.color == fill_color.to_array()));
+        let (fill_min, fill_max) = fill.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), v| (min.min(Vec2::from(v.pos)), max.max(Vec2::from(v.pos))),
+        );
+        assert_eq!(fill_min, bar_min);
+        assert_eq!(fill_max, Vec2::new(100.0, bar_max.y));
+
+        // Out of range progress is clamped
+        assert_eq!(bar.progress_fill(1.5, fill_color).size, bar.size);
+        assert_eq!(bar.progress_fill(-1.0, fill_color).size.x, 0.0);
+    }
+
+    #[test]
+    fn ambient_defaults_to_white_and_reaches_the_world_uniforms() {
+        let mut renderer = Renderer::new();
+        let mut camera = Camera2D::new();
+        assert_eq!(renderer.ambient_color(), Vec4::ONE);
+
+        let night = Vec4::new(0.2, 0.2, 0.4, 1.0);
+        renderer.set_ambient_color(night);
+        // As `flush` builds them
+        let (uniforms, screen_uniforms) =
+            renderer.frame_uniforms(&mut camera, renderer.ambient_color());
+        assert_eq!(uniforms.ambient, night.to_array());
+        // Screen-space draws are never darkened
+        assert_eq!(screen_uniforms.ambient, [1.0; 4]);
+    }
+
+    #[test]
+    fn view_bounds_from_begin_frame_are_available_while_drawing() {
+        let mut camera = Camera2D::new();
+        camera.set_position(Vec2::new(1000.0, 500.0));
+        camera.set_zoom(2.0);
+        let mut renderer = Renderer::new();
+        // Nothing is culled before the first frame
+        assert!(renderer.is_in_view(Vec2::splat(-1e6), Vec2::splat(-1e6)));
+
+        renderer.begin_frame(&camera);
+        renderer.draw_quad(&Quad::new(1000.0, 500.0, 10.0, 10.0, Vec4::ONE));
+        // 800x600 viewport at zoom 2
+        assert_eq!(
+            renderer.view_bounds(),
+            (Vec2::new(800.0, 350.0), Vec2::new(1200.0, 650.0))
+        );
+        assert!(renderer.is_in_view(Vec2::new(1190.0, 640.0), Vec2::new(1210.0, 660.0)));
+        assert!(!renderer.is_in_view(Vec2::ZERO, Vec2::splat(10.0)));
+    }
+
+    #[test]
+    fn fitting_a_wide_texture_into_a_square_keeps_its_aspect() {
+        let mut renderer = Renderer::new();
+        renderer
+            .texture_manager
+            .insert_texture("banner", sg::Image { id: 9 }, (64, 32));
+
+        let mut sprite = Sprite::new().with_texture_name("banner".to_string());
+        assert!(renderer.fit_sprite_to(&mut sprite, Vec2::splat(100.0)));
+        assert_eq!(sprite.size, Vec2::new(100.0, 50.0));
+
+        let mut unloaded = Sprite::new().with_texture_name("missing".to_string());
+        let size = unloaded.size;
+        assert!(!renderer.fit_sprite_to(&mut unloaded, Vec2::splat(100.0)));
+        assert_eq!(unloaded.size, size);
+    }
+
+    #[test]
+    fn circle_particles_take_the_circle_path() {
+        let particle = Particle {
+            position: Vec2::new(30.0, -10.0),
+            velocity: Vec2::ZERO,
+            lifetime: 0.5,
+            max_lifetime: 1.0,
+            color: Vec4::new(1.0, 0.5, 0.0, 1.0),
+            size: 12.0,
+        };
+
+        let mut quad = Renderer::new();
+        quad.draw_particle_as(&particle, &ParticleRenderMode::Quad);
+        assert_eq!(quad.vertices.len(), 4);
+
+        let mut circle = Renderer::new();
+        circle.draw_particle_as(&particle, &ParticleRenderMode::Circle);
+        // Diameter is the particle size, alpha fades with the remaining lifetime
+        let faded = Vec4::new(1.0, 0.5, 0.0, 0.5);
+        let mut expected = Renderer::new();
+        expected.draw_circle(&Circle::new(30.0, -10.0, 6.0, faded));
+        assert_eq!(geometry(&circle), geometry(&expected));
+    }
+
+    #[test]
+    fn draw_all_matches_drawing_each_item() {
+        let quad = Quad::new(0.0, 0.0, 10.0, 10.0, Vec4::ONE);
+        let circle = Circle::new(40.0, 0.0, 8.0, Vec4::new(1.0, 0.0, 0.0, 1.0));
+        let sprite = Sprite::new()
+            .with_texture_name("player".to_string())
+            .with_position(Vec2::new(-30.0, 5.0));
+
+        let mut all = renderer_with_textures(&[("player", 2)]);
+        all.draw_all(&[&quad, &sprite, &circle, &quad]);
+
+        let mut individual = renderer_with_textures(&[("player", 2)]);
+        individual.draw_quad(&quad);
+        individual.draw_sprite(&sprite);
+        individual.draw_circle(&circle);
+        individual.draw_quad(&quad);
+
+        assert_eq!(geometry(&all), geometry(&individual));
+    }
+
+    #[test]
+    fn cleared_view_cache_recreates_views_on_the_next_draw() {
+        let mut renderer = Renderer::new();
+        let made = std::cell::Cell::new(0);
+        let view_of = |renderer: &mut Renderer, id| {
+            Renderer::cached_view(&mut renderer.view_cache, sg::Image { id }, |image| {
+                made.set(made.get() + 1);
+                sg::View { id: image.id * 10 }
+            })
+            .id
+        };
+
+        assert_eq!(view_of(&mut renderer, 1), 10);
+        assert_eq!(view_of(&mut renderer, 2), 20);
+        assert_eq!(view_of(&mut renderer, 1), 10);
+        assert_eq!(renderer.cached_view_count(), 2);
+
+        assert_eq!(renderer.take_cached_views().len(), 2);
+        assert_eq!(renderer.cached_view_count(), 0);
+        assert_eq!(view_of(&mut renderer, 1), 10);
+        assert_eq!(renderer.cached_view_count(), 1);
+        assert_eq!(made.get(), 3);
+    }
+
+    #[test]
+    fn smooth_circles_use_the_sdf_material() {
+        let mut renderer = Renderer::new();
+        let circle = Circle::new(0.0, 0.0, 10.0, Vec4::ONE);
+        renderer.draw_circle(&circle.with_smooth_edge(true));
+        renderer.draw_circle(&circle);
+        renderer.draw_circle(&circle.with_smooth_edge(true).with_outline());
+
+        let kinds: Vec<_> = renderer
+            .batches
+            .iter()
+            .map(|batch| (batch.material, renderer.pipeline_kind(batch)))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (Material::SdfCircle, PipelineKind::SdfCircle),
+                (Material::Default, PipelineKind::Colored),
+                // Outlines are always lines
+                (Material::Default, PipelineKind::Lines),
+            ]
         );
-        self.draw_quad(&quad);
     }
 }
@@ -11,11 +11,52 @@ pub struct InputManager {
     mouse_buttons_pressed: [bool; 8],
     mouse_buttons_released: [bool; 8],
     mouse_wheel: f32,
+    mouse_captured: bool,
 
     previous_keys:[bool; 512],
     previous_mouse_buttons: [bool; 8]
 }
 
+/// Frozen copy of one frame's input, see `InputManager::snapshot`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InputSnapshot {
+    pub keys_down: [bool; 512],
+    pub keys_pressed: [bool; 512],
+    pub keys_released: [bool; 512],
+    pub mouse_position: Vec2,
+    pub mouse_buttons_down: [bool; 8],
+    pub mouse_buttons_pressed: [bool; 8],
+    pub mouse_buttons_released: [bool; 8],
+    pub mouse_wheel: f32,
+    pub mouse_captured: bool,
+}
+
+impl InputSnapshot {
+    pub fn is_key_down(&self, key: sapp::Keycode) -> bool {
+        self.keys_down.get(key as usize).copied().unwrap_or(false)
+    }
+
+    pub fn is_key_pressed(&self, key: sapp::Keycode) -> bool {
+        self.keys_pressed.get(key as usize).copied().unwrap_or(false)
+    }
+
+    pub fn is_key_released(&self, key: sapp::Keycode) -> bool {
+        self.keys_released.get(key as usize).copied().unwrap_or(false)
+    }
+
+    pub fn is_mouse_button_down(&self, button: sapp::Mousebutton) -> bool {
+        self.mouse_buttons_down.get(button as usize).copied().unwrap_or(false)
+    }
+
+    pub fn is_mouse_button_pressed(&self, button: sapp::Mousebutton) -> bool {
+        self.mouse_buttons_pressed.get(button as usize).copied().unwrap_or(false)
+    }
+
+    pub fn is_mouse_button_released(&self, button: sapp::Mousebutton) -> bool {
+        self.mouse_buttons_released.get(button as usize).copied().unwrap_or(false)
+    }
+}
+
 /// Implementation for engine
 impl InputManager {
     pub fn new() -> Self {
@@ -28,6 +69,7 @@ impl InputManager {
             mouse_buttons_pressed: [false; 8],
             mouse_buttons_released: [false; 8],
             mouse_wheel: 0.0,
+            mouse_captured: false,
             previous_keys: [false; 512],
             previous_mouse_buttons: [false; 8],
         }
@@ -44,10 +86,35 @@ impl InputManager {
         self.mouse_buttons_pressed.fill(false);
         self.mouse_buttons_released.fill(false);
         self.mouse_wheel = 0.0;
+        self.mouse_captured = false;
     }
 
     pub fn handle_key_down(&mut self, key: sapp::Keycode) {        
-        let key_idx = key as usize;
+        self.handle_key_down_index(key as usize);
+    }
+
+    pub fn handle_key_up(&mut self, key: sapp::Keycode) {
+        self.handle_key_up_index(key as usize);
+    }
+
+    pub fn handle_mouse_move(&mut self, x: f32, y: f32) {
+        self.mouse_position = Vec2::new(x, y);
+    }
+
+    pub fn handle_mouse_button_down(&mut self, button: sapp::Mousebutton) {
+        self.handle_mouse_button_down_index(button as usize);
+    }
+
+    pub fn handle_mouse_button_up(&mut self, button: sapp::Mousebutton) {
+        self.handle_mouse_button_up_index(button as usize);
+    }
+
+    pub fn handle_mouse_wheel(&mut self, delta: f32) {
+        self.mouse_wheel += delta; // Accumulate wheel movement this frame
+    }
+
+    /// Raw key index versions, used for replaying recorded input
+    pub(crate) fn handle_key_down_index(&mut self, key_idx: usize) {
         if key_idx < self.keys_down.len() {
             if !self.previous_keys[key_idx] && !self.keys_down[key_idx] {
                 self.keys_pressed[key_idx] = true;
@@ -56,8 +123,7 @@ impl InputManager {
         }
     }
 
-    pub fn handle_key_up(&mut self, key: sapp::Keycode) {
-        let key_idx = key as usize;
+    pub(crate) fn handle_key_up_index(&mut self, key_idx: usize) {
         if key_idx < self.keys_down.len() {
             if self.keys_down[key_idx] {
                 self.keys_released[key_idx] = true;
@@ -66,12 +132,7 @@ impl InputManager {
         }
     }
 
-    pub fn handle_mouse_move(&mut self, x: f32, y: f32) {
-        self.mouse_position = Vec2::new(x, y);
-    }
-
-    pub fn handle_mouse_button_down(&mut self, button: sapp::Mousebutton) {
-        let btn_idx = button as usize;
+    pub(crate) fn handle_mouse_button_down_index(&mut self, btn_idx: usize) {
         if btn_idx < self.mouse_buttons_down.len() {
             if !self.previous_mouse_buttons[btn_idx] && !self.mouse_buttons_down[btn_idx] {
                 self.mouse_buttons_pressed[btn_idx] = true;
@@ -80,8 +141,7 @@ impl InputManager {
         }
     }
 
-    pub fn handle_mouse_button_up(&mut self, button: sapp::Mousebutton) {
-        let btn_idx = button as usize;
+    pub(crate) fn handle_mouse_button_up_index(&mut self, btn_idx: usize) {
         if btn_idx < self.mouse_buttons_down.len() {
             if self.mouse_buttons_down[btn_idx] {
                 self.mouse_buttons_released[btn_idx] = true;
@@ -89,10 +149,6 @@ impl InputManager {
             self.mouse_buttons_down[btn_idx] = false;
         }
     }
-
-    pub fn handle_mouse_wheel(&mut self, delta: f32) {
-        self.mouse_wheel += delta; // Accumulate wheel movement this frame
-    }
 }
 
 /// Public functions for Game interface
@@ -135,4 +191,84 @@ impl InputManager {
     pub fn mouse_wheel_delta(&self) -> f32 {
         self.mouse_wheel
     }
-}
\ No newline at end of file
+
+    /// Mark the mouse as handled by UI this frame so game-world code can skip it,
+    /// usually from `Game::update_ui`. Cleared at the start of every frame.
+    pub fn set_mouse_captured(&mut self, captured: bool) {
+        self.mouse_captured = captured;
+    }
+
+    pub fn is_mouse_captured(&self) -> bool {
+        self.mouse_captured
+    }
+
+    /// Copy of the current input state that later `new_frame` and event calls don't change,
+    /// e.g. for several fixed updates sharing one frame's input or for recording
+    pub fn snapshot(&self) -> InputSnapshot {
+        InputSnapshot {
+            keys_down: self.keys_down,
+            keys_pressed: self.keys_pressed,
+            keys_released: self.keys_released,
+            mouse_position: self.mouse_position,
+            mouse_buttons_down: self.mouse_buttons_down,
+            mouse_buttons_pressed: self.mouse_buttons_pressed,
+            mouse_buttons_released: self.mouse_buttons_released,
+            mouse_wheel: self.mouse_wheel,
+            mouse_captured: self.mouse_captured,
+        }
+    }
+
+    /// Vibrate a gamepad with `low`/`high` motor strengths in 0..=1 for `duration` seconds.
+    /// The sokol backend has no gamepad support yet, so this is always a no-op that returns
+    /// false; games can call it unconditionally (e.g. on hard impacts) and get rumble once
+    /// a backend provides it.
+    pub fn set_rumble(&self, _gamepad_idx: usize, _low: f32, _high: f32, _duration: f32) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_press_is_reported_until_the_next_frame() {
+        let mut input = InputManager::new();
+        input.handle_mouse_button_down(sapp::Mousebutton::Left);
+        input.set_mouse_captured(true);
+
+        // The press still registers, world code sees the capture and skips it
+        assert!(input.is_mouse_button_pressed(sapp::Mousebutton::Left));
+        assert!(input.is_mouse_captured());
+        let spawns =
+            input.is_mouse_button_pressed(sapp::Mousebutton::Left) && !input.is_mouse_captured();
+        assert!(!spawns);
+
+        input.new_frame();
+        assert!(!input.is_mouse_captured());
+    }
+
+    #[test]
+    fn rumble_without_a_gamepad_is_a_no_op() {
+        let input = InputManager::new();
+        assert!(!input.set_rumble(0, 1.0, 1.0, 0.5));
+        assert!(!input.set_rumble(7, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn snapshot_keeps_its_state_after_new_frame() {
+        let mut input = InputManager::new();
+        input.handle_key_down(sapp::Keycode::Space);
+        input.handle_mouse_button_down(sapp::Mousebutton::Left);
+
+        let snapshot = input.snapshot();
+        input.new_frame();
+        input.handle_key_up(sapp::Keycode::Space);
+
+        assert!(!input.is_key_pressed(sapp::Keycode::Space));
+        assert!(snapshot.is_key_pressed(sapp::Keycode::Space));
+        assert!(snapshot.is_key_down(sapp::Keycode::Space));
+        assert!(!snapshot.is_key_released(sapp::Keycode::Space));
+        assert!(snapshot.is_mouse_button_pressed(sapp::Mousebutton::Left));
+    }
+}
@@ -0,0 +1,348 @@
+use glam::{Mat4, Vec2, Vec4};
+use sokol::gfx as sg;
+use std::mem;
+
+use crate::engine::graphics::{pipeline_desc, Uniforms, Vertex};
+
+/// Triangles in the fan approximating each light
+const LIGHT_SEGMENTS: u16 = 32;
+
+/// Unshadowed radial point light, brightest at `position` and fading to nothing at `radius`
+#[derive(Clone, Copy, Debug)]
+pub struct Light {
+    pub position: Vec2,
+    pub radius: f32,
+    pub color: Vec4,
+    pub intensity: f32,
+}
+
+impl Light {
+    pub fn new(position: Vec2, radius: f32, color: Vec4) -> Self {
+        Self {
+            position,
+            radius,
+            color,
+            intensity: 1.0,
+        }
+    }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Color added to the light buffer at the center
+    fn center_color(&self) -> [f32; 4] {
+        let rgb = self.color.truncate() * self.color.w * self.intensity.max(0.0);
+        [rgb.x, rgb.y, rgb.z, 1.0]
+    }
+}
+
+/// Offscreen light buffer: lights are added into it, then it is multiplied over the scene
+pub(crate) struct LightBuffer {
+    lights: Vec<Light>,
+    /// Additive blending into the light buffer
+    light_pipeline: sg::Pipeline,
+    /// Multiplies the light buffer over the scene
+    composite_pipeline: sg::Pipeline,
+    bind: sg::Bindings,
+    vbuf_size: usize,
+    ibuf_size: usize,
+    image: sg::Image,
+    attachment_view: sg::View,
+    texture_view: sg::View,
+    size: (i32, i32),
+    /// Index range of the fullscreen composite quad in this frame's buffers
+    composite_indices: Option<(usize, usize)>,
+}
+
+impl LightBuffer {
+    pub fn new() -> Self {
+        Self {
+            lights: Vec::new(),
+            light_pipeline: sg::Pipeline::default(),
+            composite_pipeline: sg::Pipeline::default(),
+            bind: sg::Bindings::default(),
+            vbuf_size: 0,
+            ibuf_size: 0,
+            image: sg::Image::default(),
+            attachment_view: sg::View::default(),
+            texture_view: sg::View::default(),
+            size: (0, 0),
+            composite_indices: None,
+        }
+    }
+
+    pub fn init(&mut self, colored_shader: sg::Shader, textured_shader: sg::Shader) {
+        let additive = sg::BlendState {
+            enabled: true,
+            src_factor_rgb: sg::BlendFactor::One,
+            dst_factor_rgb: sg::BlendFactor::One,
+            src_factor_alpha: sg::BlendFactor::One,
+            dst_factor_alpha: sg::BlendFactor::One,
+            ..Default::default()
+        };
+        let mut light_desc = pipeline_desc(colored_shader, sg::PrimitiveType::Triangles, additive);
+        // The light buffer has no depth attachment and no MSAA
+        light_desc.depth.pixel_format = sg::PixelFormat::None;
+        light_desc.colors[0].pixel_format = sg::PixelFormat::Rgba8;
+        light_desc.sample_count = 1;
+        self.light_pipeline = sg::make_pipeline(&light_desc);
+
+        let multiply = sg::BlendState {
+            enabled: true,
+            src_factor_rgb: sg::BlendFactor::DstColor,
+            dst_factor_rgb: sg::BlendFactor::Zero,
+            src_factor_alpha: sg::BlendFactor::Zero,
+            dst_factor_alpha: sg::BlendFactor::One,
+            ..Default::default()
+        };
+        self.composite_pipeline = sg::make_pipeline(&pipeline_desc(
+            textured_shader,
+            sg::PrimitiveType::Triangles,
+            multiply,
+        ));
+    }
+
+    pub fn add(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// Drop this frame's lights
+    pub fn clear(&mut self) {
+        self.lights.clear();
+        self.composite_indices = None;
+    }
+
+    /// True once `render` has filled the buffer this frame
+    pub fn is_prepared(&self) -> bool {
+        self.composite_indices.is_some()
+    }
+
+    /// Whether `render` has anything to do for `viewport`: there are lights and the
+    /// viewport isn't empty. When false the frame flushes without the composite.
+    pub fn needs_render(&self, viewport: Vec2) -> bool {
+        !self.lights.is_empty() && viewport.x >= 1.0 && viewport.y >= 1.0
+    }
+
+    /// Render the lights into the light buffer in its own pass, cleared to `ambient`.
+    /// Must run outside any other pass. Does nothing without lights.
+    pub fn render(&mut self, view_proj: Mat4, viewport: Vec2, ambient: Vec4) {
+        if !self.needs_render(viewport) {
+            return;
+        }
+        self.ensure_target(viewport.x as i32, viewport.y as i32);
+
+        let (vertices, indices, composite_start) = self.build_geometry(viewport);
+        self.upload(&vertices, &indices);
+
+        let mut action = sg::PassAction::default();
+        action.colors[0] = sg::ColorAttachmentAction {
+            load_action: sg::LoadAction::Clear,
+            clear_value: sg::Color {
+                r: ambient.x,
+                g: ambient.y,
+                b: ambient.z,
+                a: 1.0,
+            },
+            ..Default::default()
+        };
+        let mut attachments = sg::Attachments::default();
+        attachments.colors[0] = self.attachment_view;
+
+        sg::begin_pass(&sg::Pass {
+            action,
+            attachments,
+            ..Default::default()
+        });
+        sg::apply_pipeline(self.light_pipeline);
+        sg::apply_bindings(&self.bind);
+        let uniforms = Uniforms {
+            mvp: view_proj.to_cols_array_2d(),
+            ambient: [1.0; 4],
+        };
+        sg::apply_uniforms(
+            0,
+            &sg::Range {
+                ptr: &uniforms as *const _ as *const _,
+                size: mem::size_of::<Uniforms>(),
+            },
+        );
+        sg::draw(0, composite_start, 1);
+        sg::end_pass();
+
+        self.composite_indices = Some((composite_start, indices.len() - composite_start));
+    }
+
+    /// Multiply the light buffer over everything drawn so far in the current pass.
+    /// `screen_uniforms` maps screen pixels to clip space.
+    pub fn composite(&self, screen_uniforms: &Uniforms, sampler: sg::Sampler) -> bool {
+        let Some((start, count)) = self.composite_indices else {
+            return false;
+        };
+
+        let mut bind = self.bind;
+        bind.views[0] = self.texture_view;
+        bind.samplers[0] = sampler;
+
+        sg::apply_pipeline(self.composite_pipeline);
+        sg::apply_bindings(&bind);
+        sg::apply_uniforms(
+            0,
+            &sg::Range {
+                ptr: screen_uniforms as *const _ as *const _,
+                size: mem::size_of::<Uniforms>(),
+            },
+        );
+        sg::draw(start, count, 1);
+        true
+    }
+
+    /// One triangle fan per light followed by the screen-sized composite quad.
+    /// Returns the index where the composite quad starts.
+    fn build_geometry(&self, viewport: Vec2) -> (Vec<Vertex>, Vec<u16>, usize) {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        for light in &self.lights {
+            let center = vertices.len() as u16;
+            vertices.push(Vertex {
+                pos: light.position.to_array(),
+                texcoord: [0.0, 0.0],
+                color: light.center_color(),
+            });
+            for i in 0..LIGHT_SEGMENTS {
+                let angle = i as f32 / LIGHT_SEGMENTS as f32 * std::f32::consts::TAU;
+                let rim = light.position + Vec2::from_angle(angle) * light.radius;
+                vertices.push(Vertex {
+                    pos: rim.to_array(),
+                    texcoord: [0.0, 0.0],
+                    color: [0.0, 0.0, 0.0, 1.0],
+                });
+                let next = (i + 1) % LIGHT_SEGMENTS;
+                indices.extend_from_slice(&[center, center + 1 + i, center + 1 + next]);
+            }
+        }
+
+        // Render targets are stored bottom-up on OpenGL, so flip V there
+        let (v_top, v_bottom) = if cfg!(target_os = "windows") {
+            (0.0, 1.0)
+        } else {
+            (1.0, 0.0)
+        };
+        let composite_start = indices.len();
+        let first = vertices.len() as u16;
+        let corners = [
+            (Vec2::ZERO, [0.0, v_top]),
+            (Vec2::new(viewport.x, 0.0), [1.0, v_top]),
+            (viewport, [1.0, v_bottom]),
+            (Vec2::new(0.0, viewport.y), [0.0, v_bottom]),
+        ];
+        for (pos, texcoord) in corners {
+            vertices.push(Vertex {
+                pos: pos.to_array(),
+                texcoord,
+                color: [1.0; 4],
+            });
+        }
+        indices.extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+
+        (vertices, indices, composite_start)
+    }
+
+    /// Recreate the buffer image when the viewport size changes
+    fn ensure_target(&mut self, width: i32, height: i32) {
+        if self.size == (width, height) {
+            return;
+        }
+        if self.size != (0, 0) {
+            sg::destroy_view(self.attachment_view);
+            sg::destroy_view(self.texture_view);
+            sg::destroy_image(self.image);
+        }
+
+        self.image = sg::make_image(&sg::ImageDesc {
+            usage: sg::ImageUsage {
+                color_attachment: true,
+                ..Default::default()
+            },
+            width,
+            height,
+            pixel_format: sg::PixelFormat::Rgba8,
+            sample_count: 1,
+            ..Default::default()
+        });
+        self.attachment_view = sg::make_view(&sg::ViewDesc {
+            color_attachment: sg::ImageViewDesc {
+                image: self.image,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        self.texture_view = sg::make_view(&sg::ViewDesc {
+            texture: sg::TextureViewDesc {
+                image: self.image,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        self.size = (width, height);
+    }
+
+    /// Grow the light vertex/index buffers as needed and upload this frame's geometry
+    fn upload(&mut self, vertices: &[Vertex], indices: &[u16]) {
+        let vertex_bytes = mem::size_of_val(vertices);
+        let index_bytes = mem::size_of_val(indices);
+
+        if vertex_bytes > self.vbuf_size {
+            if self.vbuf_size > 0 {
+                sg::destroy_buffer(self.bind.vertex_buffers[0]);
+            }
+            self.vbuf_size = vertex_bytes.next_power_of_two();
+            self.bind.vertex_buffers[0] = sg::make_buffer(&sg::BufferDesc {
+                size: self.vbuf_size,
+                usage: sg::BufferUsage {
+                    vertex_buffer: true,
+                    stream_update: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        }
+
+        if index_bytes > self.ibuf_size {
+            if self.ibuf_size > 0 {
+                sg::destroy_buffer(self.bind.index_buffer);
+            }
+            self.ibuf_size = index_bytes.next_power_of_two();
+            self.bind.index_buffer = sg::make_buffer(&sg::BufferDesc {
+                size: self.ibuf_size,
+                usage: sg::BufferUsage {
+                    index_buffer: true,
+                    stream_update: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        }
+
+        sg::update_buffer(
+            self.bind.vertex_buffers[0],
+            &sg::Range {
+                ptr: vertices.as_ptr() as *const _,
+                size: vertex_bytes,
+            },
+        );
+        sg::update_buffer(
+            self.bind.index_buffer,
+            &sg::Range {
+                ptr: indices.as_ptr() as *const _,
+                size: index_bytes,
+            },
+        );
+    }
+}
@@ -1,31 +1,46 @@
 pub mod animation;
 pub mod app;
+pub mod assets;
+pub mod blackboard;
 pub mod camera;
 pub mod collision;
 pub mod debug;
 pub mod graphics;
 pub mod input;
+pub mod lighting;
 pub mod particle;
 pub mod physics;
+pub mod random;
+pub mod replay;
+pub mod settings;
 pub mod text;
 pub mod texture;
+pub mod transition;
 
 use crate::engine::physics_world::PhysicsWorld;
+use crate::engine::rigid_body::{BodyId, BodyType};
+use glam::Vec2;
 
 pub use animation::*;
 pub use app::*;
+pub use assets::*;
+pub use blackboard::*;
 pub use camera::*;
 pub use collision::*;
 pub use debug::*;
-use glam::Vec4;
 pub use graphics::*;
 pub use input::*;
+pub use lighting::*;
 pub use particle::*;
 pub use physics::*;
+pub use random::*;
+pub use replay::*;
+pub use settings::*;
 use sokol::gfx as sg;
 use std::collections::HashMap;
 pub use text::*;
 pub use texture::*;
+pub use transition::*;
 
 /// Game window configuration
 /// Implemented with builder
@@ -37,6 +52,9 @@ pub struct GameConfig {
     pub background_color: sg::Color,
     pub sample_count: i32,
     pub high_dpi: bool,
+    pub resizable: bool,
+    /// Smallest viewport the camera is given, in pixels
+    pub min_size: Option<(i32, i32)>,
 }
 
 impl Default for GameConfig {
@@ -54,6 +72,8 @@ impl Default for GameConfig {
             },
             sample_count: 1,
             high_dpi: false,
+            resizable: true,
+            min_size: None,
         }
     }
 }
@@ -91,6 +111,28 @@ impl GameConfig {
         self.high_dpi = high_dpi;
         self
     }
+
+    /// Hide window resizes from the game: `handle_event` never receives `Resized` events.
+    /// sokol_app has no option to lock the OS window size, so the camera viewport still
+    /// follows the framebuffer to keep the image from stretching.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Keep at least this many world units visible when the window shrinks below it: the
+    /// viewport still matches the framebuffer, but resizes set the camera zoom to
+    /// `min(framebuffer / min_size, 1)`. sokol_app can't enforce a minimum OS window size.
+    pub fn with_min_size(mut self, width: i32, height: i32) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    /// Override defaults with user settings loaded from disk
+    pub fn with_settings(mut self, settings: &Settings) -> Self {
+        settings.apply_to(&mut self);
+        self
+    }
 }
 
 pub struct EngineServices<'a> {
@@ -99,9 +141,43 @@ pub struct EngineServices<'a> {
     pub animation: &'a mut AnimationManager,
     pub camera: &'a mut Camera2D,
     pub renderer: &'a mut Renderer,
+    pub time_scale: &'a mut f32,
+    pub quit_requested: &'a mut bool,
+    pub blackboard: &'a mut Blackboard,
+    /// Seeded generator for gameplay randomness, restarted from the recorded seed on replay
+    pub rng: &'a mut GameRng,
 }
 
 impl EngineServices<'_> {
+    /// Scale the dt passed to `Game::update`, e.g. 0.5 for half speed.
+    /// 0.0 freezes gameplay while rendering and input keep running.
+    /// Takes effect from the next frame.
+    pub fn set_time_scale(&mut self, scale: f32) {
+        *self.time_scale = scale.max(0.0);
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        *self.time_scale
+    }
+
+    /// Ask the app to quit; the engine forwards this to the backend after the frame
+    pub fn request_quit(&mut self) {
+        *self.quit_requested = true;
+    }
+
+    pub fn is_quit_requested(&self) -> bool {
+        *self.quit_requested
+    }
+
+    /// Store a value that survives game resets and state changes for the rest of the run
+    pub fn set_blackboard<V: Into<BlackboardValue>>(&mut self, key: &str, value: V) {
+        self.blackboard.set(key, value);
+    }
+
+    pub fn get_blackboard(&self, key: &str) -> Option<&BlackboardValue> {
+        self.blackboard.get(key)
+    }
+
     pub fn update_physics(&mut self, dt: f32) {
         self.physics.step(dt);
     }
@@ -112,6 +188,11 @@ impl EngineServices<'_> {
     }
 
     pub fn update_particles(&mut self, dt: f32) {
+        // Frozen for inspection: keep rendering what's there
+        if debug_flags().are_particles_frozen() {
+            return;
+        }
+
         for system in self.particles.values_mut() {
             system.update(dt);
         }
@@ -133,9 +214,71 @@ impl EngineServices<'_> {
         }
     }
 
+    /// Keys of all active particle systems, sorted
+    pub fn particle_system_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.particles.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Remove every particle system whose key starts with `prefix`, e.g. `"explosion_"`.
+    /// Returns the number removed.
+    pub fn stop_particles_matching(&mut self, prefix: &str) -> usize {
+        let before = self.particles.len();
+        self.particles.retain(|key, _| !key.starts_with(prefix));
+        before - self.particles.len()
+    }
+
+    /// Spawn a copy of `template` at every contact from the last physics step
+    /// whose impulse is at least `impulse_threshold`. Returns the number spawned.
+    /// Use an `EmissionDuration` template so the bursts clean themselves up.
+    pub fn auto_impact_particles(
+        &mut self,
+        impulse_threshold: f32,
+        template: &ParticleSystem,
+    ) -> usize {
+        let mut spawned = 0;
+        for event in self.physics.get_collision_events() {
+            if event.impulse_magnitude < impulse_threshold {
+                continue;
+            }
+
+            let mut system = template.clone();
+            system.set_spawn_position(event.contact_point);
+            // One burst per body pair, a repeated hit restarts it
+            let key = format!(
+                "impact_{}_{}",
+                event.body1_id.index(),
+                event.body2_id.index()
+            );
+            self.particles.insert(key, system);
+            spawned += 1;
+        }
+        spawned
+    }
+
+    /// Shake the camera for the hardest contact from the last physics step whose impulse
+    /// is at least `impulse_threshold`, adding `scale` trauma per unit of impulse.
+    /// Call after stepping. Returns the trauma added.
+    pub fn auto_camera_shake_on_impact(&mut self, impulse_threshold: f32, scale: f32) -> f32 {
+        let hardest = self
+            .physics
+            .get_collision_events()
+            .iter()
+            .map(|event| event.impulse_magnitude)
+            .filter(|&impulse| impulse >= impulse_threshold)
+            .fold(0.0, f32::max);
+        let trauma = hardest * scale;
+        if trauma > 0.0 {
+            self.camera.add_trauma(trauma);
+        }
+        trauma
+    }
+
     pub fn update_animations(&mut self, dt: f32, sprites: &mut [&mut Sprite]) {
         for sprite in sprites {
             self.animation.update_sprite_animation(sprite, dt);
+            sprite.update_fade(dt);
         }
     }
 
@@ -147,6 +290,14 @@ impl EngineServices<'_> {
         self.animation.stop_animation(sprite);
     }
 
+    pub fn pause_animation(&mut self, sprite: &mut Sprite) {
+        self.animation.pause_animation(sprite);
+    }
+
+    pub fn resume_animation(&mut self, sprite: &mut Sprite) {
+        self.animation.resume_animation(sprite);
+    }
+
     pub fn clear_animation(&mut self, sprite: &mut Sprite) {
         self.animation.clear_animation(sprite);
     }
@@ -155,59 +306,122 @@ impl EngineServices<'_> {
         self.animation.register_animation(animation);
     }
 
+    /// Hitbox for the sprite's current animation frame, see `SpriteAnimations::with_frame_collider`
+    pub fn active_animation_collider(&self, sprite: &Sprite) -> Option<Collider> {
+        self.animation.active_collider(sprite).copied()
+    }
+
     pub fn update_camera_shake(&mut self, dt: f32) {
-        self.camera.update_shake(dt);
+        self.camera.update_shake(dt, self.rng);
     }
 
+    /// Run the game's `render_overlay` in screen space above every other draw
+    pub(crate) fn render_overlay<G: Game>(&mut self, game: &mut G) {
+        let previous_space = self.renderer.draw_space();
+        let previous_key = self.renderer.sort_key();
+        self.renderer.begin_ui();
+        self.renderer.set_sort_key(f32::INFINITY);
+
+        game.render_overlay(self);
+
+        self.renderer.set_sort_key(previous_key);
+        self.renderer.set_draw_space(previous_space);
+    }
+
+    /// Default particle rendering, each live particle drawn with its system's `ParticleRenderMode`
+    /// in world space, or in screen space for `ParticleSystem::with_screen_space` systems
     pub fn render_particles(&mut self) {
-        for system in self.particles.values_mut() {
+        let previous_space = self.renderer.draw_space();
+        for system in self.particles.values() {
+            self.renderer.set_draw_space(particle_draw_space(system));
             for particle in system.get_particles() {
-                self.renderer.draw_particle(particle);
+                self.renderer
+                    .draw_particle_as(particle, system.render_mode());
             }
         }
+        self.renderer.set_draw_space(previous_space);
+    }
+
+    /// Call `f` once per live particle in every system with the key the system was added under,
+    /// for games that want custom particle visuals. The renderer is in each system's draw space.
+    pub fn for_each_particle(&mut self, mut f: impl FnMut(&mut Renderer, &Particle, &str)) {
+        let previous_space = self.renderer.draw_space();
+        for (key, system) in self.particles.iter() {
+            self.renderer.set_draw_space(particle_draw_space(system));
+            for particle in system.get_particles() {
+                f(self.renderer, particle, key);
+            }
+        }
+        self.renderer.set_draw_space(previous_space);
     }
 
     pub fn render_physics_debug(&mut self) {
         if debug_flags().is_collision_enabled() {
-            for body in self.physics.bodies() {
-                match body.collider.shape {
-                    CollisionShape::Rectangle { width, height } => {
-                        // Use center positioning like the collider
-                        let rect_outline = Quad::new(
-                            body.collider.position.x, // Center X
-                            body.collider.position.y, // Center Y
-                            width,
-                            height,
-                            Vec4::new(1.0, 0.0, 0.0, 1.0),
-                        )
-                        .with_outline();
-                        self.renderer.draw_quad(&rect_outline);
-                    }
-                    CollisionShape::Circle { radius } => {
-                        // This one is already correct - circles use center positioning
-                        let circle_outline = Circle::new(
-                            body.collider.position.x,
-                            body.collider.position.y,
-                            radius,
-                            Vec4::new(1.0, 0.0, 0.0, 1.0),
-                        )
-                        .with_outline();
-                        self.renderer.draw_circle(&circle_outline);
-                    }
+            self.draw_collider_outlines();
+        }
+    }
+
+    /// Outline every collider of the bodies in view, colored by body type and state
+    fn draw_collider_outlines(&mut self) {
+        // Skip inactive, hidden and off-screen bodies
+        let (view_min, view_max) = self.camera.visible_aabb();
+        for body in self.physics.debug_draw_bodies(view_min, view_max) {
+            let color = body.debug_render_color();
+            for collider in body.colliders() {
+                // Outlines follow the body rotation to match its visuals, while a
+                // faint box shows the axis-aligned shape collision actually uses
+                let is_rect = matches!(collider.shape, CollisionShape::Rectangle { .. });
+                if is_rect && body.rotation != 0.0 {
+                    self.renderer
+                        .draw_collider(collider, color.with_w(color.w * 0.35));
                 }
+                self.renderer
+                    .draw_collider_rotated(collider, color, body.rotation);
             }
         }
     }
 
+    /// World point under a screen position (e.g. `input.mouse_position()`), accounting for
+    /// camera zoom, rotation, shake and coordinate space
+    pub fn pick_world_point(&mut self, screen_pos: Vec2) -> Vec2 {
+        self.camera.screen_to_world(screen_pos)
+    }
+
+    /// Body under a screen position. Non-static bodies win over static ones, so clicking
+    /// a ball resting on the ground picks the ball.
+    pub fn pick_body(&mut self, screen_pos: Vec2) -> Option<BodyId> {
+        let world_pos = self.pick_world_point(screen_pos);
+        let hits = self.physics.query_point(world_pos);
+        hits.iter()
+            .copied()
+            .find(|&id| {
+                self.physics
+                    .get_body(id)
+                    .is_some_and(|body| body.body_type != BodyType::Static)
+            })
+            .or_else(|| hits.first().copied())
+    }
+
     pub fn begin_frame(&mut self) {
-        self.renderer.begin_frame();
+        self.renderer.begin_frame(self.camera);
     }
 
+    /// Flushing from `Game::render` would draw before the frame's render pass begins
+    #[deprecated(note = "the engine flushes queued draws itself after `Game::render`")]
     pub fn flush_and_present(&mut self) {
         self.renderer.flush(self.camera);
     }
 }
 
+/// Draw space a particle system's positions are in
+fn particle_draw_space(system: &ParticleSystem) -> DrawSpace {
+    if system.is_screen_space() {
+        DrawSpace::Screen
+    } else {
+        DrawSpace::World
+    }
+}
+
 // Trait that games must implement
 pub trait Game {
     fn config() -> GameConfig
@@ -216,13 +430,245 @@ pub trait Game {
 
     fn init(&mut self, config: &GameConfig, services: &mut EngineServices);
 
+    /// UI step, called right before `update` with mutable input. Widgets that handle the
+    /// mouse call `InputManager::set_mouse_captured` so world code in `update` can skip it.
+    fn update_ui(&mut self, _input: &mut InputManager, _services: &mut EngineServices) {}
+
     fn update(&mut self, dt: f32, input: &InputManager, services: &mut EngineServices);
 
+    /// Queue this frame's draws. Runs before the frame's render pass begins, the engine
+    /// flushes everything queued here once the pass is open.
     fn render(&mut self, services: &mut EngineServices);
 
+    /// Screen-space UI that must be on top of everything `render` draws, e.g. a cursor,
+    /// tooltips or transition fades. Called every frame after `render`, with draws in screen
+    /// pixels sorted after all other batches and left unlit. The debug panel still draws
+    /// above it.
+    fn render_overlay(&mut self, _services: &mut EngineServices) {}
+
     fn handle_event(&mut self, event: &sokol::app::Event);
 
     fn request_background_color_change(&self) -> Option<sg::Color> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::rigid_body::RigidBody;
+
+    /// Owns everything `EngineServices` borrows, so tests can build services without a window
+    struct ServiceParts {
+        physics: PhysicsWorld,
+        particles: HashMap<String, ParticleSystem>,
+        animation: AnimationManager,
+        camera: Camera2D,
+        renderer: Renderer,
+        time_scale: f32,
+        quit_requested: bool,
+        blackboard: Blackboard,
+        rng: GameRng,
+    }
+
+    impl ServiceParts {
+        fn new() -> Self {
+            Self {
+                physics: PhysicsWorld::new(),
+                particles: HashMap::new(),
+                animation: AnimationManager::new(),
+                camera: Camera2D::new(),
+                renderer: Renderer::new(),
+                time_scale: 1.0,
+                quit_requested: false,
+                blackboard: Blackboard::new(),
+                rng: GameRng::new(0),
+            }
+        }
+
+        fn services(&mut self) -> EngineServices<'_> {
+            EngineServices {
+                physics: &mut self.physics,
+                particles: &mut self.particles,
+                animation: &mut self.animation,
+                camera: &mut self.camera,
+                renderer: &mut self.renderer,
+                time_scale: &mut self.time_scale,
+                quit_requested: &mut self.quit_requested,
+                blackboard: &mut self.blackboard,
+                rng: &mut self.rng,
+            }
+        }
+    }
+
+    #[test]
+    fn for_each_particle_uses_each_systems_draw_space() {
+        let mut parts = ServiceParts::new();
+        for (key, screen_space) in [("hud", true), ("sparks", false)] {
+            let mut system =
+                ParticleSystem::new(Vec2::ZERO, 10.0, 1.0, 1.0).with_screen_space(screen_space);
+            system.update(0.1);
+            parts.particles.insert(key.to_string(), system);
+        }
+        let mut services = parts.services();
+
+        let mut seen = Vec::new();
+        services.for_each_particle(|renderer, _, key| {
+            seen.push((key.to_string(), renderer.draw_space()));
+        });
+        seen.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            seen,
+            vec![
+                ("hud".to_string(), DrawSpace::Screen),
+                ("sparks".to_string(), DrawSpace::World),
+            ]
+        );
+        assert_eq!(services.renderer.draw_space(), DrawSpace::World);
+    }
+
+    /// Dynamic box moving at `speed` into a static box it already touches
+    fn add_impact(physics: &mut PhysicsWorld, at: Vec2, speed: f32) -> (BodyId, BodyId) {
+        let mover = physics.add_body(
+            RigidBody::new_dynamic(at, Collider::new_rect(at.x, at.y, 10.0, 10.0), 1.0)
+                .with_velocity(Vec2::new(speed, 0.0)),
+        );
+        let wall_at = at + Vec2::new(9.0, 0.0);
+        let wall = physics.add_body(RigidBody::new_static(
+            wall_at,
+            Collider::new_rect(wall_at.x, wall_at.y, 10.0, 10.0),
+        ));
+        (mover, wall)
+    }
+
+    #[test]
+    fn hard_impacts_spawn_particles_and_soft_ones_dont() {
+        let mut parts = ServiceParts::new();
+        let (mover, wall) = add_impact(&mut parts.physics, Vec2::ZERO, 300.0);
+        add_impact(&mut parts.physics, Vec2::new(0.0, 100.0), 1.0);
+        let mut services = parts.services();
+        services.update_physics(1.0 / 60.0);
+        assert_eq!(services.physics.get_collision_events().len(), 2);
+
+        let template = ParticleSystem::new(Vec2::ZERO, 10.0, 0.1, 0.5);
+        assert_eq!(services.auto_impact_particles(50.0, &template), 1);
+
+        let keys: Vec<&String> = parts.particles.keys().collect();
+        let pair = [mover.index(), wall.index()];
+        assert!(
+            keys == [&format!("impact_{}_{}", pair[0], pair[1])]
+                || keys == [&format!("impact_{}_{}", pair[1], pair[0])],
+            "{keys:?}"
+        );
+    }
+
+    #[test]
+    fn for_each_particle_visits_every_live_particle_once() {
+        let mut parts = ServiceParts::new();
+        for (key, steps) in [("smoke", 3), ("sparks", 6)] {
+            let mut system = ParticleSystem::new(Vec2::ZERO, 10.0, 5.0, 5.0);
+            for _ in 0..steps {
+                system.update(0.1);
+            }
+            parts.particles.insert(key.to_string(), system);
+        }
+        let live: usize = parts
+            .particles
+            .values()
+            .map(|system| system.get_particles().len())
+            .sum();
+        assert!(live > 0);
+
+        let mut calls: HashMap<String, usize> = HashMap::new();
+        parts.services().for_each_particle(|_, _, key| {
+            *calls.entry(key.to_string()).or_default() += 1;
+        });
+        for (key, system) in &parts.particles {
+            assert_eq!(
+                calls.get(key).copied().unwrap_or(0),
+                system.get_particles().len()
+            );
+        }
+        assert_eq!(calls.values().sum::<usize>(), live);
+    }
+
+    #[test]
+    fn collider_outlines_match_the_collider_dimensions() {
+        let mut parts = ServiceParts::new();
+        parts.physics.add_body(RigidBody::new_static(
+            Vec2::new(40.0, -20.0),
+            Collider::new_rect(40.0, -20.0, 30.0, 12.0),
+        ));
+        let mut services = parts.services();
+        services.draw_collider_outlines();
+
+        let corners: Vec<Vec2> = services
+            .renderer
+            .vertices()
+            .iter()
+            .map(|v| Vec2::from(v.pos))
+            .collect();
+        assert_eq!(
+            corners,
+            vec![
+                Vec2::new(25.0, -26.0),
+                Vec2::new(55.0, -26.0),
+                Vec2::new(55.0, -14.0),
+                Vec2::new(25.0, -14.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn picked_world_point_round_trips_under_zoom_and_rotation() {
+        let mut parts = ServiceParts::new();
+        parts.camera.set_position(Vec2::new(-120.0, 75.0));
+        parts.camera.set_zoom(2.5);
+        parts.camera.set_rotation(0.8);
+        let mut services = parts.services();
+
+        for world in [
+            Vec2::new(-120.0, 75.0),
+            Vec2::new(-60.0, 20.0),
+            Vec2::new(-200.0, 130.0),
+        ] {
+            let screen = services.camera.world_to_screen(world);
+            let picked = services.pick_world_point(screen);
+            assert!((picked - world).length() < 1e-3, "{picked:?} != {world:?}");
+        }
+    }
+
+    #[test]
+    fn prefix_stop_removes_only_matching_systems() {
+        let mut parts = ServiceParts::new();
+        for key in ["explosion_1", "explosion_2", "smoke", "big_explosion_3"] {
+            parts.particles.insert(
+                key.to_string(),
+                ParticleSystem::new(Vec2::ZERO, 10.0, 1.0, 1.0),
+            );
+        }
+
+        assert_eq!(parts.services().stop_particles_matching("explosion_"), 2);
+        let mut remaining: Vec<&String> = parts.particles.keys().collect();
+        remaining.sort();
+        assert_eq!(remaining, ["big_explosion_3", "smoke"]);
+    }
+
+    #[test]
+    fn hard_impacts_add_trauma_and_soft_ones_dont() {
+        let trauma_after_impact = |speed: f32| {
+            let mut parts = ServiceParts::new();
+            add_impact(&mut parts.physics, Vec2::ZERO, speed);
+            let mut services = parts.services();
+            services.update_physics(1.0 / 60.0);
+            let added = services.auto_camera_shake_on_impact(50.0, 0.001);
+            (added, parts.camera.trauma())
+        };
+
+        let (added, trauma) = trauma_after_impact(300.0);
+        assert!(added > 0.0);
+        assert_eq!(trauma, added.min(1.0));
+        assert_eq!(trauma_after_impact(1.0), (0.0, 0.0));
+    }
+}
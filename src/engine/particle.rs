@@ -1,6 +1,7 @@
 use glam::{Vec2, Vec4};
 use rand::Rng;
 
+#[derive(Clone)]
 pub struct Particle {
     pub position: Vec2,
     pub velocity: Vec2,
@@ -77,12 +78,25 @@ impl ParticleVelocitySpec {
     }
 }
 
+/// How `EngineServices::render_particles` draws each particle of a system
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ParticleRenderMode {
+    /// Square of the particle's size, e.g. for debris
+    #[default]
+    Quad,
+    /// Circle with the particle's size as diameter, e.g. for soft sparks
+    Circle,
+    /// Texture with this name stretched over the particle's square
+    Textured(String),
+}
+
 #[derive(Clone, PartialEq)]
 pub enum ParticleSystemLifetime {
     Infinite,
     EmissionDuration,
 }
 
+#[derive(Clone)]
 pub struct ParticleSystem {
     particles: Vec<Particle>,
     emission_rate: f32,
@@ -97,6 +111,10 @@ pub struct ParticleSystem {
     global_accel: Vec2,
     drag: f32,
     lifetime: ParticleSystemLifetime,
+    frozen: bool,
+    render_mode: ParticleRenderMode,
+    /// Positions are screen pixels (top-left origin, +Y down) instead of world units
+    screen_space: bool,
 }
 
 impl ParticleSystem {
@@ -120,6 +138,9 @@ impl ParticleSystem {
             global_accel: Vec2::ZERO,
             drag: 0.0,
             lifetime: ParticleSystemLifetime::Infinite,
+            frozen: false,
+            render_mode: ParticleRenderMode::Quad,
+            screen_space: false,
         }
     }
 
@@ -164,6 +185,17 @@ impl ParticleSystem {
         self
     }
 
+    /// `with_velocity_direction` with the spread in degrees, the deviation allowed to either side
+    pub fn with_velocity_cone(
+        self,
+        dir: Vec2,
+        speed_min: f32,
+        speed_max: f32,
+        spread_degrees: f32,
+    ) -> Self {
+        self.with_velocity_direction(dir, speed_min, speed_max, spread_degrees.to_radians())
+    }
+
     pub fn with_velocity_radial(mut self, speed_min: f32, speed_max: f32) -> Self {
         self.velocity_spec = ParticleVelocitySpec::Radial {
             speed_min,
@@ -187,6 +219,18 @@ impl ParticleSystem {
         self
     }
 
+    /// Simulate and draw in screen pixels so the effect ignores the camera, e.g. UI
+    /// confetti. Spawn position, velocity and acceleration are then in pixels, +Y down.
+    pub fn with_screen_space(mut self, screen_space: bool) -> Self {
+        self.screen_space = screen_space;
+        self
+    }
+
+    pub fn with_render_mode(mut self, render_mode: ParticleRenderMode) -> Self {
+        self.render_mode = render_mode;
+        self
+    }
+
     pub fn with_size_fixed(mut self, size: f32) -> Self {
         self.size_spec = ParticleSizeSpec::Fixed(size.max(0.1));
         self
@@ -240,6 +284,16 @@ impl ParticleSystem {
             spread_rad,
         };
     }
+    /// `set_velocity_direction` with the spread in degrees
+    pub fn set_velocity_cone(
+        &mut self,
+        dir: Vec2,
+        speed_min: f32,
+        speed_max: f32,
+        spread_degrees: f32,
+    ) {
+        self.set_velocity_direction(dir, speed_min, speed_max, spread_degrees.to_radians());
+    }
     pub fn set_velocity_radial(&mut self, speed_min: f32, speed_max: f32) {
         self.velocity_spec = ParticleVelocitySpec::Radial {
             speed_min,
@@ -274,7 +328,35 @@ impl ParticleSystem {
             && self.total_time >= self.emission_duration
     }
 
+    /// A frozen system keeps its current particles but doesn't advance them
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    pub fn set_screen_space(&mut self, screen_space: bool) {
+        self.screen_space = screen_space;
+    }
+
+    pub fn is_screen_space(&self) -> bool {
+        self.screen_space
+    }
+
+    pub fn set_render_mode(&mut self, render_mode: ParticleRenderMode) {
+        self.render_mode = render_mode;
+    }
+
+    pub fn render_mode(&self) -> &ParticleRenderMode {
+        &self.render_mode
+    }
+
     pub fn update(&mut self, dt: f32) {
+        if self.frozen {
+            return;
+        }
         self.total_time += dt;
 
         // Update existing particles
@@ -438,3 +520,45 @@ impl ParticleSystem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(system: &ParticleSystem) -> Vec<Vec2> {
+        system.get_particles().iter().map(|p| p.position).collect()
+    }
+
+    #[test]
+    fn frozen_particles_stay_put() {
+        let mut system = ParticleSystem::new(Vec2::ZERO, 10.0, 10.0, 5.0)
+            .with_fixed_velocity(Vec2::new(50.0, 0.0));
+        for _ in 0..5 {
+            system.update(0.1);
+        }
+        assert!(!system.get_particles().is_empty());
+
+        system.set_frozen(true);
+        let frozen = positions(&system);
+        for _ in 0..5 {
+            system.update(0.1);
+        }
+        assert_eq!(positions(&system), frozen);
+    }
+
+    #[test]
+    fn cone_spread_is_converted_to_radians() {
+        let system = ParticleSystem::new(Vec2::ZERO, 10.0, 1.0, 1.0).with_velocity_cone(
+            Vec2::Y,
+            10.0,
+            20.0,
+            180.0,
+        );
+        match system.velocity_spec {
+            ParticleVelocitySpec::Direction { spread_rad, .. } => {
+                assert!((spread_rad - std::f32::consts::PI).abs() < 1e-6);
+            }
+            _ => panic!("expected a direction spec"),
+        }
+    }
+}
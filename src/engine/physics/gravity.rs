@@ -4,6 +4,8 @@ pub struct GravityField {
     pub strength: f32,
     pub radius: f32,
     pub falloff_type: GravityFalloff,
+    /// Upper bound on the force magnitude, keeps close passes from slingshotting bodies
+    pub max_force: Option<f32>,
 }
 
 #[derive(Debug, Clone)]
@@ -16,15 +18,50 @@ pub enum GravityFalloff {
 
 impl GravityField {
     pub fn new(strength: f32, radius: f32, falloff_type: GravityFalloff) -> Self {
-        Self { strength, radius, falloff_type }
+        Self { strength, radius, falloff_type, max_force: None }
+    }
+
+    pub fn with_max_force(mut self, max_force: f32) -> Self {
+        self.max_force = Some(max_force.max(0.0));
+        self
     }
     
     pub fn calculate_force(&self, distance: f32, target_mass: f32) -> f32 {
-        match self.falloff_type {
-            GravityFalloff::Constant => self.strength * target_mass,
-            GravityFalloff::Linear => self.strength * target_mass / distance,
-            GravityFalloff::InverseSquare => self.strength * target_mass / (distance * distance),
-            GravityFalloff::Custom(rate) => self.strength * target_mass / (1.0 + distance * distance * rate),
+        let force = self.strength * target_mass * self.falloff_type.scale(distance);
+        match self.max_force {
+            Some(max_force) => force.clamp(-max_force, max_force),
+            None => force,
         }
     }
-}
\ No newline at end of file
+}
+
+impl GravityFalloff {
+    /// Multiplier applied to the base strength at the given distance
+    pub fn scale(&self, distance: f32) -> f32 {
+        match self {
+            GravityFalloff::Constant => 1.0,
+            GravityFalloff::Linear => 1.0 / distance,
+            GravityFalloff::InverseSquare => 1.0 / (distance * distance),
+            GravityFalloff::Custom(rate) => 1.0 / (1.0 + distance * distance * rate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_force_clamps_near_passes_only() {
+        let field =
+            GravityField::new(1000.0, 500.0, GravityFalloff::InverseSquare).with_max_force(50.0);
+
+        // 1000 * 2 / 0.25 unclamped
+        assert_eq!(field.calculate_force(0.5, 2.0), 50.0);
+        // 1000 * 2 / 100 is under the cap
+        assert_eq!(field.calculate_force(10.0, 2.0), 20.0);
+
+        let unclamped = GravityField::new(1000.0, 500.0, GravityFalloff::InverseSquare);
+        assert_eq!(unclamped.calculate_force(0.5, 2.0), 8000.0);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod gravity;
 pub mod physics_world;
 pub mod rigid_body;
+pub mod trigger;
 pub mod world_bounds;
@@ -1,10 +1,12 @@
 use glam::Vec2;
+use std::{collections::HashSet, time::Instant};
 
 use crate::engine::{
-    collision::{check_collision, check_collision_with_point},
-    gravity::GravityField,
+    collision::{check_collision, check_collision_with_point, contact_manifold, Collider},
+    gravity::{GravityFalloff, GravityField},
     rigid_body::{BodyId, BodyType, RigidBody},
-    world_bounds::{BoundsBehavior, BoundsEvent, WorldBounds},
+    trigger::{Trigger, TriggerEvent, TriggerEventKind, TriggerId},
+    world_bounds::{BoundsBehavior, BoundsEvent, BoundsViolation, WorldBounds},
 };
 
 #[derive(Debug, Clone)]
@@ -13,12 +15,37 @@ pub struct CollisionEvent {
     pub body2_id: BodyId,
     pub contact_point: Vec2,
     pub normal: Vec2,
+    /// Normal impulse applied to resolve the contact (0 if the bodies were separating)
+    pub impulse_magnitude: f32,
+    /// Overlap depth along `normal` at the deepest contact
+    pub penetration: f32,
+    /// Only trigger colliders overlap: reported without any impulse or position correction
+    pub is_trigger: bool,
+}
+
+/// Deepest contact between two bodies, used for resolution
+struct Contact {
+    point: Vec2,
+    /// Points the impulse is applied at, two for resting rectangle contacts
+    manifold: Vec<Vec2>,
+    normal: Vec2,
+    penetration: f32,
+    /// No solid collider pair overlaps, only pairs involving a trigger
+    is_trigger: bool,
+}
+
+/// Slot in the body id table, pointing into the dense body list while occupied
+#[derive(Debug, Clone, Copy)]
+struct BodySlot {
+    generation: u32,
+    dense_index: Option<usize>,
 }
 
 /// The main physics world that manages all physics bodies
 pub struct PhysicsWorld {
     bodies: Vec<RigidBody>,
-    next_body_id: u32,
+    slots: Vec<BodySlot>,
+    free_slots: Vec<u32>,
     global_gravity: Vec2,
     collision_events: Vec<CollisionEvent>,
 
@@ -26,23 +53,62 @@ pub struct PhysicsWorld {
     bounds_behavior: BoundsBehavior,
     bounds_events: Vec<BoundsEvent>,
 
+    triggers: Vec<Trigger>,
+    next_trigger_id: u32,
+    /// (trigger, body) pairs overlapping at the end of the last step
+    trigger_overlaps: Vec<(TriggerId, BodyId)>,
+    trigger_events: Vec<TriggerEvent>,
+
+    /// Body pairs that never collide, stored with the lower slot index first
+    ignored_pairs: HashSet<(BodyId, BodyId)>,
+    /// Collision groups whose members don't collide with each other
+    non_self_colliding_groups: HashSet<u32>,
+
     // Performance settings
     sleep_enabled: bool,
+    sleep_velocity_threshold: f32,
+    sleep_time_threshold: f32,
     substeps: u32,
+    /// Longest allowed substep, adding substeps for long frames
+    max_substep_dt: Option<f32>,
+    solver_iterations: u32,
+    max_gravity_acceleration: Option<f32>,
+    /// Fraction of a pair's kinetic energy a contact may add, None for no cap
+    energy_tolerance: Option<f32>,
+
+    // Profiling
+    profiling_enabled: bool,
+    timings: StepTimings,
+
+    contact_modifier: Option<ContactModifier>,
+}
+
+/// Per-pair override returning (restitution, friction) for a contact
+pub type ContactModifier = Box<dyn Fn(&RigidBody, &RigidBody) -> (f32, f32)>;
+
+/// Per-phase timings of the last `step`, in microseconds
+#[derive(Debug, Clone, Copy, Default)]
+struct StepTimings {
+    integration_us: u64,
+    detection_us: u64,
+    resolution_us: u64,
 }
 
 impl PhysicsWorld {
     const EXTREME_PENETRATION_THRESHOLD: f32 = 50.0;
     const GRAVITY_FIELD_MIN_DISTANCE: f32 = 0.1;
-    const SLEEP_VELOCITY_THRESHOLD: f32 = 0.1;
+    const MAX_SLIDE_ITERATIONS: usize = 4;
     const CORRECTION_PERCENT: f32 = 0.8;
     const CORRECTION_SLOP: f32 = 0.01;
+    /// Upper limit on substeps derived from `max_substep_dt`
+    const MAX_DYNAMIC_SUBSTEPS: u32 = 64;
 
     /// Create a new physics world
     pub fn new() -> Self {
         Self {
             bodies: Vec::new(),
-            next_body_id: 0,
+            slots: Vec::new(),
+            free_slots: Vec::new(),
             global_gravity: Vec2::ZERO,
             collision_events: Vec::new(),
 
@@ -50,8 +116,26 @@ impl PhysicsWorld {
             bounds_behavior: BoundsBehavior::Events,
             bounds_events: Vec::new(),
 
+            triggers: Vec::new(),
+            next_trigger_id: 0,
+            trigger_overlaps: Vec::new(),
+            trigger_events: Vec::new(),
+            ignored_pairs: HashSet::new(),
+            non_self_colliding_groups: HashSet::new(),
+
             sleep_enabled: true,
+            sleep_velocity_threshold: RigidBody::DEFAULT_SLEEP_VELOCITY_THRESHOLD,
+            sleep_time_threshold: RigidBody::DEFAULT_SLEEP_TIME_THRESHOLD,
             substeps: 1,
+            max_substep_dt: None,
+            solver_iterations: 1,
+            max_gravity_acceleration: None,
+            energy_tolerance: None,
+
+            profiling_enabled: false,
+            timings: StepTimings::default(),
+
+            contact_modifier: None,
         }
     }
 
@@ -63,6 +147,22 @@ impl PhysicsWorld {
         self.collision_events.clear();
     }
 
+    /// True if `a` and `b` touched during the last step
+    pub fn are_colliding(&self, a: BodyId, b: BodyId) -> bool {
+        self.collision_events.iter().any(|event| {
+            (event.body1_id == a && event.body2_id == b)
+                || (event.body1_id == b && event.body2_id == a)
+        })
+    }
+
+    /// Number of bodies `body_id` touched during the last step
+    pub fn contact_count(&self, body_id: BodyId) -> usize {
+        self.collision_events
+            .iter()
+            .filter(|event| event.body1_id == body_id || event.body2_id == body_id)
+            .count()
+    }
+
     /// Get bounds events (like collision events)
     pub fn get_bounds_events(&self) -> &[BoundsEvent] {
         &self.bounds_events
@@ -72,6 +172,50 @@ impl PhysicsWorld {
         self.bounds_events.clear();
     }
 
+    /// Add a sensor region that reports non-static bodies entering and leaving it
+    pub fn add_trigger(&mut self, collider: Collider) -> TriggerId {
+        let id = TriggerId(self.next_trigger_id);
+        self.next_trigger_id += 1;
+        self.triggers.push(Trigger { id, collider });
+        id
+    }
+
+    /// Remove a trigger, without emitting exit events for bodies inside it
+    pub fn remove_trigger(&mut self, id: TriggerId) -> bool {
+        let count = self.triggers.len();
+        self.triggers.retain(|trigger| trigger.id != id);
+        self.trigger_overlaps
+            .retain(|(trigger_id, _)| *trigger_id != id);
+        self.triggers.len() != count
+    }
+
+    pub fn get_trigger(&self, id: TriggerId) -> Option<&Trigger> {
+        self.triggers.iter().find(|trigger| trigger.id == id)
+    }
+
+    /// Get a trigger to move or resize it
+    pub fn get_trigger_mut(&mut self, id: TriggerId) -> Option<&mut Trigger> {
+        self.triggers.iter_mut().find(|trigger| trigger.id == id)
+    }
+
+    /// Enter/exit events from the last step
+    pub fn get_trigger_events(&self) -> &[TriggerEvent] {
+        &self.trigger_events
+    }
+
+    pub fn clear_trigger_events(&mut self) {
+        self.trigger_events.clear();
+    }
+
+    /// Bodies inside a trigger as of the last step
+    pub fn bodies_in_trigger(&self, id: TriggerId) -> Vec<BodyId> {
+        self.trigger_overlaps
+            .iter()
+            .filter(|(trigger_id, _)| *trigger_id == id)
+            .map(|(_, body_id)| *body_id)
+            .collect()
+    }
+
     /// Configure gravity for the world
     pub fn set_global_gravity(&mut self, gravity: Vec2) {
         self.global_gravity = gravity;
@@ -84,6 +228,21 @@ impl PhysicsWorld {
         }
     }
 
+    /// Gravity pointing at `angle_rad`, counter-clockwise from +X (0 is right,
+    /// -π/2 is down in a Y-up world)
+    pub fn set_gravity_from_angle(&mut self, angle_rad: f32, magnitude: f32) {
+        self.set_global_gravity(Vec2::from_angle(angle_rad) * magnitude);
+    }
+
+    /// Rotate the current gravity by `delta_rad`, keeping its strength
+    pub fn rotate_gravity(&mut self, delta_rad: f32) {
+        self.set_global_gravity(Vec2::from_angle(delta_rad).rotate(self.global_gravity));
+    }
+
+    pub fn global_gravity(&self) -> Vec2 {
+        self.global_gravity
+    }
+
     /// Configure world bounds and behavior
     pub fn set_world_bounds(&mut self, bounds: Option<WorldBounds>, behavior: BoundsBehavior) {
         self.world_bounds = bounds;
@@ -97,47 +256,183 @@ impl PhysicsWorld {
 
     /// Add a body to the physics world
     pub fn add_body(&mut self, mut body: RigidBody) -> BodyId {
-        let id = BodyId(self.next_body_id);
-        self.next_body_id += 1;
+        let dense_index = self.bodies.len();
+
+        // Reuse a freed slot if there is one, its generation was bumped on removal
+        let index = match self.free_slots.pop() {
+            Some(index) => {
+                self.slots[index as usize].dense_index = Some(dense_index);
+                index
+            }
+            None => {
+                self.slots.push(BodySlot {
+                    generation: 0,
+                    dense_index: Some(dense_index),
+                });
+                (self.slots.len() - 1) as u32
+            }
+        };
+
+        let id = BodyId {
+            index,
+            generation: self.slots[index as usize].generation,
+        };
 
         body.id = id;
+        body.sync_collider();
+        body.reset_interpolation();
         self.bodies.push(body);
 
+        // Don't leave sleeping bodies stuck inside something that just appeared
+        self.wake_bodies_touching(self.bodies.len() - 1);
+
         id
     }
 
+    /// Add many bodies at once, e.g. for level generation. Ids are returned in input order.
+    pub fn add_bodies(&mut self, bodies: Vec<RigidBody>) -> Vec<BodyId> {
+        self.bodies.reserve(bodies.len());
+        let new_slots = bodies.len().saturating_sub(self.free_slots.len());
+        self.slots.reserve(new_slots);
+
+        bodies.into_iter().map(|body| self.add_body(body)).collect()
+    }
+
     /// Remove a body from the physics world
     pub fn remove_body(&mut self, id: BodyId) -> Option<RigidBody> {
-        if let Some(index) = self.bodies.iter().position(|b| b.id == id) {
-            Some(self.bodies.remove(index))
+        let dense_index = self.dense_index(id)?;
+        Some(self.remove_at(dense_index))
+    }
+
+    /// Never collide `a` with `b`, e.g. a player and the projectile it just fired.
+    /// Both still collide with everything else. Cleared when either body is removed.
+    pub fn ignore_collision(&mut self, a: BodyId, b: BodyId) {
+        if a != b {
+            self.ignored_pairs.insert(Self::pair_key(a, b));
+        }
+    }
+
+    pub fn unignore_collision(&mut self, a: BodyId, b: BodyId) {
+        self.ignored_pairs.remove(&Self::pair_key(a, b));
+    }
+
+    pub fn is_collision_ignored(&self, a: BodyId, b: BodyId) -> bool {
+        self.ignored_pairs.contains(&Self::pair_key(a, b))
+    }
+
+    /// Whether bodies sharing `collision_group` collide with each other (the default),
+    /// e.g. false for bullets while enemies keep bumping into each other.
+    /// Bodies in the group still collide with everything outside it.
+    pub fn set_group_self_collide(&mut self, group: u32, self_collide: bool) {
+        if self_collide {
+            self.non_self_colliding_groups.remove(&group);
         } else {
-            None
+            self.non_self_colliding_groups.insert(group);
         }
     }
 
-    /// Remove a body from the physics world
+    pub fn group_self_collides(&self, group: u32) -> bool {
+        !self.non_self_colliding_groups.contains(&group)
+    }
+
+    /// Order-independent key for the ignore set
+    fn pair_key(a: BodyId, b: BodyId) -> (BodyId, BodyId) {
+        if (a.index, a.generation) <= (b.index, b.generation) {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    /// Whether the bodies at two dense indices are an ignored pair
+    /// or share a group that doesn't self-collide
+    fn pair_ignored(&self, i: usize, j: usize) -> bool {
+        if let (Some(a), Some(b)) = (
+            self.bodies[i].collision_group,
+            self.bodies[j].collision_group,
+        ) {
+            if a == b && !self.group_self_collides(a) {
+                return true;
+            }
+        }
+        !self.ignored_pairs.is_empty()
+            && self
+                .ignored_pairs
+                .contains(&Self::pair_key(self.bodies[i].id, self.bodies[j].id))
+    }
+
+    /// Remove all bodies from the physics world, invalidating their ids
     pub fn clear_bodies(&mut self) {
-        self.bodies.clear();
+        while !self.bodies.is_empty() {
+            self.remove_at(self.bodies.len() - 1);
+        }
     }
 
     pub fn remove_marked_bodies(&mut self) -> Vec<RigidBody> {
-        let (remaining, removed): (Vec<_>, Vec<_>) = self
-            .bodies
-            .drain(..)
-            .partition(|body| !body.marked_for_deletion);
-
-        self.bodies = remaining;
+        let mut removed = Vec::new();
+        let mut index = 0;
+        while index < self.bodies.len() {
+            if self.bodies[index].marked_for_deletion {
+                // swap_remove moves the last body into `index`, so check it next
+                removed.push(self.remove_at(index));
+            } else {
+                index += 1;
+            }
+        }
         removed
     }
 
     /// Get a reference to a body
     pub fn get_body(&self, id: BodyId) -> Option<&RigidBody> {
-        self.bodies.iter().find(|b| b.id == id)
+        let dense_index = self.dense_index(id)?;
+        self.bodies.get(dense_index)
     }
 
     /// Get a mutable reference to a body
     pub fn get_body_mut(&mut self, id: BodyId) -> Option<&mut RigidBody> {
-        self.bodies.iter_mut().find(|b| b.id == id)
+        let dense_index = self.dense_index(id)?;
+        self.bodies.get_mut(dense_index)
+    }
+
+    /// True if `id` refers to a body that is still in the world.
+    /// Ids of removed bodies stay dead even after their slot is reused.
+    pub fn is_body_alive(&self, id: BodyId) -> bool {
+        self.dense_index(id).is_some()
+    }
+
+    /// Number of bodies currently in the world
+    pub fn body_count(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// Position of a body in the dense list, or None for stale or unknown ids
+    fn dense_index(&self, id: BodyId) -> Option<usize> {
+        let slot = self.slots.get(id.index as usize)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        slot.dense_index
+    }
+
+    /// Swap-remove a body and free its slot for reuse
+    fn remove_at(&mut self, dense_index: usize) -> RigidBody {
+        let body = self.bodies.swap_remove(dense_index);
+        if !self.ignored_pairs.is_empty() {
+            self.ignored_pairs
+                .retain(|&(a, b)| a != body.id && b != body.id);
+        }
+
+        let slot = &mut self.slots[body.id.index as usize];
+        slot.dense_index = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_slots.push(body.id.index);
+
+        // The previously last body now lives at `dense_index`
+        if let Some(moved) = self.bodies.get(dense_index) {
+            self.slots[moved.id.index as usize].dense_index = Some(dense_index);
+        }
+
+        body
     }
 
     /// Get all bodies
@@ -145,22 +440,198 @@ impl PhysicsWorld {
         &self.bodies
     }
 
+    /// Bodies of one type, e.g. every dynamic body
+    pub fn bodies_of_type(&self, body_type: BodyType) -> impl Iterator<Item = &RigidBody> {
+        self.bodies
+            .iter()
+            .filter(move |body| body.body_type == body_type)
+    }
+
+    /// Mutable `bodies_of_type`; move bodies with `set_position` so their colliders follow
+    pub fn bodies_of_type_mut(
+        &mut self,
+        body_type: BodyType,
+    ) -> impl Iterator<Item = &mut RigidBody> {
+        self.bodies
+            .iter_mut()
+            .filter(move |body| body.body_type == body_type)
+    }
+
+    /// Ids of all bodies with a collider overlapping the circle at `center`
+    pub fn query_radius(&self, center: Vec2, radius: f32) -> Vec<BodyId> {
+        let area = Collider::new_circle(center.x, center.y, radius);
+        self.bodies
+            .iter()
+            .filter(|body| body.colliders().any(|c| check_collision(&area, c)))
+            .map(|body| body.id)
+            .collect()
+    }
+
+    /// Ids of all bodies with a collider containing `point`
+    pub fn query_point(&self, point: Vec2) -> Vec<BodyId> {
+        self.bodies
+            .iter()
+            .filter(|body| body.colliders().any(|c| c.contains_point(point)))
+            .map(|body| body.id)
+            .collect()
+    }
+
+    /// Bounding box (min, max) enclosing every body's colliders, or None when empty.
+    /// Useful for auto-framing cameras and minimaps.
+    pub fn world_aabb(&self) -> Option<(Vec2, Vec2)> {
+        self.bodies
+            .iter()
+            .map(Self::get_body_bounds_static)
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+    }
+
+    /// Active, debug-visible bodies whose bounds overlap the view `(min, max)`,
+    /// which is what physics debug rendering draws
+    pub fn debug_draw_bodies(&self, min: Vec2, max: Vec2) -> impl Iterator<Item = &RigidBody> {
+        self.bodies.iter().filter(move |body| {
+            if !body.active || !body.debug_visible {
+                return false;
+            }
+            let (body_min, body_max) = Self::get_body_bounds_static(body);
+            body_min.x <= max.x && body_max.x >= min.x && body_min.y <= max.y && body_max.y >= min.y
+        })
+    }
+
+    /// Push dynamic bodies within `radius` away from `center`, e.g. for explosions.
+    /// The impulse is `strength` scaled by `falloff` at the body's distance.
+    pub fn apply_radial_impulse(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        strength: f32,
+        falloff: GravityFalloff,
+    ) {
+        // Keep Linear/InverseSquare finite for bodies right at the center
+        const MIN_DISTANCE: f32 = 1.0;
+
+        for id in self.query_radius(center, radius) {
+            if let Some(body) = self.get_body_mut(id) {
+                let offset = body.position - center;
+                let distance = offset.length().max(MIN_DISTANCE);
+                let direction = offset.try_normalize().unwrap_or(Vec2::Y);
+                body.apply_impulse(direction * strength * falloff.scale(distance));
+            }
+        }
+    }
+
+    /// Move a body by `displacement`, pushing it out of static colliders so it slides
+    /// along walls instead of passing through them. Returns the displacement achieved.
+    /// This is the character controller primitive for kinematic bodies, which the
+    /// collision solver never moves, so other body types don't move and get `Vec2::ZERO`.
+    pub fn move_and_slide(&mut self, id: BodyId, displacement: Vec2) -> Vec2 {
+        let Some(index) = self.dense_index(id) else {
+            return Vec2::ZERO;
+        };
+        if self.bodies[index].body_type != BodyType::Kinematic {
+            return Vec2::ZERO;
+        }
+
+        let start = self.bodies[index].position;
+        self.bodies[index].position += displacement;
+        self.bodies[index].sync_collider();
+
+        // Pushing out of one wall can push into another, so repeat a few times
+        for _ in 0..Self::MAX_SLIDE_ITERATIONS {
+            let mut pushed = false;
+            for other in 0..self.bodies.len() {
+                if other == index
+                    || self.bodies[other].body_type != BodyType::Static
+                    || self.pair_ignored(index, other)
+                {
+                    continue;
+                }
+                if let Some((normal, depth)) = self.solid_separation(index, other) {
+                    let body = &mut self.bodies[index];
+                    body.position -= normal * depth;
+                    body.sync_collider();
+                    pushed = true;
+                }
+            }
+            if !pushed {
+                break;
+            }
+        }
+
+        // The move is a teleport, don't let rendering lerp across it
+        self.bodies[index].reset_interpolation();
+        self.bodies[index].position - start
+    }
+
     /// Step the physics simulation forward by dt seconds
     pub fn step(&mut self, dt: f32) {
         if dt <= 0.0 {
             return;
         }
 
-        let sub_dt = dt / self.substeps as f32;
+        let substeps = self.substep_count(dt);
+        let sub_dt = dt / substeps as f32;
+        self.timings = StepTimings::default();
 
-        for _ in 0..self.substeps {
+        for body in &mut self.bodies {
+            body.reset_interpolation();
+        }
+
+        for _ in 0..substeps {
             self.step_internal(sub_dt);
         }
+
+        self.update_triggers();
+    }
+
+    /// Compare trigger overlaps with the previous step and emit enter/exit events.
+    /// Bodies removed while inside a trigger get an exit event with their stale id.
+    fn update_triggers(&mut self) {
+        self.trigger_events.clear();
+        if self.triggers.is_empty() && self.trigger_overlaps.is_empty() {
+            return;
+        }
+
+        let mut overlaps = Vec::new();
+        for trigger in &self.triggers {
+            for body in &self.bodies {
+                if body.body_type == BodyType::Static || !body.active {
+                    continue;
+                }
+                if body
+                    .colliders()
+                    .any(|collider| check_collision(&trigger.collider, collider))
+                {
+                    overlaps.push((trigger.id, body.id));
+                }
+            }
+        }
+
+        for &(trigger_id, body_id) in &overlaps {
+            if !self.trigger_overlaps.contains(&(trigger_id, body_id)) {
+                self.trigger_events.push(TriggerEvent {
+                    trigger_id,
+                    body_id,
+                    kind: TriggerEventKind::Enter,
+                });
+            }
+        }
+        for &(trigger_id, body_id) in &self.trigger_overlaps {
+            if !overlaps.contains(&(trigger_id, body_id)) {
+                self.trigger_events.push(TriggerEvent {
+                    trigger_id,
+                    body_id,
+                    kind: TriggerEventKind::Exit,
+                });
+            }
+        }
+
+        self.trigger_overlaps = overlaps;
     }
 
     /// Internal physics step
     fn step_internal(&mut self, dt: f32) {
         self.collision_events.clear();
+        let integration_start = self.profile_start();
 
         let mut gravity_fields: Vec<(BodyId, Vec2, GravityField)> = Vec::new();
         for body in &self.bodies {
@@ -171,12 +642,13 @@ impl PhysicsWorld {
 
         // Apply forces to dynamic bodies
         for body in &mut self.bodies {
-            if body.body_type == BodyType::Dynamic && !body.is_sleeping {
+            if body.body_type == BodyType::Dynamic && body.active && !body.is_sleeping {
                 // Apply global gravity
                 let global_gravity_force = self.global_gravity * body.mass;
                 body.force_accumulator += global_gravity_force;
 
                 // Apply gravity from other bodies with gravity fields
+                let mut field_force = Vec2::ZERO;
                 for (other_id, other_position, gravity_field) in &gravity_fields {
                     if *other_id != body.id {
                         let to_other = *other_position - body.position;
@@ -189,10 +661,14 @@ impl PhysicsWorld {
                             let direction = to_other / distance;
                             let force_magnitude =
                                 gravity_field.calculate_force(distance, body.mass);
-                            body.force_accumulator += direction * force_magnitude;
+                            field_force += direction * force_magnitude;
                         }
                     }
                 }
+                if let Some(max_acceleration) = self.max_gravity_acceleration {
+                    field_force = field_force.clamp_length_max(max_acceleration * body.mass);
+                }
+                body.force_accumulator += field_force;
 
                 // Apply drag
                 if body.material.drag > 0.0 {
@@ -204,7 +680,7 @@ impl PhysicsWorld {
 
         // Integrate forces and update positions
         for body in &mut self.bodies {
-            if body.body_type == BodyType::Dynamic && !body.is_sleeping {
+            if body.body_type == BodyType::Dynamic && body.active && !body.is_sleeping {
                 // Calculate acceleration from forces (F = ma, so a = F/m)
                 body.acceleration = body.force_accumulator / body.mass;
 
@@ -225,7 +701,7 @@ impl PhysicsWorld {
                 }
 
                 // Update collider position
-                body.collider.position = body.position;
+                body.sync_collider();
 
                 // Clear force accumulator for next frame
                 body.force_accumulator = Vec2::ZERO;
@@ -233,33 +709,92 @@ impl PhysicsWorld {
 
                 // Update sleep timer
                 if self.sleep_enabled {
-                    if body.velocity.length() < Self::SLEEP_VELOCITY_THRESHOLD {
+                    if body.velocity.length() < self.sleep_velocity_threshold {
                         body.sleep_timer += dt;
                     } else {
                         body.sleep_timer = 0.0;
                     }
 
                     // Put body to sleep if it's been still long enough
-                    if body.should_sleep() {
+                    if body
+                        .should_sleep_with(self.sleep_velocity_threshold, self.sleep_time_threshold)
+                    {
                         body.is_sleeping = true;
                         body.velocity = Vec2::ZERO;
                     }
                 }
-            } else if body.body_type == BodyType::Kinematic {
+            } else if body.body_type == BodyType::Kinematic && body.active {
                 // Kinematic bodies only update position based on velocity
                 body.position += body.velocity * dt;
-                body.collider.position = body.position;
+                body.sync_collider();
+            }
+        }
+
+        // Moving kinematic bodies wake whatever they touch
+        for index in 0..self.bodies.len() {
+            let body = &self.bodies[index];
+            if body.body_type == BodyType::Kinematic && body.active && body.velocity != Vec2::ZERO {
+                self.wake_bodies_touching(index);
             }
         }
 
+        self.timings.integration_us += Self::elapsed_us(integration_start);
+
         // Add separation forces for overlapping bodies
+        let separation_start = self.profile_start();
         self.separate_overlapping_bodies();
+        self.timings.resolution_us += Self::elapsed_us(separation_start);
+
+        // Check for collisions
+        let detection_start = self.profile_start();
+        let collision_pairs = self.find_collision_pairs();
+        self.timings.detection_us += Self::elapsed_us(detection_start);
 
-        // Check for collisions and resolve them
-        self.resolve_collisions();
+        let resolution_start = self.profile_start();
+
+        // Resolve collisions, repeating over the same pairs so contacts converge.
+        // Each touching pair reports one event with the impulse summed over all iterations.
+        let mut pair_events: Vec<Option<usize>> = vec![None; collision_pairs.len()];
+        for _ in 0..self.solver_iterations {
+            for (pair_index, &(i, j)) in collision_pairs.iter().enumerate() {
+                let Some(event) = self.resolve_collision_pair(i, j) else {
+                    continue;
+                };
+                match pair_events[pair_index] {
+                    Some(event_index) => {
+                        self.collision_events[event_index].impulse_magnitude +=
+                            event.impulse_magnitude;
+                    }
+                    None => {
+                        pair_events[pair_index] = Some(self.collision_events.len());
+                        self.collision_events.push(event);
+                    }
+                }
+            }
+        }
 
         // Handle world bounds - add this line
         self.handle_world_bounds();
+
+        self.timings.resolution_us += Self::elapsed_us(resolution_start);
+    }
+
+    /// Time the phases of each step and report them in `stats()`. Off by default.
+    pub fn set_profiling_enabled(&mut self, enabled: bool) {
+        self.profiling_enabled = enabled;
+        self.timings = StepTimings::default();
+    }
+
+    pub fn is_profiling_enabled(&self) -> bool {
+        self.profiling_enabled
+    }
+
+    fn profile_start(&self) -> Option<Instant> {
+        self.profiling_enabled.then(Instant::now)
+    }
+
+    fn elapsed_us(start: Option<Instant>) -> u64 {
+        start.map_or(0, |start| start.elapsed().as_micros() as u64)
     }
 
     /// Set the number of physics substeps (higher = more accurate but slower)
@@ -267,6 +802,51 @@ impl PhysicsWorld {
         self.substeps = substeps.max(1);
     }
 
+    /// Split steps so no substep is longer than `max_dt`, e.g. after a frame hitch.
+    /// The `set_substeps` count stays the minimum, the dynamic count is capped at 64.
+    /// None (the default) always uses the fixed count.
+    pub fn set_max_substep_dt(&mut self, max_dt: Option<f32>) {
+        self.max_substep_dt = max_dt.filter(|max_dt| *max_dt > 0.0);
+    }
+
+    /// Substeps `step` uses for a frame of `dt` seconds
+    pub fn substep_count(&self, dt: f32) -> u32 {
+        let Some(max_dt) = self.max_substep_dt else {
+            return self.substeps;
+        };
+        let needed = (dt / max_dt).ceil().min(Self::MAX_DYNAMIC_SUBSTEPS as f32) as u32;
+        needed.max(self.substeps)
+    }
+
+    /// Number of times contacts are resolved per step (default 1).
+    /// More iterations let stacked bodies converge instead of sinking into each other.
+    /// Unlike substeps this doesn't re-integrate, it only re-solves the same contacts.
+    pub fn set_solver_iterations(&mut self, iterations: u32) {
+        self.solver_iterations = iterations.max(1);
+    }
+
+    pub fn solver_iterations(&self) -> u32 {
+        self.solver_iterations
+    }
+
+    /// Cap the combined acceleration gravity fields can give a body, None for no cap.
+    /// Global gravity isn't affected.
+    pub fn set_max_gravity_acceleration(&mut self, max_acceleration: Option<f32>) {
+        self.max_gravity_acceleration = max_acceleration.map(|max| max.max(0.0));
+    }
+
+    /// Stop contacts from adding kinetic energy to a pair of dynamic bodies beyond
+    /// `tolerance` (a fraction, e.g. 0.01), so stacks of bouncy bodies can't build up
+    /// energy and fly apart. None (the default) disables the cap. Pairs with a kinematic
+    /// body are never capped since kinematic movers legitimately push bodies along.
+    pub fn set_energy_cap(&mut self, tolerance: Option<f32>) {
+        self.energy_tolerance = tolerance.map(|tolerance| tolerance.max(0.0));
+    }
+
+    pub fn energy_cap(&self) -> Option<f32> {
+        self.energy_tolerance
+    }
+
     /// Enable or disable sleeping (performance optimization)
     pub fn set_sleep_enabled(&mut self, enabled: bool) {
         self.sleep_enabled = enabled;
@@ -279,8 +859,38 @@ impl PhysicsWorld {
         }
     }
 
-    /// Check for collisions between all bodies and resolve them
-    fn resolve_collisions(&mut self) {
+    /// Override the averaged restitution and friction per contact, e.g. for icy or sticky
+    /// surfaces. The modifier receives both bodies and returns (restitution, friction).
+    pub fn set_contact_modifier<F>(&mut self, modifier: F)
+    where
+        F: Fn(&RigidBody, &RigidBody) -> (f32, f32) + 'static,
+    {
+        self.contact_modifier = Some(Box::new(modifier));
+    }
+
+    /// Go back to averaging the two bodies' materials
+    pub fn clear_contact_modifier(&mut self) {
+        self.contact_modifier = None;
+    }
+
+    /// Bodies slower than `velocity` for more than `time` seconds fall asleep
+    pub fn set_sleep_thresholds(&mut self, velocity: f32, time: f32) {
+        self.sleep_velocity_threshold = velocity.max(0.0);
+        self.sleep_time_threshold = time.max(0.0);
+    }
+
+    /// Wake every sleeping body overlapping the body at `index`
+    fn wake_bodies_touching(&mut self, index: usize) {
+        for other in 0..self.bodies.len() {
+            if other != index && self.bodies[other].is_sleeping && self.bodies_overlap(index, other)
+            {
+                self.bodies[other].wake_up();
+            }
+        }
+    }
+
+    /// Find all overlapping body pairs
+    fn find_collision_pairs(&self) -> Vec<(usize, usize)> {
         // Collect collision pairs first to avoid borrowing issues
         let mut collision_pairs = Vec::new();
 
@@ -293,56 +903,189 @@ impl PhysicsWorld {
                     continue;
                 }
 
+                if !self.bodies[i].active || !self.bodies[j].active || self.pair_ignored(i, j) {
+                    continue;
+                }
+
                 // Check if bodies are colliding
-                if check_collision(&self.bodies[i].collider, &self.bodies[j].collider) {
+                if self.bodies_overlap(i, j) {
                     collision_pairs.push((i, j));
                 }
             }
         }
 
-        // Resolve collisions
-        for (i, j) in collision_pairs {
-            self.resolve_collision_pair(i, j);
-        }
+        collision_pairs
     }
 
-    /// Resolve collision between two bodies by index
-    fn resolve_collision_pair(&mut self, i: usize, j: usize) {
+    /// Resolve collision between two bodies by index, returning the contact event
+    fn resolve_collision_pair(&mut self, i: usize, j: usize) -> Option<CollisionEvent> {
         // Get collision details and calculate penetration once
-        let collision_result =
-            check_collision_with_point(&self.bodies[i].collider, &self.bodies[j].collider);
-        if !collision_result.collided {
-            return;
+        let contact = self.find_contact(i, j)?;
+
+        // Triggers only report the overlap, games use the depth for their own response
+        if contact.is_trigger {
+            return Some(CollisionEvent {
+                body1_id: self.bodies[i].id,
+                body2_id: self.bodies[j].id,
+                contact_point: contact.point,
+                normal: contact.normal,
+                impulse_magnitude: 0.0,
+                penetration: contact.penetration,
+                is_trigger: true,
+            });
         }
 
-        let penetration =
-            self.calculate_penetration(&self.bodies[i].collider, &self.bodies[j].collider);
-
         // Skip if penetration is too extreme
-        if penetration > Self::EXTREME_PENETRATION_THRESHOLD {
-            return;
+        if contact.penetration > Self::EXTREME_PENETRATION_THRESHOLD {
+            return None;
         }
 
-        // Calculate collision normal
-        let normal = self.calculate_collision_normal(i, j);
+        // Kinematic/static pairs only report the contact, neither body can respond.
+        // Both have infinite mass, so resolving them would divide zero by zero.
+        let any_dynamic = self.bodies[i].body_type == BodyType::Dynamic
+            || self.bodies[j].body_type == BodyType::Dynamic;
 
-        // Record collision event
-        self.collision_events.push(CollisionEvent {
+        // Apply impulse response
+        let impulse_magnitude = if any_dynamic {
+            self.apply_collision_impulse(i, j, contact.normal, &contact.manifold)
+        } else {
+            0.0
+        };
+
+        // Apply position correction (using the already calculated penetration)
+        if any_dynamic {
+            self.apply_position_correction(i, j, contact.normal, contact.penetration);
+        }
+
+        Some(CollisionEvent {
             body1_id: self.bodies[i].id,
             body2_id: self.bodies[j].id,
-            contact_point: collision_result.contact_point,
-            normal,
-        });
+            contact_point: contact.point,
+            normal: contact.normal,
+            impulse_magnitude,
+            penetration: contact.penetration,
+            is_trigger: false,
+        })
+    }
 
-        // Apply impulse response
-        self.apply_collision_impulse(i, j, normal, collision_result.contact_point);
+    /// True if any collider of body i overlaps any collider of body j
+    fn bodies_overlap(&self, i: usize, j: usize) -> bool {
+        self.bodies[i]
+            .colliders()
+            .any(|c1| self.bodies[j].colliders().any(|c2| check_collision(c1, c2)))
+    }
 
-        // Apply position correction (using the already calculated penetration)
-        self.apply_position_correction(i, j, normal, penetration);
+    /// Test every collider pair of two bodies.
+    /// Normal and penetration come from the deepest overlap, the contact point
+    /// is the average over all overlapping pairs. Solid pairs take precedence,
+    /// the contact is a trigger contact only if every overlapping pair involves a trigger.
+    fn find_contact(&self, i: usize, j: usize) -> Option<Contact> {
+        // Index 0 collects solid pairs, index 1 pairs with a trigger collider
+        let mut deepest: [Option<Contact>; 2] = [None, None];
+        let mut point_sum = [Vec2::ZERO; 2];
+        let mut count = [0; 2];
+
+        for c1 in self.bodies[i].colliders() {
+            for c2 in self.bodies[j].colliders() {
+                let result = check_collision_with_point(c1, c2);
+                if !result.collided {
+                    continue;
+                }
+
+                let kind = usize::from(c1.is_trigger || c2.is_trigger);
+                let penetration = self.calculate_penetration(c1, c2);
+                point_sum[kind] += result.contact_point;
+                count[kind] += 1;
+
+                let is_deeper = match &deepest[kind] {
+                    Some(current) => penetration > current.penetration,
+                    None => true,
+                };
+                if is_deeper {
+                    deepest[kind] = Some(Contact {
+                        point: result.contact_point,
+                        manifold: contact_manifold(c1, c2),
+                        normal: self.calculate_collision_normal(c1, c2),
+                        penetration,
+                        is_trigger: kind == 1,
+                    });
+                }
+            }
+        }
+
+        let kind = if deepest[0].is_some() { 0 } else { 1 };
+        deepest[kind].take().map(|mut contact| {
+            contact.point = point_sum[kind] / count[kind] as f32;
+            contact
+        })
+    }
+
+    /// Deepest overlap between the non-trigger colliders of two bodies,
+    /// as a normal pointing from body i towards body j and a penetration depth
+    fn solid_separation(&self, i: usize, j: usize) -> Option<(Vec2, f32)> {
+        use crate::engine::CollisionShape;
+
+        let mut deepest: Option<(Vec2, f32)> = None;
+
+        for c1 in self.bodies[i].colliders().filter(|c| !c.is_trigger) {
+            for c2 in self.bodies[j].colliders().filter(|c| !c.is_trigger) {
+                if !check_collision(c1, c2) {
+                    continue;
+                }
+
+                let penetration = self.calculate_penetration(c1, c2);
+                let normal = match (&c1.shape, &c2.shape) {
+                    (
+                        CollisionShape::Rectangle {
+                            width: w1,
+                            height: h1,
+                        },
+                        CollisionShape::Rectangle {
+                            width: w2,
+                            height: h2,
+                        },
+                    ) => {
+                        // Push out along the axis of least overlap so boxes slide flush
+                        let offset = c2.position - c1.position;
+                        let overlap_x = (w1 + w2) * 0.5 - offset.x.abs();
+                        let overlap_y = (h1 + h2) * 0.5 - offset.y.abs();
+                        if overlap_x < overlap_y {
+                            Vec2::new(if offset.x < 0.0 { -1.0 } else { 1.0 }, 0.0)
+                        } else {
+                            Vec2::new(0.0, if offset.y < 0.0 { -1.0 } else { 1.0 })
+                        }
+                    }
+                    _ => self.calculate_collision_normal(c1, c2),
+                };
+
+                if penetration <= 0.0 {
+                    continue;
+                }
+                let is_deeper = match deepest {
+                    Some((_, depth)) => penetration > depth,
+                    None => true,
+                };
+                if is_deeper {
+                    deepest = Some((normal, penetration));
+                }
+            }
+        }
+
+        deepest
     }
 
-    // Apply impulse-based collision response
-    fn apply_collision_impulse(&mut self, i: usize, j: usize, normal: Vec2, contact_point: Vec2) {
+    // Apply impulse-based collision response, returning the normal impulse magnitude.
+    // Impulses for every contact point are computed from the velocities before any of
+    // them is applied, and each is scaled by 1/n, so a flat resting contact stays balanced.
+    // Kinematic bodies act as infinite-mass movers: their velocity is part of the relative
+    // velocity, so a moving kinematic body pushes dynamic bodies along at its own speed.
+    fn apply_collision_impulse(
+        &mut self,
+        i: usize,
+        j: usize,
+        normal: Vec2,
+        contact_points: &[Vec2],
+    ) -> f32 {
         if self.bodies[i].is_sleeping {
             self.bodies[i].wake_up();
         }
@@ -350,23 +1093,6 @@ impl PhysicsWorld {
             self.bodies[j].wake_up();
         }
 
-        // Calculate contact points relative to centers of mass
-        let r1 = contact_point - self.bodies[i].position;
-        let r2 = contact_point - self.bodies[j].position;
-
-        // Calculate relative velocity at contact point including rotation
-        let v1_at_contact =
-            self.bodies[i].velocity + Vec2::new(-r1.y, r1.x) * self.bodies[i].angular_velocity;
-        let v2_at_contact =
-            self.bodies[j].velocity + Vec2::new(-r2.y, r2.x) * self.bodies[j].angular_velocity;
-        let relative_velocity = v2_at_contact - v1_at_contact;
-        let velocity_along_normal = relative_velocity.dot(normal);
-
-        // Don't resolve if velocities are separating
-        if velocity_along_normal > 0.0 {
-            return;
-        }
-
         // Calculate inverse masses and inertias
         let inv_mass1 = if self.bodies[i].mass.is_infinite() {
             0.0
@@ -390,66 +1116,154 @@ impl PhysicsWorld {
             1.0 / self.bodies[j].moment_of_inertia
         };
 
-        // Calculate cross products for angular effects
-        let r1_cross_n = r1.x * normal.y - r1.y * normal.x;
-        let r2_cross_n = r2.x * normal.y - r2.y * normal.x;
+        let (restitution, friction) = match &self.contact_modifier {
+            Some(modifier) => modifier(&self.bodies[i], &self.bodies[j]),
+            None => (
+                (self.bodies[i].material.restitution + self.bodies[j].material.restitution) / 2.0,
+                (self.bodies[i].material.friction + self.bodies[j].material.friction) / 2.0,
+            ),
+        };
+        let share = 1.0 / contact_points.len().max(1) as f32;
+        let cross = |a: Vec2, b: Vec2| a.x * b.y - a.y * b.x;
+
+        // (r1, r2, impulse on body j) for every contact point
+        let mut impulses: Vec<(Vec2, Vec2, Vec2)> = Vec::new();
+        let mut total_impulse = 0.0;
+
+        for &contact_point in contact_points {
+            // Calculate contact points relative to centers of mass
+            let r1 = contact_point - self.bodies[i].position;
+            let r2 = contact_point - self.bodies[j].position;
+
+            // Calculate relative velocity at contact point including rotation
+            let v1_at_contact =
+                self.bodies[i].velocity + Vec2::new(-r1.y, r1.x) * self.bodies[i].angular_velocity;
+            let v2_at_contact =
+                self.bodies[j].velocity + Vec2::new(-r2.y, r2.x) * self.bodies[j].angular_velocity;
+            let relative_velocity = v2_at_contact - v1_at_contact;
+            let velocity_along_normal = relative_velocity.dot(normal);
+
+            // Don't resolve if velocities are separating
+            if velocity_along_normal > 0.0 {
+                continue;
+            }
 
-        // Calculate impulse scalar including angular effects
-        let denominator = inv_mass1
-            + inv_mass2
-            + (r1_cross_n * r1_cross_n * inv_inertia1)
-            + (r2_cross_n * r2_cross_n * inv_inertia2);
+            // Calculate cross products for angular effects
+            let r1_cross_n = cross(r1, normal);
+            let r2_cross_n = cross(r2, normal);
+
+            // Calculate impulse scalar including angular effects
+            let denominator = inv_mass1
+                + inv_mass2
+                + (r1_cross_n * r1_cross_n * inv_inertia1)
+                + (r2_cross_n * r2_cross_n * inv_inertia2);
+            if denominator <= 0.0 {
+                continue;
+            }
+
+            let impulse_scalar = -(1.0 + restitution) * velocity_along_normal / denominator * share;
+            impulses.push((r1, r2, normal * impulse_scalar));
+            total_impulse += impulse_scalar.abs();
+
+            // Add friction calculation with better thresholds
+            if friction > 0.0 {
+                // Calculate tangent (perpendicular to normal)
+                let tangent = Vec2::new(-normal.y, normal.x);
+                let relative_velocity_tangent = relative_velocity.dot(tangent);
+
+                // Only apply friction if there's significant tangential movement
+                if relative_velocity_tangent.abs() > 1.5 {
+                    // Calculate tangential impulse with angular effects
+                    let r1_cross_t = cross(r1, tangent);
+                    let r2_cross_t = cross(r2, tangent);
+
+                    let tangent_denominator = inv_mass1
+                        + inv_mass2
+                        + (r1_cross_t * r1_cross_t * inv_inertia1)
+                        + (r2_cross_t * r2_cross_t * inv_inertia2);
+
+                    let friction_impulse_scalar =
+                        -relative_velocity_tangent / tangent_denominator * share;
+                    let max_friction = friction * impulse_scalar.abs() * 0.3;
+                    let friction_impulse_scalar =
+                        friction_impulse_scalar.clamp(-max_friction, max_friction);
+                    impulses.push((r1, r2, tangent * friction_impulse_scalar));
+                }
+            }
+        }
 
-        let restitution =
-            (self.bodies[i].material.restitution + self.bodies[j].material.restitution) / 2.0;
-        let impulse_scalar = -(1.0 + restitution) * velocity_along_normal / denominator;
-        let impulse = normal * impulse_scalar;
+        let energy_before = self
+            .energy_tolerance
+            .and_then(|_| self.pair_internal_energy(i, j))
+            .map(|(_, energy)| energy);
 
-        // Apply linear and angular impulse
-        if self.bodies[i].body_type == BodyType::Dynamic {
-            self.bodies[i].velocity -= impulse * inv_mass1;
-            self.bodies[i].angular_velocity -= r1_cross_n * impulse_scalar * inv_inertia1;
+        // Apply linear and angular impulses
+        for (r1, r2, impulse) in impulses {
+            if self.bodies[i].body_type == BodyType::Dynamic {
+                self.bodies[i].velocity -= impulse * inv_mass1;
+                self.bodies[i].angular_velocity -= cross(r1, impulse) * inv_inertia1;
+            }
+            if self.bodies[j].body_type == BodyType::Dynamic {
+                self.bodies[j].velocity += impulse * inv_mass2;
+                self.bodies[j].angular_velocity += cross(r2, impulse) * inv_inertia2;
+            }
         }
-        if self.bodies[j].body_type == BodyType::Dynamic {
-            self.bodies[j].velocity += impulse * inv_mass2;
-            self.bodies[j].angular_velocity += r2_cross_n * impulse_scalar * inv_inertia2;
-        }
-
-        // Add friction calculation with better thresholds
-        let friction = (self.bodies[i].material.friction + self.bodies[j].material.friction) / 2.0;
-        if friction > 0.0 {
-            // Calculate tangent (perpendicular to normal)
-            let tangent = Vec2::new(-normal.y, normal.x);
-            let relative_velocity_tangent = relative_velocity.dot(tangent);
-
-            // Only apply friction if there's significant tangential movement
-            if relative_velocity_tangent.abs() > 1.5 {
-                // Calculate tangential impulse with angular effects
-                let r1_cross_t = r1.x * tangent.y - r1.y * tangent.x;
-                let r2_cross_t = r2.x * tangent.y - r2.y * tangent.x;
-
-                let tangent_denominator = inv_mass1
-                    + inv_mass2
-                    + (r1_cross_t * r1_cross_t * inv_inertia1)
-                    + (r2_cross_t * r2_cross_t * inv_inertia2);
-
-                let friction_impulse_scalar = -relative_velocity_tangent / tangent_denominator;
-                let max_friction = friction * impulse_scalar.abs() * 0.3;
-                let friction_impulse_scalar =
-                    friction_impulse_scalar.clamp(-max_friction, max_friction);
-                let friction_impulse = tangent * friction_impulse_scalar;
-
-                // Apply friction impulse (linear and angular)
-                if self.bodies[i].body_type == BodyType::Dynamic {
-                    self.bodies[i].velocity -= friction_impulse * inv_mass1;
-                    self.bodies[i].angular_velocity -=
-                        r1_cross_t * friction_impulse_scalar * inv_inertia1;
-                }
-                if self.bodies[j].body_type == BodyType::Dynamic {
-                    self.bodies[j].velocity += friction_impulse * inv_mass2;
-                    self.bodies[j].angular_velocity +=
-                        r2_cross_t * friction_impulse_scalar * inv_inertia2;
-                }
+
+        if let (Some(energy_before), Some(tolerance)) = (energy_before, self.energy_tolerance) {
+            self.clamp_pair_energy(i, j, energy_before * (1.0 + tolerance));
+        }
+
+        total_impulse
+    }
+
+    /// Velocity of the pair's center of mass and the kinetic energy relative to it,
+    /// which is what a contact can change. None if either body is kinematic.
+    fn pair_internal_energy(&self, i: usize, j: usize) -> Option<(Vec2, f32)> {
+        let (a, b) = (&self.bodies[i], &self.bodies[j]);
+        if a.body_type == BodyType::Kinematic || b.body_type == BodyType::Kinematic {
+            return None;
+        }
+
+        // A static body is an infinite mass at rest, so the frame doesn't move
+        let center_velocity =
+            if a.body_type == BodyType::Dynamic && b.body_type == BodyType::Dynamic {
+                (a.velocity * a.mass + b.velocity * b.mass) / (a.mass + b.mass)
+            } else {
+                Vec2::ZERO
+            };
+
+        let energy = [a, b]
+            .iter()
+            .filter(|body| body.body_type == BodyType::Dynamic)
+            .map(|body| {
+                let linear = 0.5 * body.mass * (body.velocity - center_velocity).length_squared();
+                let angular = if body.moment_of_inertia.is_finite() {
+                    0.5 * body.moment_of_inertia * body.angular_velocity * body.angular_velocity
+                } else {
+                    0.0
+                };
+                linear + angular
+            })
+            .sum();
+        Some((center_velocity, energy))
+    }
+
+    /// Scale the pair's motion relative to its center of mass down to `max_energy`,
+    /// keeping its momentum
+    fn clamp_pair_energy(&mut self, i: usize, j: usize, max_energy: f32) {
+        let Some((center_velocity, energy)) = self.pair_internal_energy(i, j) else {
+            return;
+        };
+        if energy <= max_energy || energy <= f32::EPSILON {
+            return;
+        }
+
+        let scale = (max_energy / energy).sqrt();
+        for index in [i, j] {
+            let body = &mut self.bodies[index];
+            if body.body_type == BodyType::Dynamic {
+                body.velocity = center_velocity + (body.velocity - center_velocity) * scale;
+                body.angular_velocity *= scale;
             }
         }
     }
@@ -477,32 +1291,36 @@ impl PhysicsWorld {
         // Apply position correction to dynamic bodies
         if self.bodies[i].body_type == BodyType::Dynamic {
             self.bodies[i].position -= correction * inv_mass1;
-            self.bodies[i].collider.position = self.bodies[i].position;
+            self.bodies[i].sync_collider();
         }
         if self.bodies[j].body_type == BodyType::Dynamic {
             self.bodies[j].position += correction * inv_mass2;
-            self.bodies[j].collider.position = self.bodies[j].position;
+            self.bodies[j].sync_collider();
         }
     }
 
-    fn calculate_collision_normal(&self, i: usize, j: usize) -> Vec2 {
+    /// Collision normal pointing from collider1 towards collider2
+    fn calculate_collision_normal(
+        &self,
+        collider1: &crate::engine::Collider,
+        collider2: &crate::engine::Collider,
+    ) -> Vec2 {
         use crate::engine::CollisionShape;
 
-        let body1 = &self.bodies[i];
-        let body2 = &self.bodies[j];
-
-        match (&body1.collider.shape, &body2.collider.shape) {
+        match (&collider1.shape, &collider2.shape) {
             (CollisionShape::Circle { .. }, CollisionShape::Rectangle { .. }) => {
-                // Circle to rectangle: normal points from rectangle to circle
-                self.get_rect_to_circle_normal(&body2.collider, &body1.collider)
+                // Circle to rectangle: flip the rectangle-to-circle normal
+                -self.get_rect_to_circle_normal(collider2, collider1)
             }
             (CollisionShape::Rectangle { .. }, CollisionShape::Circle { .. }) => {
                 // Rectangle to circle: normal points from rectangle to circle
-                self.get_rect_to_circle_normal(&body1.collider, &body2.collider)
+                self.get_rect_to_circle_normal(collider1, collider2)
             }
             _ => {
-                // Default: normal from body1 to body2
-                (body2.position - body1.position).normalize()
+                // Default: center to center, stable fallback when colliders coincide
+                (collider2.position - collider1.position)
+                    .try_normalize()
+                    .unwrap_or(Vec2::Y)
             }
         }
     }
@@ -643,6 +1461,10 @@ impl PhysicsWorld {
             active_bodies: total_bodies - sleeping_bodies,
             sleeping_bodies,
             total_kinetic_energy: total_energy,
+            profiling_enabled: self.profiling_enabled,
+            integration_us: self.timings.integration_us,
+            detection_us: self.timings.detection_us,
+            resolution_us: self.timings.resolution_us,
         }
     }
 
@@ -658,13 +1480,23 @@ impl PhysicsWorld {
                     continue;
                 }
 
-                let penetration =
-                    self.calculate_penetration(&self.bodies[i].collider, &self.bodies[j].collider);
+                if !self.bodies[i].active || !self.bodies[j].active || self.pair_ignored(i, j) {
+                    continue;
+                }
+
+                // Triggers only report overlaps, they are never pushed apart
+                let penetration = self
+                    .find_contact(i, j)
+                    .filter(|contact| !contact.is_trigger)
+                    .map_or(0.0, |contact| contact.penetration);
 
                 // If significantly overlapping, apply separation force
                 if penetration > 1.0 {
-                    let direction =
-                        (self.bodies[j].position - self.bodies[i].position).normalize_or_zero();
+                    // Coincident bodies still need a direction to separate along
+                    let direction = (self.bodies[j].collider.position
+                        - self.bodies[i].collider.position)
+                        .try_normalize()
+                        .unwrap_or(Vec2::Y);
                     let separation_force = direction * penetration * SEPARATION_FORCE_MULTIPLIER;
 
                     // Apply separation forces
@@ -686,16 +1518,17 @@ impl PhysicsWorld {
             self.bounds_events.clear();
 
             for body in &mut self.bodies {
+                if !body.active {
+                    continue;
+                }
+
                 if body.body_type == BodyType::Static
                     && !matches!(self.bounds_behavior, BoundsBehavior::Events)
                 {
                     continue;
                 }
 
-                let behavior = body
-                    .bounds_behavior
-                    .as_ref()
-                    .unwrap_or(&self.bounds_behavior);
+                let behavior = Self::resolve_bounds_behavior(&self.bounds_behavior, body);
 
                 // Inline the bounds behavior logic instead of calling self.apply_bounds_behavior
                 let violations = Self::check_bounds_violations_static(body, &bounds);
@@ -707,40 +1540,68 @@ impl PhysicsWorld {
                 match behavior {
                     BoundsBehavior::Ignore => {}
                     BoundsBehavior::Events => {
-                        for violation in violations {
-                            self.bounds_events.push(BoundsEvent {
-                                body_id: body.id,
-                                position: body.position,
-                                violation,
-                            });
-                        }
+                        Self::push_bounds_events(&mut self.bounds_events, body, violations);
                     }
                     BoundsBehavior::Clamp { restitution } => {
+                        Self::push_bounds_events(&mut self.bounds_events, body, violations);
                         Self::clamp_to_bounds_static(body, &bounds, *restitution);
                     }
                     BoundsBehavior::Wrap => {
-                        Self::wrap_to_bounds_static(body, &bounds);
+                        // Only report edges the body actually wrapped across
+                        let position = body.position;
+                        let wrapped = Self::wrap_to_bounds_static(body, &bounds);
+                        for violation in wrapped {
+                            self.bounds_events.push(BoundsEvent {
+                                body_id: body.id,
+                                position,
+                                violation,
+                            });
+                        }
                     }
                     BoundsBehavior::Delete { safety_margin } => {
                         if Self::is_beyond_safety_margin_static(body, &bounds, *safety_margin) {
-                            for violation in violations {
-                                self.bounds_events.push(BoundsEvent {
-                                    body_id: body.id,
-                                    position: body.position,
-                                    violation,
-                                });
-                            }
+                            Self::push_bounds_events(&mut self.bounds_events, body, violations);
 
                             body.mark_for_deletion();
                         }
                     }
                     BoundsBehavior::PerBody => {
-                        // Should not reach here if properly implemented
+                        // resolve_bounds_behavior never returns PerBody
                     }
                 }
             }
         }
     }
+
+    /// A body's own `bounds_behavior` takes precedence over the world behavior.
+    /// With `PerBody` as the world behavior, bodies without their own setting fall back to `Events`.
+    fn resolve_bounds_behavior<'a>(
+        world_behavior: &'a BoundsBehavior,
+        body: &'a RigidBody,
+    ) -> &'a BoundsBehavior {
+        const PER_BODY_DEFAULT: BoundsBehavior = BoundsBehavior::Events;
+
+        match (&body.bounds_behavior, world_behavior) {
+            (Some(BoundsBehavior::PerBody), BoundsBehavior::PerBody)
+            | (None, BoundsBehavior::PerBody) => &PER_BODY_DEFAULT,
+            (Some(BoundsBehavior::PerBody), world) | (None, world) => world,
+            (Some(own), _) => own,
+        }
+    }
+
+    fn push_bounds_events(
+        events: &mut Vec<BoundsEvent>,
+        body: &RigidBody,
+        violations: Vec<BoundsViolation>,
+    ) {
+        for violation in violations {
+            events.push(BoundsEvent {
+                body_id: body.id,
+                position: body.position,
+                violation,
+            });
+        }
+    }
 }
 
 /// Static helper functions
@@ -748,7 +1609,7 @@ impl PhysicsWorld {
     fn check_bounds_violations_static(
         body: &RigidBody,
         bounds: &WorldBounds,
-    ) -> Vec<crate::engine::world_bounds::BoundsViolation> {
+    ) -> Vec<BoundsViolation> {
         let mut violations = Vec::new();
 
         // Get body bounds (considering collider shape)
@@ -756,24 +1617,16 @@ impl PhysicsWorld {
 
         // Check each boundary
         if body_min.x < bounds.min.x {
-            violations.push(crate::engine::world_bounds::BoundsViolation::Left(
-                bounds.min.x - body_min.x,
-            ));
+            violations.push(BoundsViolation::Left(bounds.min.x - body_min.x));
         }
         if body_max.x > bounds.max.x {
-            violations.push(crate::engine::world_bounds::BoundsViolation::Right(
-                body_max.x - bounds.max.x,
-            ));
+            violations.push(BoundsViolation::Right(body_max.x - bounds.max.x));
         }
         if body_min.y < bounds.min.y {
-            violations.push(crate::engine::world_bounds::BoundsViolation::Bottom(
-                bounds.min.y - body_min.y,
-            ));
+            violations.push(BoundsViolation::Bottom(bounds.min.y - body_min.y));
         }
         if body_max.y > bounds.max.y {
-            violations.push(crate::engine::world_bounds::BoundsViolation::Top(
-                body_max.y - bounds.max.y,
-            ));
+            violations.push(BoundsViolation::Top(body_max.y - bounds.max.y));
         }
 
         violations
@@ -782,104 +1635,125 @@ impl PhysicsWorld {
     fn get_body_bounds_static(body: &RigidBody) -> (Vec2, Vec2) {
         use crate::engine::CollisionShape;
 
-        match &body.collider.shape {
-            CollisionShape::Circle { radius } => {
-                let min = body.position - Vec2::splat(*radius);
-                let max = body.position + Vec2::splat(*radius);
-                (min, max)
-            }
-            CollisionShape::Rectangle { width, height } => {
-                let half_size = Vec2::new(*width * 0.5, *height * 0.5);
-                let min = body.position - half_size;
-                let max = body.position + half_size;
-                (min, max)
-            }
+        // Union of all collider AABBs
+        let mut min = Vec2::splat(f32::INFINITY);
+        let mut max = Vec2::splat(f32::NEG_INFINITY);
+
+        for (offset, collider) in body.collider_parts() {
+            let center = body.position + offset;
+            let half_size = match collider.shape {
+                CollisionShape::Circle { radius } => Vec2::splat(radius),
+                CollisionShape::Rectangle { width, height } => Vec2::new(width * 0.5, height * 0.5),
+            };
+            min = min.min(center - half_size);
+            max = max.max(center + half_size);
         }
+
+        (min, max)
     }
 
+    /// Push the body back inside the bounds and reflect the velocity into each wall it hit,
+    /// scaled by `restitution` (1.0 bounces perfectly, 0.0 sticks to the wall)
     fn clamp_to_bounds_static(body: &mut RigidBody, bounds: &WorldBounds, restitution: f32) {
+        let restitution = restitution.clamp(0.0, 1.0);
         let (body_min, body_max) = Self::get_body_bounds_static(body);
-        let mut position_changed = false;
-        let mut velocity_damped = false;
 
-        // Clamp X axis
-        if body_min.x < bounds.min.x {
-            let penetration = bounds.min.x - body_min.x;
-            body.position.x += penetration;
-            position_changed = true;
+        let (dx, hit_x) = Self::clamp_axis(
+            body_min.x,
+            body_max.x,
+            bounds.min.x,
+            bounds.max.x,
+            &mut body.velocity.x,
+            restitution,
+        );
+        let (dy, hit_y) = Self::clamp_axis(
+            body_min.y,
+            body_max.y,
+            bounds.min.y,
+            bounds.max.y,
+            &mut body.velocity.y,
+            restitution,
+        );
 
-            if body.velocity.x < 0.0 {
-                body.velocity.x = -body.velocity.x * restitution;
-                velocity_damped = true;
-            }
-        } else if body_max.x > bounds.max.x {
-            let penetration = body_max.x - bounds.max.x;
-            body.position.x -= penetration;
-            position_changed = true;
-
-            if body.velocity.x > 0.0 {
-                body.velocity.x = -body.velocity.x * restitution;
-                velocity_damped = true;
-            }
+        // Update collider position if body moved
+        if dx != 0.0 || dy != 0.0 {
+            body.position += Vec2::new(dx, dy);
+            body.sync_collider();
         }
 
-        // Clamp Y axis
-        if body_min.y < bounds.min.y {
-            let penetration = bounds.min.y - body_min.y;
-            body.position.y += penetration;
-            position_changed = true;
-
-            if body.velocity.y < 0.0 {
-                body.velocity.y = -body.velocity.y * restitution;
-                velocity_damped = true;
-            }
-        } else if body_max.y > bounds.max.y {
-            let penetration = body_max.y - bounds.max.y;
-            body.position.y -= penetration;
-            position_changed = true;
-
-            if body.velocity.y > 0.0 {
-                body.velocity.y = -body.velocity.y * restitution;
-                velocity_damped = true;
-            }
+        // Wake up body if it hit bounds
+        if (hit_x || hit_y) && body.is_sleeping {
+            body.wake_up();
         }
+    }
 
-        // Update collider position if body moved
-        if position_changed {
-            body.collider.position = body.position;
+    /// Correction along one axis and whether the velocity was reflected.
+    /// A body wider than the bounds can't fit, so it's centered and stopped on that axis
+    /// instead of being pushed back and forth between the two walls.
+    fn clamp_axis(
+        body_min: f32,
+        body_max: f32,
+        bounds_min: f32,
+        bounds_max: f32,
+        velocity: &mut f32,
+        restitution: f32,
+    ) -> (f32, bool) {
+        if body_max - body_min >= bounds_max - bounds_min {
+            if body_min >= bounds_min && body_max <= bounds_max {
+                return (0.0, false);
+            }
+            let correction = (bounds_min + bounds_max) * 0.5 - (body_min + body_max) * 0.5;
+            let hit = *velocity != 0.0;
+            *velocity = 0.0;
+            return (correction, hit);
         }
 
-        // Wake up body if it hit bounds
-        if velocity_damped && body.is_sleeping {
-            body.wake_up();
+        if body_min < bounds_min {
+            let hit = *velocity < 0.0;
+            if hit {
+                *velocity = -*velocity * restitution;
+            }
+            (bounds_min - body_min, hit)
+        } else if body_max > bounds_max {
+            let hit = *velocity > 0.0;
+            if hit {
+                *velocity = -*velocity * restitution;
+            }
+            (bounds_max - body_max, hit)
+        } else {
+            (0.0, false)
         }
     }
 
-    fn wrap_to_bounds_static(body: &mut RigidBody, bounds: &WorldBounds) {
-        let mut position_changed = false;
+    /// Wrap the body to the opposite side, returning the edges it crossed
+    fn wrap_to_bounds_static(body: &mut RigidBody, bounds: &WorldBounds) -> Vec<BoundsViolation> {
+        let mut wrapped = Vec::new();
 
         // Wrap X axis
         if body.position.x < bounds.min.x {
+            wrapped.push(BoundsViolation::Left(bounds.min.x - body.position.x));
             body.position.x = bounds.max.x - (bounds.min.x - body.position.x);
-            position_changed = true;
         } else if body.position.x > bounds.max.x {
+            wrapped.push(BoundsViolation::Right(body.position.x - bounds.max.x));
             body.position.x = bounds.min.x + (body.position.x - bounds.max.x);
-            position_changed = true;
         }
 
         // Wrap Y axis
         if body.position.y < bounds.min.y {
+            wrapped.push(BoundsViolation::Bottom(bounds.min.y - body.position.y));
             body.position.y = bounds.max.y - (bounds.min.y - body.position.y);
-            position_changed = true;
         } else if body.position.y > bounds.max.y {
+            wrapped.push(BoundsViolation::Top(body.position.y - bounds.max.y));
             body.position.y = bounds.min.y + (body.position.y - bounds.max.y);
-            position_changed = true;
         }
 
-        // Update collider position if body moved
-        if position_changed {
-            body.collider.position = body.position;
+        // Update collider position if body moved, and don't interpolate across the jump
+        if !wrapped.is_empty() {
+            body.sync_collider();
+            body.reset_interpolation();
         }
+
+        wrapped
     }
 
     fn is_beyond_safety_margin_static(
@@ -910,4 +1784,721 @@ pub struct PhysicsStats {
     pub active_bodies: usize,
     pub sleeping_bodies: usize,
     pub total_kinetic_energy: f32,
+    /// Timings below are only measured while profiling is enabled
+    pub profiling_enabled: bool,
+    /// Force and velocity integration time of the last step
+    pub integration_us: u64,
+    /// Collision pair detection time of the last step
+    pub detection_us: u64,
+    /// Overlap separation, collision resolution and bounds handling time of the last step
+    pub resolution_us: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dynamic_box(position: Vec2) -> RigidBody {
+        RigidBody::new_dynamic(
+            position,
+            Collider::new_rect(position.x, position.y, 10.0, 10.0),
+            1.0,
+        )
+    }
+
+    #[test]
+    fn stale_id_does_not_resolve_after_slot_reuse() {
+        let mut world = PhysicsWorld::new();
+        let removed = world.add_body(dynamic_box(Vec2::new(0.0, 0.0)));
+        let kept = world.add_body(dynamic_box(Vec2::new(100.0, 0.0)));
+        assert!(world.remove_body(removed).is_some());
+
+        let reused = world.add_body(dynamic_box(Vec2::new(200.0, 0.0)));
+        assert_eq!(reused.index(), removed.index());
+        assert_ne!(reused.generation(), removed.generation());
+
+        assert!(world.get_body(removed).is_none());
+        assert!(world.remove_body(removed).is_none());
+        assert_eq!(
+            world.get_body(reused).unwrap().position,
+            Vec2::new(200.0, 0.0)
+        );
+        assert_eq!(
+            world.get_body(kept).unwrap().position,
+            Vec2::new(100.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn is_body_alive_tracks_removal() {
+        let mut world = PhysicsWorld::new();
+        let id = world.add_body(dynamic_box(Vec2::ZERO));
+        assert!(world.is_body_alive(id));
+        assert!(!world.is_body_alive(BodyId::INVALID));
+
+        world.remove_body(id);
+        assert!(!world.is_body_alive(id));
+
+        let reused = world.add_body(dynamic_box(Vec2::ZERO));
+        assert!(world.is_body_alive(reused));
+        assert!(!world.is_body_alive(id));
+
+        world.clear_bodies();
+        assert!(!world.is_body_alive(reused));
+    }
+
+    #[test]
+    fn add_bodies_returns_distinct_ids_in_order() {
+        let mut world = PhysicsWorld::new();
+        let bodies = (0..100)
+            .map(|i| dynamic_box(Vec2::new(i as f32 * 20.0, 0.0)))
+            .collect();
+        let ids = world.add_bodies(bodies);
+
+        assert_eq!(ids.len(), 100);
+        assert_eq!(world.body_count(), 100);
+        let unique: HashSet<BodyId> = ids.iter().copied().collect();
+        assert_eq!(unique.len(), 100);
+        for (i, id) in ids.iter().enumerate() {
+            assert_eq!(world.get_body(*id).unwrap().position.x, i as f32 * 20.0);
+        }
+    }
+
+    #[test]
+    fn clamp_axis_reflects_velocity_off_the_right_wall() {
+        let mut velocity = 10.0;
+        let (correction, hit) =
+            PhysicsWorld::clamp_axis(95.0, 105.0, 0.0, 100.0, &mut velocity, 0.5);
+
+        assert!(hit);
+        assert_eq!(correction, -5.0);
+        assert_eq!(velocity, -5.0);
+    }
+
+    #[test]
+    fn clamp_axis_centers_a_body_wider_than_the_bounds() {
+        let mut velocity = 10.0;
+        let (correction, hit) =
+            PhysicsWorld::clamp_axis(30.0, 150.0, 0.0, 100.0, &mut velocity, 0.5);
+
+        assert!(hit);
+        // Body center 90 moves to the bounds center 50
+        assert_eq!(correction, -40.0);
+        assert_eq!(velocity, 0.0);
+    }
+
+    #[test]
+    fn substep_count_splits_long_frames() {
+        let mut world = PhysicsWorld::new();
+        world.set_substeps(2);
+        assert_eq!(world.substep_count(0.1), 2);
+
+        world.set_max_substep_dt(Some(0.25));
+        // The fixed count stays the minimum
+        assert_eq!(world.substep_count(0.1), 2);
+        assert_eq!(world.substep_count(0.6), 3);
+        assert_eq!(world.substep_count(1.0), 4);
+        // Hitches are capped
+        assert_eq!(
+            world.substep_count(100.0),
+            PhysicsWorld::MAX_DYNAMIC_SUBSTEPS
+        );
+
+        world.set_max_substep_dt(None);
+        assert_eq!(world.substep_count(10.0), 2);
+    }
+
+    fn total_energy(world: &PhysicsWorld) -> f32 {
+        world
+            .bodies()
+            .iter()
+            .map(|body| {
+                let angular = if body.moment_of_inertia.is_finite() {
+                    0.5 * body.moment_of_inertia * body.angular_velocity * body.angular_velocity
+                } else {
+                    0.0
+                };
+                body.kinetic_energy() + angular
+            })
+            .sum()
+    }
+
+    #[test]
+    fn energy_cap_keeps_a_closed_box_from_gaining_energy() {
+        const TOLERANCE: f32 = 0.01;
+
+        let mut world = PhysicsWorld::new();
+        world.set_sleep_enabled(false);
+        // Same setup as the physics test game, deep overlaps would add separation forces
+        world.set_substeps(8);
+        world.set_energy_cap(Some(TOLERANCE));
+        world.set_world_bounds(
+            Some(WorldBounds {
+                min: Vec2::ZERO,
+                max: Vec2::new(200.0, 200.0),
+            }),
+            BoundsBehavior::Clamp { restitution: 1.0 },
+        );
+        for i in 0..12 {
+            let position = Vec2::new(25.0 + (i % 4) as f32 * 50.0, 40.0 + (i / 4) as f32 * 60.0);
+            let velocity = Vec2::new(90.0 - i as f32 * 17.0, 40.0 + i as f32 * 11.0);
+            let body = RigidBody::new_dynamic(
+                position,
+                Collider::new_rect(position.x, position.y, 16.0, 16.0),
+                1.0 + i as f32 * 0.25,
+            )
+            .with_restitution(1.0)
+            .with_velocity(velocity);
+            world.add_body(body);
+        }
+
+        let initial = total_energy(&world);
+        let mut peak = initial;
+        for _ in 0..1200 {
+            world.step(1.0 / 60.0);
+            peak = peak.max(total_energy(&world));
+        }
+        assert!(
+            peak <= initial * (1.0 + TOLERANCE),
+            "energy rose from {initial} to {peak}"
+        );
+    }
+
+    #[test]
+    fn trigger_overlap_reports_penetration_without_pushing() {
+        let mut world = PhysicsWorld::new();
+        let mut sensor = Collider::new_rect(0.0, 0.0, 20.0, 20.0);
+        sensor.is_trigger = true;
+        let body = world.add_body(RigidBody::new_dynamic(Vec2::ZERO, sensor, 1.0));
+        world.add_body(RigidBody::new_static(
+            Vec2::new(0.0, 15.0),
+            Collider::new_rect(0.0, 15.0, 20.0, 20.0),
+        ));
+
+        // Separation forces land in the next step's integration, so step twice
+        world.step(1.0 / 60.0);
+        world.step(1.0 / 60.0);
+
+        let events = world.get_collision_events();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].is_trigger);
+        assert!((events[0].penetration - 5.0).abs() < 1e-4);
+        assert_eq!(world.get_body(body).unwrap().velocity, Vec2::ZERO);
+    }
+
+    #[test]
+    fn circle_against_rect_normal_points_from_circle_to_rect() {
+        let mut world = PhysicsWorld::new();
+        let circle = world.add_body(RigidBody::new_dynamic(
+            Vec2::ZERO,
+            Collider::new_circle(0.0, 0.0, 10.0),
+            1.0,
+        ));
+        world.add_body(RigidBody::new_static(
+            Vec2::new(15.0, 0.0),
+            Collider::new_rect(15.0, 0.0, 20.0, 20.0),
+        ));
+
+        world.step(1.0 / 60.0);
+
+        let events = world.get_collision_events();
+        assert_eq!(events.len(), 1);
+        assert!((events[0].normal - Vec2::X).length() < 1e-4);
+        // Position correction pushes the circle out of the rect, not through it
+        assert!(world.get_body(circle).unwrap().position.x < 0.0);
+    }
+
+    #[test]
+    fn both_limbs_of_a_compound_body_collide() {
+        let mut world = PhysicsWorld::new();
+        let l_shape = world.add_body(
+            RigidBody::new_static(Vec2::ZERO, Collider::new_rect(0.0, 0.0, 60.0, 20.0))
+                .with_compound_collider(
+                    Vec2::new(-20.0, 30.0),
+                    Collider::new_rect(0.0, 0.0, 20.0, 60.0),
+                ),
+        );
+        let on_bar = world.add_body(dynamic_box(Vec2::new(25.0, 0.0)));
+        let on_limb = world.add_body(dynamic_box(Vec2::new(-20.0, 55.0)));
+
+        world.step(1.0 / 60.0);
+        assert!(world.are_colliding(l_shape, on_bar));
+        assert!(world.are_colliding(l_shape, on_limb));
+
+        // A quarter turn swings the limb from +y to -x
+        let body = world.get_body_mut(l_shape).unwrap();
+        body.set_transform(Vec2::ZERO, std::f32::consts::FRAC_PI_2);
+        assert!(world
+            .query_point(Vec2::new(-30.0, -20.0))
+            .contains(&l_shape));
+        assert!(!world.query_point(Vec2::new(-20.0, 50.0)).contains(&l_shape));
+    }
+
+    #[test]
+    fn move_and_slide_slides_a_kinematic_body_along_a_wall() {
+        let mut world = PhysicsWorld::new();
+        world.add_body(RigidBody::new_static(
+            Vec2::new(20.0, 0.0),
+            Collider::new_rect(20.0, 0.0, 10.0, 100.0),
+        ));
+        let player = world.add_body(RigidBody::new_kinematic(
+            Vec2::ZERO,
+            Collider::new_rect(0.0, 0.0, 10.0, 10.0),
+        ));
+
+        // Moving diagonally into the wall keeps the vertical part of the move
+        let moved = world.move_and_slide(player, Vec2::new(12.0, 10.0));
+        assert!((moved - Vec2::new(10.0, 10.0)).length() < 1e-4);
+        let body = world.get_body(player).unwrap();
+        assert_eq!(body.render_position(0.0), body.position);
+
+        let crate_id = world.add_body(dynamic_box(Vec2::new(-50.0, 0.0)));
+        assert_eq!(world.move_and_slide(crate_id, Vec2::X), Vec2::ZERO);
+        assert_eq!(
+            world.get_body(crate_id).unwrap().position,
+            Vec2::new(-50.0, 0.0)
+        );
+    }
+
+    /// A 10x10 box at each position inside (0, 0)..(100, 100), bounds applied once
+    fn apply_bounds(behavior: BoundsBehavior, positions: &[Vec2]) -> PhysicsWorld {
+        let mut world = PhysicsWorld::new();
+        world.set_world_bounds(
+            Some(WorldBounds {
+                min: Vec2::ZERO,
+                max: Vec2::splat(100.0),
+            }),
+            behavior,
+        );
+        for &position in positions {
+            world.add_body(dynamic_box(position));
+        }
+        world.handle_world_bounds();
+        world
+    }
+
+    fn violations(world: &PhysicsWorld) -> Vec<(&'static str, f32)> {
+        world
+            .get_bounds_events()
+            .iter()
+            .map(|event| match event.violation {
+                BoundsViolation::Left(depth) => ("left", depth),
+                BoundsViolation::Right(depth) => ("right", depth),
+                BoundsViolation::Bottom(depth) => ("bottom", depth),
+                BoundsViolation::Top(depth) => ("top", depth),
+            })
+            .collect()
+    }
+
+    fn positions(world: &PhysicsWorld) -> Vec<Vec2> {
+        world.bodies.iter().map(|body| body.position).collect()
+    }
+
+    const STRADDLING_EACH_EDGE: [Vec2; 4] = [
+        Vec2::new(2.0, 50.0),
+        Vec2::new(98.0, 50.0),
+        Vec2::new(50.0, 2.0),
+        Vec2::new(50.0, 98.0),
+    ];
+
+    #[test]
+    fn bounds_events_report_each_edge_without_moving() {
+        let world = apply_bounds(BoundsBehavior::Events, &STRADDLING_EACH_EDGE);
+        assert_eq!(
+            violations(&world),
+            vec![("left", 3.0), ("right", 3.0), ("bottom", 3.0), ("top", 3.0)]
+        );
+        assert_eq!(positions(&world), STRADDLING_EACH_EDGE.to_vec());
+
+        let world = apply_bounds(BoundsBehavior::Ignore, &STRADDLING_EACH_EDGE);
+        assert!(violations(&world).is_empty());
+        assert_eq!(positions(&world), STRADDLING_EACH_EDGE.to_vec());
+    }
+
+    #[test]
+    fn bounds_clamp_pushes_each_edge_back_inside() {
+        let world = apply_bounds(
+            BoundsBehavior::Clamp { restitution: 0.5 },
+            &STRADDLING_EACH_EDGE,
+        );
+        assert_eq!(
+            violations(&world),
+            vec![("left", 3.0), ("right", 3.0), ("bottom", 3.0), ("top", 3.0)]
+        );
+        assert_eq!(
+            positions(&world),
+            vec![
+                Vec2::new(5.0, 50.0),
+                Vec2::new(95.0, 50.0),
+                Vec2::new(50.0, 5.0),
+                Vec2::new(50.0, 95.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn bounds_wrap_moves_each_edge_to_the_opposite_side() {
+        let outside = [
+            Vec2::new(-2.0, 50.0),
+            Vec2::new(102.0, 50.0),
+            Vec2::new(50.0, -2.0),
+            Vec2::new(50.0, 102.0),
+        ];
+        let world = apply_bounds(BoundsBehavior::Wrap, &outside);
+        assert_eq!(
+            violations(&world),
+            vec![("left", 2.0), ("right", 2.0), ("bottom", 2.0), ("top", 2.0)]
+        );
+        assert_eq!(
+            positions(&world),
+            vec![
+                Vec2::new(98.0, 50.0),
+                Vec2::new(2.0, 50.0),
+                Vec2::new(50.0, 98.0),
+                Vec2::new(50.0, 2.0),
+            ]
+        );
+        // Straddling an edge with the center still inside doesn't wrap
+        let world = apply_bounds(BoundsBehavior::Wrap, &STRADDLING_EACH_EDGE);
+        assert!(violations(&world).is_empty());
+    }
+
+    #[test]
+    fn bounds_delete_marks_bodies_past_the_safety_margin() {
+        let positions = [
+            Vec2::new(-10.0, 50.0),
+            Vec2::new(110.0, 50.0),
+            Vec2::new(50.0, -10.0),
+            Vec2::new(50.0, 110.0),
+            Vec2::new(-2.0, 50.0),
+        ];
+        let world = apply_bounds(BoundsBehavior::Delete { safety_margin: 5.0 }, &positions);
+        assert_eq!(
+            violations(&world),
+            vec![
+                ("left", 15.0),
+                ("right", 15.0),
+                ("bottom", 15.0),
+                ("top", 15.0)
+            ]
+        );
+        let marked: Vec<bool> = world
+            .bodies
+            .iter()
+            .map(|body| body.marked_for_deletion)
+            .collect();
+        assert_eq!(marked, vec![true, true, true, true, false]);
+    }
+
+    #[test]
+    fn coincident_circles_separate_without_nan() {
+        let mut world = PhysicsWorld::new();
+        let circle =
+            |mass| RigidBody::new_dynamic(Vec2::ZERO, Collider::new_circle(0.0, 0.0, 5.0), mass);
+        world.add_body(circle(1.0));
+        world.add_body(circle(2.0));
+
+        for _ in 0..3 {
+            world.step(1.0 / 60.0);
+        }
+        for body in &world.bodies {
+            assert!(body.position.is_finite(), "{:?}", body.position);
+            assert!(body.velocity.is_finite(), "{:?}", body.velocity);
+            assert!(body.rotation.is_finite());
+        }
+    }
+
+    #[test]
+    fn radial_impulse_pushes_nearer_bodies_harder() {
+        let mut world = PhysicsWorld::new();
+        let near = world.add_body(dynamic_box(Vec2::new(20.0, 0.0)));
+        let far = world.add_body(dynamic_box(Vec2::new(-60.0, 0.0)));
+        let outside = world.add_body(dynamic_box(Vec2::new(0.0, 200.0)));
+
+        world.apply_radial_impulse(Vec2::ZERO, 100.0, 1000.0, GravityFalloff::Linear);
+
+        let velocity = |id| world.get_body(id).unwrap().velocity;
+        // Outward from the center, and faster closer to it
+        assert!(velocity(near).x > 0.0);
+        assert!(velocity(far).x < 0.0);
+        assert!(velocity(near).length() > velocity(far).length());
+        assert_eq!(velocity(outside), Vec2::ZERO);
+    }
+
+    #[test]
+    fn longer_sleep_time_keeps_a_slow_body_awake() {
+        let run = |time_threshold: f32| {
+            let mut world = PhysicsWorld::new();
+            world.set_sleep_thresholds(0.1, time_threshold);
+            let id = world.add_body(dynamic_box(Vec2::ZERO).with_velocity(Vec2::new(0.05, 0.0)));
+            for _ in 0..15 {
+                world.step(0.1);
+            }
+            world.get_body(id).unwrap().is_sleeping()
+        };
+
+        assert!(run(1.0));
+        assert!(!run(2.0));
+    }
+
+    #[test]
+    fn add_body_returns_the_id_used_for_lookups() {
+        let mut world = PhysicsWorld::new();
+        let unadded = dynamic_box(Vec2::ZERO);
+        assert_eq!(unadded.id, BodyId::INVALID);
+
+        let first = world.add_body(unadded);
+        let second = world.add_body(dynamic_box(Vec2::new(50.0, 0.0)));
+        assert_ne!(first, second);
+
+        let body = world.get_body(second).unwrap();
+        assert_eq!(body.id, second);
+        assert_eq!(body.position, Vec2::new(50.0, 0.0));
+        assert_eq!(world.get_body(first).unwrap().id, first);
+    }
+
+    #[test]
+    fn box_resting_flat_on_a_platform_does_not_spin() {
+        let mut world = PhysicsWorld::new();
+        world.set_global_gravity(Vec2::new(0.0, -500.0));
+        world.add_body(RigidBody::new_static(
+            Vec2::new(0.0, -10.0),
+            Collider::new_rect(0.0, -10.0, 200.0, 20.0),
+        ));
+        let crate_id = world.add_body(RigidBody::new_dynamic(
+            Vec2::new(0.0, 9.9),
+            Collider::new_rect(0.0, 9.9, 20.0, 20.0),
+            1.0,
+        ));
+
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+        }
+        let body = world.get_body(crate_id).unwrap();
+        assert!(
+            body.angular_velocity.abs() < 1e-4,
+            "{}",
+            body.angular_velocity
+        );
+        assert!(body.rotation.abs() < 1e-4, "{}", body.rotation);
+        assert!((body.position.y - 10.0).abs() < 1.0, "{}", body.position.y);
+    }
+
+    #[test]
+    fn moving_kinematic_body_pushes_a_resting_box() {
+        let mut world = PhysicsWorld::new();
+        world.add_body(
+            RigidBody::new_kinematic(Vec2::ZERO, Collider::new_rect(0.0, 0.0, 10.0, 10.0))
+                .with_velocity(Vec2::new(100.0, 0.0)),
+        );
+        let box_id = world.add_body(dynamic_box(Vec2::new(10.5, 0.0)));
+
+        world.step(1.0 / 60.0);
+        let velocity = world.get_body(box_id).unwrap().velocity;
+        assert!(velocity.x > 0.0, "{velocity:?}");
+        assert!(velocity.y.abs() < 1e-4, "{velocity:?}");
+    }
+
+    #[test]
+    fn contact_modifier_restitution_overrides_the_materials() {
+        let bounce_velocity = |modifier: bool| {
+            let mut world = PhysicsWorld::new();
+            world.set_global_gravity(Vec2::ZERO);
+            if modifier {
+                world.set_contact_modifier(|_, _| (0.0, 0.0));
+            }
+            world.add_body(
+                RigidBody::new_static(
+                    Vec2::new(0.0, -10.0),
+                    Collider::new_rect(0.0, -10.0, 200.0, 20.0),
+                )
+                .with_restitution(1.0),
+            );
+            let ball = world.add_body(
+                dynamic_box(Vec2::new(0.0, 6.0))
+                    .with_velocity(Vec2::new(0.0, -300.0))
+                    .with_restitution(1.0),
+            );
+            for _ in 0..3 {
+                world.step(1.0 / 60.0);
+            }
+            world.get_body(ball).unwrap().velocity.y
+        };
+
+        assert!(bounce_velocity(false) > 100.0);
+        assert!(bounce_velocity(true).abs() < 1.0);
+    }
+
+    #[test]
+    fn world_aabb_encloses_every_body() {
+        let mut world = PhysicsWorld::new();
+        assert_eq!(world.world_aabb(), None);
+
+        world.add_body(dynamic_box(Vec2::new(-100.0, 20.0)));
+        world.add_body(dynamic_box(Vec2::new(50.0, -80.0)));
+        world.add_body(RigidBody::new_static(
+            Vec2::new(200.0, 300.0),
+            Collider::new_circle(200.0, 300.0, 15.0),
+        ));
+
+        assert_eq!(
+            world.world_aabb(),
+            Some((Vec2::new(-105.0, -85.0), Vec2::new(215.0, 315.0)))
+        );
+    }
+
+    #[test]
+    fn are_colliding_reports_only_touching_pairs() {
+        let mut world = PhysicsWorld::new();
+        let a = world.add_body(dynamic_box(Vec2::ZERO));
+        let b = world.add_body(dynamic_box(Vec2::new(8.0, 0.0)));
+        let far = world.add_body(dynamic_box(Vec2::new(100.0, 0.0)));
+
+        world.step(1.0 / 60.0);
+        assert!(world.are_colliding(a, b));
+        assert!(world.are_colliding(b, a));
+        assert!(!world.are_colliding(a, far));
+        assert_eq!(world.contact_count(a), 1);
+        assert_eq!(world.contact_count(far), 0);
+    }
+
+    #[test]
+    fn debug_draw_skips_inactive_and_hidden_bodies() {
+        let mut world = PhysicsWorld::new();
+        let shown = world.add_body(dynamic_box(Vec2::ZERO));
+        world.add_body(dynamic_box(Vec2::new(20.0, 0.0)).with_active(false));
+        world.add_body(dynamic_box(Vec2::new(40.0, 0.0)).with_debug_visible(false));
+        world.add_body(dynamic_box(Vec2::new(500.0, 0.0)));
+
+        let drawn: Vec<BodyId> = world
+            .debug_draw_bodies(Vec2::splat(-100.0), Vec2::splat(100.0))
+            .map(|body| body.id)
+            .collect();
+        assert_eq!(drawn, vec![shown]);
+    }
+
+    #[test]
+    fn more_solver_iterations_reduce_stack_penetration() {
+        let residual_penetration = |iterations: u32| {
+            let mut world = PhysicsWorld::new();
+            world.set_global_gravity(Vec2::new(0.0, -500.0));
+            world.set_solver_iterations(iterations);
+            world.add_body(RigidBody::new_static(
+                Vec2::new(0.0, -10.0),
+                Collider::new_rect(0.0, -10.0, 200.0, 20.0),
+            ));
+            for level in 0..3 {
+                world.add_body(dynamic_box(Vec2::new(0.0, 5.0 + level as f32 * 10.0)));
+            }
+            for _ in 0..90 {
+                world.step(1.0 / 60.0);
+            }
+
+            // Floor/bottom box, then each box on the one below
+            (0..3)
+                .filter_map(|i| world.solid_separation(i, i + 1))
+                .map(|(_, penetration)| penetration)
+                .sum::<f32>()
+        };
+
+        let single = residual_penetration(1);
+        let iterated = residual_penetration(8);
+        assert!(iterated <= single, "{iterated} > {single}");
+        assert!(iterated < 1.0, "{iterated}");
+    }
+
+    #[test]
+    fn entering_a_trigger_reports_it_without_slowing_the_body() {
+        let mut world = PhysicsWorld::new();
+        let trigger = world.add_trigger(Collider::new_rect(22.0, 0.0, 10.0, 10.0));
+        let velocity = Vec2::new(600.0, 0.0);
+        let body = world.add_body(dynamic_box(Vec2::ZERO).with_velocity(velocity));
+
+        world.step(1.0 / 60.0);
+        assert!(world.get_trigger_events().is_empty());
+
+        world.step(1.0 / 60.0);
+        let events = world.get_trigger_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].trigger_id, trigger);
+        assert_eq!(events[0].body_id, body);
+        assert_eq!(events[0].kind, TriggerEventKind::Enter);
+        assert_eq!(world.get_body(body).unwrap().velocity, velocity);
+    }
+
+    #[test]
+    fn ignored_pairs_pass_through_while_others_still_collide() {
+        let mut world = PhysicsWorld::new();
+        let a = world.add_body(dynamic_box(Vec2::ZERO));
+        let ghost =
+            world.add_body(dynamic_box(Vec2::new(8.0, 0.0)).with_velocity(Vec2::new(-100.0, 0.0)));
+        let solid =
+            world.add_body(dynamic_box(Vec2::new(-8.0, 0.0)).with_velocity(Vec2::new(100.0, 0.0)));
+        world.ignore_collision(a, ghost);
+
+        world.step(1.0 / 60.0);
+        assert!(!world.are_colliding(a, ghost));
+        assert!(world.are_colliding(a, solid));
+        assert_eq!(
+            world.get_body(ghost).unwrap().velocity,
+            Vec2::new(-100.0, 0.0)
+        );
+        assert!(world.get_body(solid).unwrap().velocity.x < 100.0);
+    }
+
+    #[test]
+    fn bodies_of_type_returns_only_that_type() {
+        let mut world = PhysicsWorld::new();
+        let first = world.add_body(dynamic_box(Vec2::ZERO));
+        let wall = world.add_body(RigidBody::new_static(
+            Vec2::new(100.0, 0.0),
+            Collider::new_rect(100.0, 0.0, 10.0, 10.0),
+        ));
+        let second = world.add_body(dynamic_box(Vec2::new(200.0, 0.0)));
+
+        let ids = |world: &PhysicsWorld, body_type| -> Vec<BodyId> {
+            world
+                .bodies_of_type(body_type)
+                .map(|body| body.id)
+                .collect()
+        };
+        assert_eq!(ids(&world, BodyType::Dynamic), vec![first, second]);
+        assert_eq!(ids(&world, BodyType::Static), vec![wall]);
+        assert!(ids(&world, BodyType::Kinematic).is_empty());
+    }
+
+    #[test]
+    fn gravity_from_angle_points_along_the_angle() {
+        let mut world = PhysicsWorld::new();
+        world.set_gravity_from_angle(0.0, 9.8);
+        assert_eq!(world.global_gravity(), Vec2::new(9.8, 0.0));
+
+        world.set_gravity_from_angle(-std::f32::consts::FRAC_PI_2, 9.8);
+        assert!((world.global_gravity() - Vec2::new(0.0, -9.8)).length() < 1e-5);
+
+        world.rotate_gravity(std::f32::consts::PI);
+        assert!((world.global_gravity() - Vec2::new(0.0, 9.8)).length() < 1e-5);
+    }
+
+    #[test]
+    fn only_self_colliding_groups_collide_internally() {
+        const SWARM: u32 = 1;
+        const CRATES: u32 = 2;
+        let mut world = PhysicsWorld::new();
+        world.set_group_self_collide(SWARM, false);
+        let bee = world.add_body(dynamic_box(Vec2::ZERO).with_collision_group(SWARM));
+        let other_bee =
+            world.add_body(dynamic_box(Vec2::new(8.0, 0.0)).with_collision_group(SWARM));
+        let crate_a =
+            world.add_body(dynamic_box(Vec2::new(100.0, 0.0)).with_collision_group(CRATES));
+        let crate_b =
+            world.add_body(dynamic_box(Vec2::new(108.0, 0.0)).with_collision_group(CRATES));
+        let outsider = world.add_body(dynamic_box(Vec2::new(0.0, 8.0)));
+
+        world.step(1.0 / 60.0);
+        assert!(!world.are_colliding(bee, other_bee));
+        assert!(world.are_colliding(crate_a, crate_b));
+        // Members still collide with bodies outside their group
+        assert!(world.are_colliding(bee, outsider));
+    }
 }
@@ -1,8 +1,30 @@
 use crate::engine::{gravity::GravityField, world_bounds::BoundsBehavior, Collider};
-use glam::Vec2;
+use glam::{Vec2, Vec4};
 
+/// Handle to a body in a `PhysicsWorld`.
+/// The generation changes whenever a slot is freed, so ids of removed bodies stay invalid
+/// even after their slot is reused.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct BodyId(pub u32);
+pub struct BodyId {
+    pub(crate) index: u32,
+    pub(crate) generation: u32,
+}
+
+impl BodyId {
+    /// Placeholder for bodies not yet added to a world
+    pub const INVALID: BodyId = BodyId {
+        index: u32::MAX,
+        generation: 0,
+    };
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BodyType {
@@ -33,6 +55,7 @@ impl Default for PhysicsMaterial {
 
 #[derive(Debug, Clone)]
 pub struct RigidBody {
+    /// Assigned by `PhysicsWorld::add_body`, `BodyId::INVALID` until then
     pub id: BodyId,
 
     pub body_type: BodyType,
@@ -45,6 +68,10 @@ pub struct RigidBody {
 
     pub material: PhysicsMaterial,
     pub collider: Collider,
+    /// Collider center relative to the body position
+    pub local_offset: Vec2,
+    /// Extra colliders for compound shapes, as (offset from body position, collider)
+    pub(crate) compound_colliders: Vec<(Vec2, Collider)>,
     pub gravity_field: Option<GravityField>,
     pub marked_for_deletion: bool,
 
@@ -54,8 +81,20 @@ pub struct RigidBody {
     pub moment_of_inertia: f32,
 
     pub bounds_behavior: Option<BoundsBehavior>,
+    /// Group for `PhysicsWorld::set_group_self_collide`, e.g. all bullets
+    pub collision_group: Option<u32>,
+
+    /// Override for the collider color in physics debug rendering
+    pub debug_color: Option<Vec4>,
+    /// Inactive bodies stay in the world but are skipped by the solver and debug rendering
+    pub active: bool,
+    /// Draw this body's colliders in physics debug rendering
+    pub debug_visible: bool,
 
     // Internal state
+    /// Transform before the last `PhysicsWorld::step`, for render interpolation
+    pub(crate) previous_position: Vec2,
+    pub(crate) previous_rotation: f32,
     pub(crate) torque_accumulator: f32,
     pub(crate) force_accumulator: Vec2,
     pub(crate) is_sleeping: bool,
@@ -63,12 +102,15 @@ pub struct RigidBody {
 }
 
 impl RigidBody {
+    pub const DEFAULT_SLEEP_VELOCITY_THRESHOLD: f32 = 0.1;
+    pub const DEFAULT_SLEEP_TIME_THRESHOLD: f32 = 1.0;
+
     /// Create a new dynamic rigid body
-    pub fn new_dynamic(id: BodyId, position: Vec2, collider: Collider, mass: f32) -> Self {
+    pub fn new_dynamic(position: Vec2, collider: Collider, mass: f32) -> Self {
         let moment_of_inertia = Self::calculate_moment_of_inertia(&collider, mass);
 
         Self {
-            id,
+            id: BodyId::INVALID,
             body_type: BodyType::Dynamic,
             position,
             velocity: Vec2::ZERO,
@@ -76,6 +118,8 @@ impl RigidBody {
             mass: mass.max(0.001), // Prevent division by zero
             material: PhysicsMaterial::default(),
             collider,
+            local_offset: Vec2::ZERO,
+            compound_colliders: Vec::new(),
             gravity_field: None,
             marked_for_deletion: false,
 
@@ -85,7 +129,13 @@ impl RigidBody {
             moment_of_inertia,
 
             bounds_behavior: None,
+            debug_color: None,
+            collision_group: None,
+            active: true,
+            debug_visible: true,
 
+            previous_position: position,
+            previous_rotation: 0.0,
             torque_accumulator: 0.0,
             force_accumulator: Vec2::ZERO,
             is_sleeping: false,
@@ -94,11 +144,11 @@ impl RigidBody {
     }
 
     /// Create a new static rigid body (walls, platforms)
-    pub fn new_static(id: BodyId, position: Vec2, collider: Collider) -> Self {
+    pub fn new_static(position: Vec2, collider: Collider) -> Self {
         let moment_of_inertia = Self::calculate_moment_of_inertia(&collider, f32::INFINITY);
 
         Self {
-            id,
+            id: BodyId::INVALID,
             body_type: BodyType::Static,
             position,
             velocity: Vec2::ZERO,
@@ -106,6 +156,8 @@ impl RigidBody {
             mass: f32::INFINITY,
             material: PhysicsMaterial::default(),
             collider,
+            local_offset: Vec2::ZERO,
+            compound_colliders: Vec::new(),
             gravity_field: None,
             marked_for_deletion: false,
 
@@ -115,7 +167,13 @@ impl RigidBody {
             moment_of_inertia,
 
             bounds_behavior: Some(BoundsBehavior::Ignore),
+            debug_color: None,
+            collision_group: None,
+            active: true,
+            debug_visible: true,
 
+            previous_position: position,
+            previous_rotation: 0.0,
             torque_accumulator: 0.0,
             force_accumulator: Vec2::ZERO,
             is_sleeping: true, // Static bodies are always "sleeping"
@@ -124,11 +182,11 @@ impl RigidBody {
     }
 
     /// Create a new kinematic rigid body (moving platforms)
-    pub fn new_kinematic(id: BodyId, position: Vec2, collider: Collider) -> Self {
+    pub fn new_kinematic(position: Vec2, collider: Collider) -> Self {
         let moment_of_inertia = Self::calculate_moment_of_inertia(&collider, f32::INFINITY);
 
         Self {
-            id,
+            id: BodyId::INVALID,
             body_type: BodyType::Kinematic,
             position,
             velocity: Vec2::ZERO,
@@ -136,6 +194,8 @@ impl RigidBody {
             mass: f32::INFINITY,
             material: PhysicsMaterial::default(),
             collider,
+            local_offset: Vec2::ZERO,
+            compound_colliders: Vec::new(),
             gravity_field: None,
             marked_for_deletion: false,
 
@@ -145,7 +205,13 @@ impl RigidBody {
             moment_of_inertia,
 
             bounds_behavior: None,
+            debug_color: None,
+            collision_group: None,
+            active: true,
+            debug_visible: true,
 
+            previous_position: position,
+            previous_rotation: 0.0,
             torque_accumulator: 0.0,
             force_accumulator: Vec2::ZERO,
             is_sleeping: false,
@@ -222,12 +288,48 @@ impl RigidBody {
     /// Set position directly
     pub fn set_position(&mut self, position: Vec2) {
         self.position = position;
-        self.collider.position = position;
+        self.sync_collider();
         if self.body_type == BodyType::Dynamic {
             self.wake_up();
         }
     }
 
+    /// Position and rotation together
+    pub fn transform(&self) -> (Vec2, f32) {
+        (self.position, self.rotation)
+    }
+
+    /// Set position and rotation in one call, moving the colliders with them
+    pub fn set_transform(&mut self, position: Vec2, rotation: f32) {
+        self.rotation = rotation;
+        self.set_position(position);
+    }
+
+    /// Position and rotation before the last physics step
+    pub fn previous_transform(&self) -> (Vec2, f32) {
+        (self.previous_position, self.previous_rotation)
+    }
+
+    /// Position between the previous and current step, `alpha` 0 is the previous step
+    /// and 1 the current one, e.g. the leftover fraction of a fixed timestep
+    pub fn render_position(&self, alpha: f32) -> Vec2 {
+        self.previous_position
+            .lerp(self.position, alpha.clamp(0.0, 1.0))
+    }
+
+    /// Rotation between the previous and current step, see `render_position`
+    pub fn render_rotation(&self, alpha: f32) -> f32 {
+        let alpha = alpha.clamp(0.0, 1.0);
+        self.previous_rotation + (self.rotation - self.previous_rotation) * alpha
+    }
+
+    /// Make the previous transform the current one so the next frame doesn't
+    /// interpolate across a teleport
+    pub fn reset_interpolation(&mut self) {
+        self.previous_position = self.position;
+        self.previous_rotation = self.rotation;
+    }
+
     /// Wake up the body (stop it from sleeping)
     pub fn wake_up(&mut self) {
         if self.body_type == BodyType::Dynamic {
@@ -236,14 +338,19 @@ impl RigidBody {
         }
     }
 
-    /// Check if the body should go to sleep (performance optimization)
+    /// Check if the body should go to sleep using the default thresholds
     pub fn should_sleep(&self) -> bool {
-        const SLEEP_VELOCITY_THRESHOLD: f32 = 0.1;
-        const SLEEP_TIME_THRESHOLD: f32 = 1.0;
+        self.should_sleep_with(
+            Self::DEFAULT_SLEEP_VELOCITY_THRESHOLD,
+            Self::DEFAULT_SLEEP_TIME_THRESHOLD,
+        )
+    }
 
+    /// Check if the body has been slower than `velocity_threshold` for longer than `time_threshold`
+    pub fn should_sleep_with(&self, velocity_threshold: f32, time_threshold: f32) -> bool {
         self.body_type == BodyType::Dynamic
-            && self.velocity.length() < SLEEP_VELOCITY_THRESHOLD
-            && self.sleep_timer > SLEEP_TIME_THRESHOLD
+            && self.velocity.length() < velocity_threshold
+            && self.sleep_timer > time_threshold
     }
 
     /// Get the current kinetic energy of the body
@@ -258,6 +365,123 @@ impl RigidBody {
     pub fn clear_forces(&mut self) {
         self.force_accumulator = Vec2::ZERO;
     }
+
+    /// Move the colliders to the body position plus their offsets, rotated with the body
+    pub(crate) fn sync_collider(&mut self) {
+        let rotation = Vec2::from_angle(self.rotation);
+        self.collider.position = self.position + rotation.rotate(self.local_offset);
+        for (offset, collider) in &mut self.compound_colliders {
+            collider.position = self.position + rotation.rotate(*offset);
+        }
+    }
+
+    /// Primary collider followed by any compound colliders
+    pub fn colliders(&self) -> impl Iterator<Item = &Collider> {
+        std::iter::once(&self.collider).chain(self.compound_colliders.iter().map(|(_, c)| c))
+    }
+
+    /// All colliders with their current (rotated) offsets from the body position
+    pub(crate) fn collider_parts(&self) -> impl Iterator<Item = (Vec2, &Collider)> {
+        let rotation = Vec2::from_angle(self.rotation);
+        std::iter::once((rotation.rotate(self.local_offset), &self.collider)).chain(
+            self.compound_colliders
+                .iter()
+                .map(move |(o, c)| (rotation.rotate(*o), c)),
+        )
+    }
+
+    /// Attach another collider at an offset from the body position. The offset turns
+    /// with the body's rotation and `mass` is shared across all parts for the inertia.
+    pub fn add_compound_collider(&mut self, offset: Vec2, collider: Collider) {
+        self.compound_colliders.push((offset, collider));
+        self.sync_collider();
+        self.update_moment_of_inertia();
+    }
+
+    pub fn clear_compound_colliders(&mut self) {
+        self.compound_colliders.clear();
+        self.update_moment_of_inertia();
+    }
+
+    /// Set the collider offset relative to the body position
+    pub fn set_local_offset(&mut self, offset: Vec2) {
+        self.local_offset = offset;
+        self.sync_collider();
+        self.update_moment_of_inertia();
+    }
+
+    /// Recompute the inertia about the body position from every collider part.
+    /// Mass is split between parts by area, each offset adds `m * d²` (parallel axis).
+    fn update_moment_of_inertia(&mut self) {
+        use crate::engine::CollisionShape;
+
+        if !self.mass.is_finite() {
+            self.moment_of_inertia = f32::INFINITY;
+            return;
+        }
+
+        let parts: Vec<(Vec2, &Collider)> = std::iter::once((self.local_offset, &self.collider))
+            .chain(self.compound_colliders.iter().map(|(o, c)| (*o, c)))
+            .collect();
+        let area = |collider: &Collider| match collider.shape {
+            CollisionShape::Circle { radius } => std::f32::consts::PI * radius * radius,
+            CollisionShape::Rectangle { width, height } => width * height,
+        };
+        let total_area: f32 = parts.iter().map(|(_, c)| area(c)).sum();
+
+        self.moment_of_inertia = parts
+            .iter()
+            .map(|(offset, collider)| {
+                let share = if total_area > 0.0 {
+                    area(collider) / total_area
+                } else {
+                    1.0 / parts.len() as f32
+                };
+                let mass = self.mass * share;
+                Self::calculate_moment_of_inertia(collider, mass) + mass * offset.length_squared()
+            })
+            .sum();
+    }
+
+    pub fn is_sleeping(&self) -> bool {
+        self.is_sleeping
+    }
+
+    /// Enable or disable the body without removing it from the world
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+        if active {
+            self.wake_up();
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn set_debug_visible(&mut self, visible: bool) {
+        self.debug_visible = visible;
+    }
+
+    /// Set or clear the debug render color override
+    pub fn set_debug_color(&mut self, color: Option<Vec4>) {
+        self.debug_color = color;
+    }
+
+    /// Color used when drawing this body's collider in debug mode.
+    /// Uses `debug_color` if set, otherwise static = green, sleeping = gray,
+    /// dynamic = red, kinematic = blue.
+    pub fn debug_render_color(&self) -> Vec4 {
+        if let Some(color) = self.debug_color {
+            return color;
+        }
+        match self.body_type {
+            BodyType::Static => Vec4::new(0.0, 1.0, 0.0, 1.0),
+            BodyType::Dynamic if self.is_sleeping => Vec4::new(0.5, 0.5, 0.5, 1.0),
+            BodyType::Dynamic => Vec4::new(1.0, 0.0, 0.0, 1.0),
+            BodyType::Kinematic => Vec4::new(0.0, 0.4, 1.0, 1.0),
+        }
+    }
 }
 
 /// Builder pattern for useful properties
@@ -292,6 +516,18 @@ impl RigidBody {
         self
     }
 
+    /// Offset the collider from the body position (e.g. a hitbox at the torso)
+    pub fn with_local_offset(mut self, offset: Vec2) -> Self {
+        self.set_local_offset(offset);
+        self
+    }
+
+    /// Attach another collider at an offset, building up a compound shape
+    pub fn with_compound_collider(mut self, offset: Vec2, collider: Collider) -> Self {
+        self.add_compound_collider(offset, collider);
+        self
+    }
+
     /// Replace the full physics material
     pub fn with_material(mut self, material: PhysicsMaterial) -> Self {
         self.material = material;
@@ -321,8 +557,130 @@ impl RigidBody {
         self
     }
 
+    /// Start the body enabled or disabled, see `set_active`
+    pub fn with_active(mut self, active: bool) -> Self {
+        self.active = active;
+        self
+    }
+
+    /// Show the body in physics debug rendering (the default) or hide it
+    pub fn with_debug_visible(mut self, visible: bool) -> Self {
+        self.debug_visible = visible;
+        self
+    }
+
+    /// Override the collider color in physics debug rendering
+    pub fn with_debug_color(mut self, color: Vec4) -> Self {
+        self.debug_color = Some(color);
+        self
+    }
+
+    pub fn with_collision_group(mut self, group: u32) -> Self {
+        self.collision_group = Some(group);
+        self
+    }
+
     /// Add a gravity field to an existing body
     pub fn set_gravity_field(&mut self, gravity_field: Option<GravityField>) {
         self.gravity_field = gravity_field;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_transform_interpolates_between_steps() {
+        let mut body = RigidBody::new_dynamic(
+            Vec2::new(10.0, 20.0),
+            Collider::new_circle(10.0, 20.0, 5.0),
+            1.0,
+        );
+        body.set_transform(Vec2::new(30.0, 40.0), 1.0);
+
+        assert_eq!(body.render_position(0.5), Vec2::new(20.0, 30.0));
+        assert_eq!(body.render_rotation(0.5), 0.5);
+        assert_eq!(body.render_position(2.0), Vec2::new(30.0, 40.0));
+
+        body.reset_interpolation();
+        assert_eq!(body.render_position(0.0), Vec2::new(30.0, 40.0));
+    }
+
+    fn l_shape(position: Vec2) -> RigidBody {
+        RigidBody::new_dynamic(position, Collider::new_rect(0.0, 0.0, 60.0, 20.0), 1.0)
+            .with_compound_collider(
+                Vec2::new(-20.0, 30.0),
+                Collider::new_rect(0.0, 0.0, 20.0, 60.0),
+            )
+    }
+
+    #[test]
+    fn compound_offsets_rotate_with_the_body_and_add_inertia() {
+        let single =
+            RigidBody::new_dynamic(Vec2::ZERO, Collider::new_rect(0.0, 0.0, 60.0, 20.0), 1.0);
+        let mut body = l_shape(Vec2::ZERO);
+        assert!(body.moment_of_inertia > single.moment_of_inertia);
+
+        body.set_transform(Vec2::new(100.0, 0.0), std::f32::consts::FRAC_PI_2);
+        let limb = body.colliders().nth(1).unwrap().position;
+        assert!((limb - Vec2::new(70.0, -20.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn debug_color_reflects_body_type_and_sleep() {
+        let collider = Collider::new_circle(0.0, 0.0, 5.0);
+        let red = Vec4::new(1.0, 0.0, 0.0, 1.0);
+        let gray = Vec4::new(0.5, 0.5, 0.5, 1.0);
+
+        let mut dynamic = RigidBody::new_dynamic(Vec2::ZERO, collider, 1.0);
+        assert_eq!(dynamic.debug_render_color(), red);
+        dynamic.is_sleeping = true;
+        assert_eq!(dynamic.debug_render_color(), gray);
+
+        let static_body = RigidBody::new_static(Vec2::ZERO, collider);
+        assert_eq!(
+            static_body.debug_render_color(),
+            Vec4::new(0.0, 1.0, 0.0, 1.0)
+        );
+        let kinematic = RigidBody::new_kinematic(Vec2::ZERO, collider);
+        assert_eq!(
+            kinematic.debug_render_color(),
+            Vec4::new(0.0, 0.4, 1.0, 1.0)
+        );
+
+        // The override wins over type and sleep state
+        dynamic.set_debug_color(Some(Vec4::ONE));
+        assert_eq!(dynamic.debug_render_color(), Vec4::ONE);
+    }
+
+    #[test]
+    fn collider_tracks_position_plus_offset_after_moving() {
+        let mut body = RigidBody::new_dynamic(
+            Vec2::new(10.0, 10.0),
+            Collider::new_rect(0.0, 0.0, 8.0, 16.0),
+            1.0,
+        )
+        .with_local_offset(Vec2::new(0.0, 12.0));
+        assert_eq!(body.collider.position, Vec2::new(10.0, 22.0));
+
+        body.set_position(Vec2::new(-5.0, 40.0));
+        assert_eq!(body.collider.position, Vec2::new(-5.0, 52.0));
+    }
+
+    #[test]
+    fn set_transform_moves_position_rotation_and_collider_together() {
+        let mut body =
+            RigidBody::new_dynamic(Vec2::ZERO, Collider::new_rect(0.0, 0.0, 8.0, 4.0), 1.0)
+                .with_local_offset(Vec2::new(12.0, 0.0));
+        body.is_sleeping = true;
+
+        body.set_transform(Vec2::new(50.0, 50.0), std::f32::consts::FRAC_PI_2);
+        assert_eq!(
+            body.transform(),
+            (Vec2::new(50.0, 50.0), std::f32::consts::FRAC_PI_2)
+        );
+        assert!((body.collider.position - Vec2::new(50.0, 62.0)).length() < 1e-4);
+        assert!(!body.is_sleeping());
+    }
+}
@@ -0,0 +1,26 @@
+use crate::engine::{collision::Collider, rigid_body::BodyId};
+
+/// Handle to a trigger region added with `PhysicsWorld::add_trigger`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TriggerId(pub(crate) u32);
+
+/// Sensor region (checkpoint, damage zone) that reports bodies entering and leaving it.
+/// Unlike a static body with an `is_trigger` collider it has no mass and is never integrated.
+#[derive(Debug, Clone)]
+pub struct Trigger {
+    pub id: TriggerId,
+    pub collider: Collider,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEventKind {
+    Enter,
+    Exit,
+}
+
+#[derive(Debug, Clone)]
+pub struct TriggerEvent {
+    pub trigger_id: TriggerId,
+    pub body_id: BodyId,
+    pub kind: TriggerEventKind,
+}
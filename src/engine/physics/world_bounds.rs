@@ -1,4 +1,5 @@
 use glam::Vec2;
+use rand::Rng;
 
 use crate::engine::rigid_body::BodyId;
 
@@ -8,6 +9,28 @@ pub struct WorldBounds {
     pub max: Vec2,
 }
 
+impl WorldBounds {
+    /// Uniform random position inside the bounds
+    pub fn random_point(&self, rng: &mut impl Rng) -> Vec2 {
+        self.random_point_inset(rng, 0.0)
+    }
+
+    /// Uniform random position at least `margin` away from every edge.
+    /// Falls back to the center on an axis narrower than twice the margin.
+    pub fn random_point_inset(&self, rng: &mut impl Rng, margin: f32) -> Vec2 {
+        let min = self.min + Vec2::splat(margin);
+        let max = self.max - Vec2::splat(margin);
+        let mut axis = |min: f32, max: f32| {
+            if min < max {
+                rng.random_range(min..max)
+            } else {
+                (min + max) * 0.5
+            }
+        };
+        Vec2::new(axis(min.x, max.x), axis(min.y, max.y))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum BoundsBehavior {
     /// Ignore bounds completely (infinit world)
@@ -38,3 +61,27 @@ pub enum BoundsViolation {
     Top(f32),
     Bottom(f32),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::GameRng;
+
+    #[test]
+    fn inset_points_stay_inside_the_margin() {
+        let bounds = WorldBounds {
+            min: Vec2::new(-100.0, 0.0),
+            max: Vec2::new(100.0, 50.0),
+        };
+        let mut rng = GameRng::new(7);
+        for _ in 0..200 {
+            let point = bounds.random_point_inset(&mut rng, 10.0);
+            assert!(point.x >= -90.0 && point.x < 90.0, "{point:?}");
+            assert!(point.y >= 10.0 && point.y < 40.0, "{point:?}");
+        }
+
+        // Narrower than twice the margin falls back to the center on that axis
+        let point = bounds.random_point_inset(&mut rng, 30.0);
+        assert_eq!(point.y, 25.0);
+    }
+}
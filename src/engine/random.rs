@@ -0,0 +1,60 @@
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+/// Seeded random number generator owned by the engine and shared through
+/// `EngineServices::rng`. Gameplay randomness drawn from it repeats exactly when a
+/// recorded replay restarts it from the same seed.
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Seed the current sequence started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restart the sequence from `seed`
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng.fill_bytes(dst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn same_seed_gives_the_same_sequence() {
+        let mut a = GameRng::new(42);
+        let mut b = GameRng::new(42);
+        let first: Vec<f32> = (0..8).map(|_| a.random_range(0.0..1.0)).collect();
+        let second: Vec<f32> = (0..8).map(|_| b.random_range(0.0..1.0)).collect();
+        assert_eq!(first, second);
+
+        a.reseed(42);
+        assert_eq!(a.random_range(0.0..1.0), first[0]);
+    }
+}
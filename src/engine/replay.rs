@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use sokol::app as sapp;
+use std::{error::Error, fs, path::Path};
+
+use crate::engine::InputManager;
+
+/// A single input event captured during a frame
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedInput {
+    KeyDown(usize),
+    KeyUp(usize),
+    MouseMove { x: f32, y: f32 },
+    MouseDown(usize),
+    MouseUp(usize),
+    MouseScroll(f32),
+}
+
+impl RecordedInput {
+    /// Convert a sokol event into a recordable input, if it affects the input manager
+    pub fn from_event(event: &sapp::Event) -> Option<Self> {
+        match event._type {
+            sapp::EventType::KeyDown => Some(Self::KeyDown(event.key_code as usize)),
+            sapp::EventType::KeyUp => Some(Self::KeyUp(event.key_code as usize)),
+            sapp::EventType::MouseMove => Some(Self::MouseMove {
+                x: event.mouse_x,
+                y: event.mouse_y,
+            }),
+            sapp::EventType::MouseDown => Some(Self::MouseDown(event.mouse_button as usize)),
+            sapp::EventType::MouseUp => Some(Self::MouseUp(event.mouse_button as usize)),
+            sapp::EventType::MouseScroll => Some(Self::MouseScroll(event.scroll_y)),
+            _ => None,
+        }
+    }
+
+    /// Feed this input into the input manager as if it came from a live event
+    pub fn apply(&self, input: &mut InputManager) {
+        match *self {
+            Self::KeyDown(key) => input.handle_key_down_index(key),
+            Self::KeyUp(key) => input.handle_key_up_index(key),
+            Self::MouseMove { x, y } => input.handle_mouse_move(x, y),
+            Self::MouseDown(button) => input.handle_mouse_button_down_index(button),
+            Self::MouseUp(button) => input.handle_mouse_button_up_index(button),
+            Self::MouseScroll(delta) => input.handle_mouse_wheel(delta),
+        }
+    }
+}
+
+/// Inputs received before one game update, plus the unscaled dt it ran with
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub dt: f32,
+    pub inputs: Vec<RecordedInput>,
+}
+
+/// A recorded play session, saved to disk as JSON
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    /// Seed the engine `GameRng` started from, playback restarts it from the same seed
+    #[serde(default)]
+    pub seed: u64,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Load a replay from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        let replay = serde_json::from_str(&contents)?;
+        Ok(replay)
+    }
+
+    /// Save the replay to a JSON file, creating parent directories as needed
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        let contents = serde_json::to_string(self)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+/// Collects inputs frame by frame while recording
+pub struct ReplayRecorder {
+    path: String,
+    replay: Replay,
+    pending: Vec<RecordedInput>,
+}
+
+impl ReplayRecorder {
+    /// Recorder saving to `path`, for a session whose engine RNG started from `seed`
+    pub fn new(path: &str, seed: u64) -> Self {
+        Self {
+            path: path.to_string(),
+            replay: Replay::new().with_seed(seed),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Record an event for the frame currently being collected
+    pub fn record_event(&mut self, event: &sapp::Event) {
+        if let Some(input) = RecordedInput::from_event(event) {
+            self.record_input(input);
+        }
+    }
+
+    pub fn record_input(&mut self, input: RecordedInput) {
+        self.pending.push(input);
+    }
+
+    /// Close the current frame after the game has updated with `dt`
+    pub fn end_frame(&mut self, dt: f32) {
+        self.replay.frames.push(ReplayFrame {
+            dt,
+            inputs: std::mem::take(&mut self.pending),
+        });
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Write the recording to the path it was created with
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        self.replay.save(&self.path)
+    }
+}
+
+/// Steps through a recorded replay one frame at a time
+pub struct ReplayPlayer {
+    replay: Replay,
+    frame: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, frame: 0 }
+    }
+
+    /// Next recorded frame, or None once the replay is exhausted
+    pub fn next_frame(&mut self) -> Option<&ReplayFrame> {
+        let frame = self.replay.frames.get(self.frame)?;
+        self.frame += 1;
+        Some(frame)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.replay.frames.len()
+    }
+
+    pub fn current_frame(&self) -> usize {
+        self.frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::GameRng;
+    use glam::Vec2;
+    use rand::Rng;
+
+    /// Stand-in for a game update driven by both input and engine randomness
+    fn update(position: &mut Vec2, input: &InputManager, rng: &mut GameRng, dt: f32) {
+        if input.is_key_down(sapp::Keycode::Right) {
+            position.x += 100.0 * dt;
+        }
+        if input.is_mouse_button_pressed(sapp::Mousebutton::Left) {
+            *position = input.mouse_position();
+        }
+        *position += Vec2::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0));
+    }
+
+    #[test]
+    fn replaying_a_recording_reaches_the_same_state() {
+        let right = sapp::Keycode::Right as usize;
+        let left_button = sapp::Mousebutton::Left as usize;
+        let session = [
+            (1.0 / 60.0, vec![RecordedInput::KeyDown(right)]),
+            (1.0 / 60.0, vec![]),
+            (
+                1.0 / 30.0,
+                vec![
+                    RecordedInput::MouseMove { x: 40.0, y: 25.0 },
+                    RecordedInput::MouseDown(left_button),
+                ],
+            ),
+            (
+                1.0 / 60.0,
+                vec![
+                    RecordedInput::KeyUp(right),
+                    RecordedInput::MouseUp(left_button),
+                ],
+            ),
+        ];
+
+        let mut rng = GameRng::new(1234);
+        let mut recorder = ReplayRecorder::new("unused.json", rng.seed());
+        let mut live_input = InputManager::new();
+        let mut live = Vec2::ZERO;
+        for (dt, inputs) in &session {
+            for recorded in inputs {
+                recorded.apply(&mut live_input);
+                recorder.record_input(recorded.clone());
+            }
+            update(&mut live, &live_input, &mut rng, *dt);
+            recorder.end_frame(*dt);
+            live_input.new_frame();
+        }
+
+        // Through JSON, like a saved file
+        let json = serde_json::to_string(recorder.replay()).unwrap();
+        let replay: Replay = serde_json::from_str(&json).unwrap();
+
+        let mut rng = GameRng::new(replay.seed);
+        let mut player = ReplayPlayer::new(replay);
+        let mut input = InputManager::new();
+        let mut replayed = Vec2::ZERO;
+        while let Some(frame) = player.next_frame() {
+            for recorded in &frame.inputs {
+                recorded.apply(&mut input);
+            }
+            update(&mut replayed, &input, &mut rng, frame.dt);
+            input.new_frame();
+        }
+
+        assert!(player.is_finished());
+        assert_eq!(replayed, live);
+        assert_eq!(input.snapshot(), live_input.snapshot());
+    }
+}
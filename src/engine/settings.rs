@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use sokol::gfx as sg;
+use std::{collections::HashMap, error::Error, fs, path::Path};
+
+use crate::engine::GameConfig;
+
+/// User settings persisted to disk as JSON.
+/// Every field is optional so a settings file only overrides what the user changed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub window_width: Option<i32>,
+    pub window_height: Option<i32>,
+    pub sample_count: Option<i32>,
+    pub high_dpi: Option<bool>,
+    /// RGBA background color
+    pub background_color: Option<[f32; 4]>,
+    pub master_volume: Option<f32>,
+    /// Action name -> key code (`sapp::Keycode as i32`)
+    #[serde(default)]
+    pub key_bindings: HashMap<String, i32>,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load settings from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        let settings = serde_json::from_str(&contents)?;
+        Ok(settings)
+    }
+
+    /// Load settings, falling back to defaults if the file is missing or invalid
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Self {
+        Self::load(path).unwrap_or_default()
+    }
+
+    /// Save settings to a JSON file, creating parent directories as needed
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Capture the current values of a config
+    pub fn from_config(config: &GameConfig) -> Self {
+        let color = config.background_color;
+        Self {
+            window_width: Some(config.window_width),
+            window_height: Some(config.window_height),
+            sample_count: Some(config.sample_count),
+            high_dpi: Some(config.high_dpi),
+            background_color: Some([color.r, color.g, color.b, color.a]),
+            ..Default::default()
+        }
+    }
+
+    /// Override the fields of `config` that are set in these settings
+    pub fn apply_to(&self, config: &mut GameConfig) {
+        if let Some(width) = self.window_width {
+            config.window_width = width;
+        }
+        if let Some(height) = self.window_height {
+            config.window_height = height;
+        }
+        if let Some(samples) = self.sample_count {
+            config.sample_count = samples;
+        }
+        if let Some(high_dpi) = self.high_dpi {
+            config.high_dpi = high_dpi;
+        }
+        if let Some([r, g, b, a]) = self.background_color {
+            config.background_color = sg::Color { r, g, b, a };
+        }
+    }
+
+    pub fn set_key_binding(&mut self, action: &str, key_code: i32) {
+        self.key_bindings.insert(action.to_string(), key_code);
+    }
+
+    pub fn get_key_binding(&self, action: &str) -> Option<i32> {
+        self.key_bindings.get(action).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let mut settings = Settings::new();
+        settings.window_width = Some(1280);
+        settings.window_height = Some(720);
+        settings.high_dpi = Some(true);
+        settings.background_color = Some([0.1, 0.2, 0.3, 1.0]);
+        settings.master_volume = Some(0.5);
+        settings.set_key_binding("jump", 32);
+
+        let path = std::env::temp_dir()
+            .join(format!("rusclog_settings_{}", std::process::id()))
+            .join("settings.json");
+        settings.save(&path).unwrap();
+        let loaded = Settings::load(&path);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+
+        assert_eq!(loaded.unwrap(), settings);
+    }
+}
@@ -1,6 +1,14 @@
 use glam::{Vec2, Vec4};
+use std::collections::HashMap;
 
-use crate::engine::{Renderer, Sprite};
+use crate::engine::{Quad, Renderer, Sprite};
+
+/// Where text shorter than its path starts along it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathTextAlign {
+    Start,
+    Center,
+}
 
 #[derive(Clone)]
 pub struct TextRenderer {
@@ -12,6 +20,9 @@ pub struct TextRenderer {
     scale: f32,       // default scale
     spacing: f32,     // extra advance between glyphs (in source glyph pixels)
     first_codepoint: u32,
+    cell_padding: Vec2, // pixels between neighbouring glyph cells in the atlas
+    atlas_origin: Vec2, // pixel offset of the first cell, e.g. an atlas border
+    atlas_size: Option<Vec2>, // atlas size in pixels, derived from the layout if None
 }
 
 impl TextRenderer {
@@ -31,9 +42,40 @@ impl TextRenderer {
             scale: 1.0,
             spacing: 0.0,
             first_codepoint: 32,
+            cell_padding: Vec2::ZERO,
+            atlas_origin: Vec2::ZERO,
+            atlas_size: None,
         }
     }
 
+    /// Pixels of padding between glyph cells, so neighbours don't bleed into each glyph
+    pub fn with_cell_padding(mut self, padding: Vec2) -> Self {
+        self.cell_padding = padding.max(Vec2::ZERO);
+        self
+    }
+
+    /// Pixel position of the first cell's top-left corner, for atlases with a border
+    pub fn with_atlas_origin(mut self, origin: Vec2) -> Self {
+        self.atlas_origin = origin.max(Vec2::ZERO);
+        self
+    }
+
+    /// Atlas size in pixels. Without it the atlas is assumed to have the same border
+    /// on all sides: origin + cells + padding between cells + origin.
+    pub fn with_atlas_size(mut self, size: Vec2) -> Self {
+        self.atlas_size = Some(size);
+        self
+    }
+
+    /// Atlas size in pixels used for UVs
+    fn atlas_pixel_size(&self) -> Vec2 {
+        self.atlas_size.unwrap_or_else(|| {
+            let cells = Vec2::new(self.atlas_cols as f32, self.atlas_rows as f32);
+            let gaps = (cells - Vec2::ONE).max(Vec2::ZERO);
+            self.atlas_origin * 2.0 + cells * self.glyph_size + gaps * self.cell_padding
+        })
+    }
+
     pub fn set_color(&mut self, color: Vec4) {
         self.color = color;
     }
@@ -50,53 +92,176 @@ impl TextRenderer {
         Vec2::new(w, h)
     }
 
+    /// Largest scale at which `text` fits on one line inside `size`
+    pub fn fit_scale(&self, size: Vec2, text: &str) -> f32 {
+        let unit = self.measure_single_line_px(text) / self.scale;
+        if unit.x <= 0.0 || unit.y <= 0.0 {
+            return self.scale;
+        }
+        (size.x / unit.x).min(size.y / unit.y).max(0.01)
+    }
+
+    /// Draw `text` on one line as large as fits inside `rect`, centered in it
+    pub fn draw_text_fit(&self, renderer: &mut Renderer, rect: &Quad, text: &str) {
+        let mut fitted = self.clone();
+        fitted.set_scale(self.fit_scale(rect.size, text));
+        let size = fitted.measure_single_line_px(text);
+        fitted.draw_text_world(renderer, rect.position - size * 0.5, text);
+    }
+
     // Draw anchored in world space (respects camera)
     pub fn draw_text_world(&self, renderer: &mut Renderer, mut pos: Vec2, text: &str) {
-        let uv_w = 1.0 / self.atlas_cols as f32;
-        let uv_h = 1.0 / self.atlas_rows as f32;
-
         let adv_x = (self.glyph_size.x + self.spacing) * self.scale;
         let adv_y = (self.glyph_size.y + self.spacing) * self.scale;
 
         let line_start_x = pos.x;
+        // Glyph bitmaps are stored top row first, so flip them when +Y is up
+        let y_up = renderer.is_y_up();
+        let line_step = if y_up { -adv_y } else { adv_y };
 
         for ch in text.chars() {
             if ch == '\n' {
                 pos.x = line_start_x;
-                pos.y -= adv_y;
+                pos.y += line_step;
                 continue;
             }
 
-            // Map from Unicode codepoint to atlas index starting at first_codepoint (' ' = 32)
-            let code = ch as u32;
-            let idx = code.saturating_sub(self.first_codepoint);
-            if idx >= (self.atlas_cols * self.atlas_rows) {
+            let Some(uv) = self.glyph_uv(ch) else {
                 pos.x += adv_x;
                 continue;
-            }
+            };
 
-            let col = (idx % self.atlas_cols) as f32;
-            let row = (idx / self.atlas_cols) as f32;
+            let mut sprite = Sprite::new()
+                .with_texture_name(self.texture_name.clone())
+                .with_position(pos + self.glyph_size * 0.5 * self.scale)
+                .with_size(self.glyph_size * self.scale)
+                .with_uv(uv)
+                .with_color(self.color)
+                .with_flip_y(y_up);
 
-            // Flip V so row 0 is the top row of the bitmap
-            let u = col * uv_w;
-            let v = row * uv_h;
+            renderer.draw_sprite(&mut sprite);
+            pos.x += adv_x;
+        }
+    }
 
-            let uv = glam::Vec4::new(u, v, uv_w, uv_h);
+    /// Draw text along a world-space polyline, each glyph rotated to follow the path.
+    /// Glyphs that don't fit before the end of the path are clipped.
+    pub fn draw_text_along_path(&self, renderer: &mut Renderer, points: &[Vec2], text: &str) {
+        self.draw_text_along_path_aligned(renderer, points, text, PathTextAlign::Start);
+    }
 
+    pub fn draw_text_along_path_aligned(
+        &self,
+        renderer: &mut Renderer,
+        points: &[Vec2],
+        text: &str,
+        align: PathTextAlign,
+    ) {
+        let half_height = self.glyph_size.y * 0.5 * self.scale;
+        let y_up = renderer.is_y_up();
+
+        for (ch, (position, tangent)) in text
+            .chars()
+            .zip(self.path_glyph_placements(points, text, align))
+        {
+            let Some(uv) = self.glyph_uv(ch) else {
+                continue;
+            };
+
+            // Sit the glyph on the path like a baseline, on the side that is up on screen
+            let normal = if y_up {
+                tangent.perp()
+            } else {
+                -tangent.perp()
+            };
             let mut sprite = Sprite::new()
                 .with_texture_name(self.texture_name.clone())
-                .with_position(pos + self.glyph_size * 0.5 * self.scale)
+                .with_position(position + normal * half_height)
                 .with_size(self.glyph_size * self.scale)
                 .with_uv(uv)
                 .with_color(self.color)
-                .with_flip_y(true);
+                .with_rotation(tangent.y.atan2(tangent.x))
+                .with_flip_y(y_up);
 
             renderer.draw_sprite(&mut sprite);
-            pos.x += adv_x;
         }
     }
 
+    /// Center and unit tangent on the path for each glyph of `text`, in order.
+    /// Stops early when the path runs out, so this can be shorter than the text.
+    pub fn path_glyph_placements(
+        &self,
+        points: &[Vec2],
+        text: &str,
+        align: PathTextAlign,
+    ) -> Vec<(Vec2, Vec2)> {
+        let path_length: f32 = points.windows(2).map(|w| w[0].distance(w[1])).sum();
+        if path_length <= 0.0 {
+            return Vec::new();
+        }
+
+        let advance = (self.glyph_size.x + self.spacing) * self.scale;
+        let text_length = text.chars().count() as f32 * advance;
+        let start = match align {
+            PathTextAlign::Start => 0.0,
+            PathTextAlign::Center => ((path_length - text_length) * 0.5).max(0.0),
+        };
+
+        let mut placements = Vec::new();
+        for index in 0..text.chars().count() {
+            let glyph_start = start + index as f32 * advance;
+            if glyph_start + advance > path_length {
+                break;
+            }
+            if let Some(placement) = Self::sample_path(points, glyph_start + advance * 0.5) {
+                placements.push(placement);
+            }
+        }
+        placements
+    }
+
+    /// Point and unit tangent at `distance` along a polyline
+    fn sample_path(points: &[Vec2], mut distance: f32) -> Option<(Vec2, Vec2)> {
+        let mut last = None;
+        for segment in points.windows(2) {
+            let (a, b) = (segment[0], segment[1]);
+            let length = a.distance(b);
+            let Some(tangent) = (b - a).try_normalize() else {
+                continue;
+            };
+            if distance <= length {
+                return Some((a + tangent * distance, tangent));
+            }
+            distance -= length;
+            last = Some((b, tangent));
+        }
+        last
+    }
+
+    /// Atlas UV rect for a character, None if it's outside the atlas
+    fn glyph_uv(&self, ch: char) -> Option<Vec4> {
+        let atlas = self.atlas_pixel_size();
+        let uv_w = self.glyph_size.x / atlas.x;
+        let uv_h = self.glyph_size.y / atlas.y;
+
+        // Map from Unicode codepoint to atlas index starting at first_codepoint (' ' = 32)
+        let code = ch as u32;
+        let idx = code.saturating_sub(self.first_codepoint);
+        if idx >= (self.atlas_cols * self.atlas_rows) {
+            return None;
+        }
+
+        let col = (idx % self.atlas_cols) as f32;
+        let row = (idx / self.atlas_cols) as f32;
+
+        // Row 0 is the top row of the bitmap; padding sits after each cell
+        let pitch = self.glyph_size + self.cell_padding;
+        let u = (self.atlas_origin.x + col * pitch.x) / atlas.x;
+        let v = (self.atlas_origin.y + row * pitch.y) / atlas.y;
+
+        Some(Vec4::new(u, v, uv_w, uv_h))
+    }
+
     // Draw at screen pixel position (top-left origin) regardless of camera
     pub fn draw_text_screen(
         &self,
@@ -135,3 +300,206 @@ impl TextRenderer {
         self.draw_text_screen(renderer, camera, Vec2::new(x, y), text);
     }
 }
+
+/// Named fonts, so games pick a font per draw like they pick textures by name
+#[derive(Clone, Default)]
+pub struct FontRegistry {
+    fonts: HashMap<String, TextRenderer>,
+}
+
+impl FontRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_font(mut self, name: &str, font: TextRenderer) -> Self {
+        self.register(name, font);
+        self
+    }
+
+    /// Add a font, replacing any font already registered under `name`
+    pub fn register(&mut self, name: &str, font: TextRenderer) {
+        self.fonts.insert(name.to_string(), font);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<TextRenderer> {
+        self.fonts.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TextRenderer> {
+        self.fonts.get(name)
+    }
+
+    /// For changing a font's color, scale or spacing
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut TextRenderer> {
+        self.fonts.get_mut(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.fonts.contains_key(name)
+    }
+
+    /// `TextRenderer::draw_text_world` with the named font. Returns false if it isn't registered.
+    pub fn draw_text(
+        &self,
+        renderer: &mut Renderer,
+        font_name: &str,
+        pos: Vec2,
+        text: &str,
+    ) -> bool {
+        let Some(font) = self.fonts.get(font_name) else {
+            return false;
+        };
+        font.draw_text_world(renderer, pos, text);
+        true
+    }
+
+    /// Like `draw_text`, but `pos` is in screen pixels from the top-left, for HUD and
+    /// menu text. The renderer's draw space is restored afterwards.
+    pub fn draw_text_ui(
+        &self,
+        renderer: &mut Renderer,
+        font_name: &str,
+        pos: Vec2,
+        text: &str,
+    ) -> bool {
+        let previous_space = renderer.draw_space();
+        renderer.begin_ui();
+        let drawn = self.draw_text(renderer, font_name, pos, text);
+        renderer.set_draw_space(previous_space);
+        drawn
+    }
+
+    /// `TextRenderer::draw_text_fit` with the named font. Returns false if it isn't registered.
+    pub fn draw_text_fit(
+        &self,
+        renderer: &mut Renderer,
+        font_name: &str,
+        rect: &Quad,
+        text: &str,
+    ) -> bool {
+        let Some(font) = self.fonts.get(font_name) else {
+            return false;
+        };
+        font.draw_text_fit(renderer, rect, text);
+        true
+    }
+
+    /// Size of `text` on one line in the named font
+    pub fn measure(&self, font_name: &str, text: &str) -> Option<Vec2> {
+        self.fonts
+            .get(font_name)
+            .map(|font| font.measure_single_line_px(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::DrawSpace;
+
+    fn font() -> TextRenderer {
+        TextRenderer::new("font", 8.0, 8.0, 16, 6)
+    }
+
+    #[test]
+    fn fit_scale_uses_the_tighter_axis() {
+        let mut font = font();
+        // "abcd" is 32x8 at scale 1
+        assert_eq!(font.fit_scale(Vec2::new(64.0, 32.0), "abcd"), 2.0);
+        assert_eq!(font.fit_scale(Vec2::new(320.0, 16.0), "abcd"), 2.0);
+
+        // The current scale doesn't change the fit
+        font.set_scale(3.0);
+        assert_eq!(font.fit_scale(Vec2::new(64.0, 32.0), "abcd"), 2.0);
+        assert_eq!(font.fit_scale(Vec2::new(64.0, 32.0), ""), 3.0);
+    }
+
+    #[test]
+    fn path_glyph_placements_follow_the_path() {
+        let font = font();
+        let corner = [Vec2::ZERO, Vec2::new(40.0, 0.0), Vec2::new(40.0, 40.0)];
+
+        let start = font.path_glyph_placements(&corner, "abc", PathTextAlign::Start);
+        assert_eq!(
+            start,
+            vec![
+                (Vec2::new(4.0, 0.0), Vec2::X),
+                (Vec2::new(12.0, 0.0), Vec2::X),
+                (Vec2::new(20.0, 0.0), Vec2::X),
+            ]
+        );
+
+        // 24 pixels of text centered on the 80 pixel path, the last glyph past the corner
+        let centered = font.path_glyph_placements(&corner, "abc", PathTextAlign::Center);
+        assert_eq!(
+            centered,
+            vec![
+                (Vec2::new(32.0, 0.0), Vec2::X),
+                (Vec2::new(40.0, 0.0), Vec2::X),
+                (Vec2::new(40.0, 8.0), Vec2::Y),
+            ]
+        );
+    }
+
+    #[test]
+    fn path_glyph_placements_stop_at_the_end_of_the_path() {
+        let font = font();
+        let short = [Vec2::ZERO, Vec2::new(20.0, 0.0)];
+        let placements = font.path_glyph_placements(&short, "abcdef", PathTextAlign::Center);
+        assert_eq!(placements.len(), 2);
+        assert!(font
+            .path_glyph_placements(&[Vec2::ZERO], "abc", PathTextAlign::Start)
+            .is_empty());
+    }
+
+    #[test]
+    fn glyph_uv_skips_padding_and_border() {
+        let font = font()
+            .with_cell_padding(Vec2::new(2.0, 2.0))
+            .with_atlas_origin(Vec2::new(1.0, 1.0));
+        // 1 + 16 * 8 + 15 * 2 + 1 by 1 + 6 * 8 + 5 * 2 + 1
+        let atlas = Vec2::new(160.0, 60.0);
+
+        // 'A' is index 33: column 1, row 2
+        let uv = font.glyph_uv('A').unwrap();
+        let expected = Vec4::new(11.0 / atlas.x, 21.0 / atlas.y, 8.0 / atlas.x, 8.0 / atlas.y);
+        assert!((uv - expected).abs().max_element() < 1e-6, "{uv:?}");
+
+        assert!(font.glyph_uv('\u{80}').is_none());
+    }
+
+    #[test]
+    fn registered_fonts_draw_with_their_own_atlas() {
+        let fonts = FontRegistry::new()
+            .with_font("body", font())
+            .with_font("title", TextRenderer::new("title", 16.0, 16.0, 8, 4));
+        let mut renderer = Renderer::new();
+
+        assert!(fonts.draw_text_ui(&mut renderer, "body", Vec2::new(10.0, 20.0), "!"));
+        assert!(fonts.draw_text_ui(&mut renderer, "title", Vec2::new(10.0, 20.0), "!"));
+        assert!(!fonts.draw_text_ui(&mut renderer, "missing", Vec2::ZERO, "!"));
+
+        // Top-left and bottom-right corner of each glyph: position and atlas UV
+        let corners: Vec<_> = renderer
+            .vertices()
+            .iter()
+            .step_by(2)
+            .map(|vertex| (vertex.pos, vertex.texcoord))
+            .collect();
+        assert_eq!(
+            corners,
+            vec![
+                ([10.0, 20.0], [8.0 / 128.0, 0.0]),
+                ([18.0, 28.0], [16.0 / 128.0, 8.0 / 48.0]),
+                ([10.0, 20.0], [16.0 / 128.0, 0.0]),
+                ([26.0, 36.0], [32.0 / 128.0, 16.0 / 64.0]),
+            ]
+        );
+        assert!(renderer
+            .batch_spaces()
+            .iter()
+            .all(|&space| space == DrawSpace::Screen));
+        assert_eq!(renderer.draw_space(), DrawSpace::World);
+    }
+}
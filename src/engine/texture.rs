@@ -1,16 +1,29 @@
+use glam::{Vec2, Vec4};
 use sokol::gfx as sg;
 use std::collections::HashMap;
 
+const MISSING_TEXTURE_SIZE: usize = 8;
+const MISSING_TEXTURE_CELL: usize = 4;
+
 pub struct TextureManager {
     textures: HashMap<String, sg::Image>,
+    /// Pixel size of each loaded texture
+    sizes: HashMap<String, (u32, u32)>,
     white_texture: sg::Image,
+    /// Magenta checkerboard drawn in place of textures that were never loaded
+    missing_texture: sg::Image,
+    /// Panic on missing textures instead of drawing the placeholder
+    strict: bool,
 }
 
 impl TextureManager {
     pub fn new() -> Self {
         Self {
             textures: HashMap::new(),
+            sizes: HashMap::new(),
             white_texture: sg::Image::default(),
+            missing_texture: sg::Image::default(),
+            strict: false,
         }
     }
 
@@ -27,9 +40,44 @@ impl TextureManager {
             },
             ..Default::default()
         });
+
+        let missing_pixels = Self::missing_texture_pixels();
+        self.missing_texture = sg::make_image(&sg::ImageDesc {
+            width: MISSING_TEXTURE_SIZE as i32,
+            height: MISSING_TEXTURE_SIZE as i32,
+            pixel_format: sg::PixelFormat::Rgba8,
+            data: sg::ImageData {
+                subimage: [[sg::Range {
+                    ptr: missing_pixels.as_ptr() as *const _,
+                    size: missing_pixels.len(),
+                }; 16]; 6],
+            },
+            ..Default::default()
+        });
+    }
+
+    /// RGBA pixels of the placeholder: magenta and black cells
+    fn missing_texture_pixels() -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(MISSING_TEXTURE_SIZE * MISSING_TEXTURE_SIZE * 4);
+        for y in 0..MISSING_TEXTURE_SIZE {
+            for x in 0..MISSING_TEXTURE_SIZE {
+                let magenta =
+                    (x / MISSING_TEXTURE_CELL + y / MISSING_TEXTURE_CELL).is_multiple_of(2);
+                if magenta {
+                    pixels.extend_from_slice(&[255, 0, 255, 255]);
+                } else {
+                    pixels.extend_from_slice(&[0, 0, 0, 255]);
+                }
+            }
+        }
+        pixels
     }
 
-    pub fn load_texture(&mut self, name: &str, path: &str) -> Result<sg::Image, Box<dyn std::error::Error>> {
+    pub fn load_texture(
+        &mut self,
+        name: &str,
+        path: &str,
+    ) -> Result<sg::Image, Box<dyn std::error::Error>> {
         // Check if already loaded
         if let Some(&texture) = self.textures.get(name) {
             return Ok(texture);
@@ -54,16 +102,157 @@ impl TextureManager {
             ..Default::default()
         });
 
-        // Store in cache
-        self.textures.insert(name.to_string(), sg_texture);
+        self.insert_texture(name, sg_texture, (width, height));
         Ok(sg_texture)
     }
 
+    /// Cache an already created image under `name` with its pixel size
+    pub(crate) fn insert_texture(&mut self, name: &str, texture: sg::Image, size: (u32, u32)) {
+        self.textures.insert(name.to_string(), texture);
+        self.sizes.insert(name.to_string(), size);
+    }
+
+    /// Destroy a loaded texture and drop it from the cache, returning the freed image.
+    /// The built-in white texture is never unloaded.
+    pub fn unload_texture(&mut self, name: &str) -> Option<sg::Image> {
+        let texture = self.forget_texture(name)?;
+        sg::destroy_image(texture);
+        Some(texture)
+    }
+
+    /// Drop a texture from the cache without destroying the image
+    fn forget_texture(&mut self, name: &str) -> Option<sg::Image> {
+        let texture = *self.textures.get(name)?;
+        if texture.id == self.white_texture.id {
+            return None;
+        }
+
+        self.textures.remove(name);
+        self.sizes.remove(name);
+        Some(texture)
+    }
+
+    /// Destroy every loaded texture, e.g. on scene transitions, returning the freed images
+    pub fn unload_all(&mut self) -> Vec<sg::Image> {
+        let names: Vec<String> = self.textures.keys().cloned().collect();
+        names
+            .iter()
+            .filter_map(|name| self.unload_texture(name))
+            .collect()
+    }
+
     pub fn get_texture(&self, name: &str) -> Option<sg::Image> {
         self.textures.get(name).copied()
     }
 
+    /// Size in pixels of a loaded texture
+    pub fn texture_size(&self, name: &str) -> Option<(u32, u32)> {
+        self.sizes.get(name).copied()
+    }
+
     pub fn get_white_texture(&self) -> sg::Image {
         self.white_texture
     }
-}
\ No newline at end of file
+
+    pub fn get_missing_texture(&self) -> sg::Image {
+        self.missing_texture
+    }
+
+    /// Texture to draw for `name`: white for an empty name (untextured), the loaded texture,
+    /// or the missing-texture placeholder. Panics instead of using the placeholder in strict mode.
+    pub fn resolve_texture(&self, name: &str) -> sg::Image {
+        if name.is_empty() {
+            return self.white_texture;
+        }
+        match self.textures.get(name) {
+            Some(&texture) => texture,
+            None if self.strict => panic!("texture '{}' is not loaded", name),
+            None => self.missing_texture,
+        }
+    }
+
+    /// Panic when drawing a texture that isn't loaded, e.g. to catch missing assets in CI
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+}
+
+/// Normalized UV rect (u, v, width, height) of cell `index` in a sheet of `frame_size` cells,
+/// `cols` per row, counted left to right then top to bottom
+pub fn grid_cell_uv(texture_size: Vec2, frame_size: Vec2, cols: u32, index: u32) -> Vec4 {
+    let cols = cols.max(1);
+    let col = index % cols;
+    let row = index / cols;
+    let size = frame_size / texture_size;
+    Vec4::new(col as f32 * size.x, row as f32 * size.y, size.x, size.y)
+}
+
+/// UV rects of every cell in a `cols` x `rows` grid of `frame_size` cells, in
+/// `grid_cell_uv` order. Usable as sprite UVs for static frames of a sheet.
+pub fn slice_grid(texture_size: Vec2, frame_size: Vec2, cols: u32, rows: u32) -> Vec<Vec4> {
+    (0..cols * rows)
+        .map(|index| grid_cell_uv(texture_size, frame_size, cols, index))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_grid_2x2_on_64x64() {
+        let uvs = slice_grid(Vec2::new(64.0, 64.0), Vec2::new(32.0, 32.0), 2, 2);
+        assert_eq!(
+            uvs,
+            vec![
+                Vec4::new(0.0, 0.0, 0.5, 0.5),
+                Vec4::new(0.5, 0.0, 0.5, 0.5),
+                Vec4::new(0.0, 0.5, 0.5, 0.5),
+                Vec4::new(0.5, 0.5, 0.5, 0.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn forgotten_texture_can_be_inserted_again() {
+        let mut manager = TextureManager::new();
+        manager.insert_texture("player", sg::Image { id: 7 }, (16, 16));
+
+        assert_eq!(
+            manager.forget_texture("player").map(|image| image.id),
+            Some(7)
+        );
+        assert!(manager.get_texture("player").is_none());
+        assert_eq!(manager.texture_size("player"), None);
+        assert!(manager.forget_texture("player").is_none());
+
+        manager.insert_texture("player", sg::Image { id: 8 }, (32, 32));
+        assert_eq!(manager.get_texture("player").map(|image| image.id), Some(8));
+        assert_eq!(manager.texture_size("player"), Some((32, 32)));
+    }
+
+    #[test]
+    fn missing_textures_resolve_to_the_placeholder() {
+        let mut manager = TextureManager::new();
+        // Stand-ins for the images `init` creates
+        manager.white_texture = sg::Image { id: 1 };
+        manager.missing_texture = sg::Image { id: 2 };
+        manager.insert_texture("player", sg::Image { id: 3 }, (16, 16));
+
+        assert_eq!(manager.resolve_texture("").id, 1);
+        assert_eq!(manager.resolve_texture("player").id, 3);
+        assert_eq!(manager.resolve_texture("no_such_texture").id, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "not loaded")]
+    fn strict_mode_panics_on_missing_textures() {
+        let mut manager = TextureManager::new();
+        manager.set_strict(true);
+        manager.resolve_texture("no_such_texture");
+    }
+}
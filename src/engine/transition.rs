@@ -0,0 +1,137 @@
+use glam::Vec4;
+
+use crate::engine::{Quad, Renderer};
+
+/// Full-screen fade for hiding scene changes, e.g. fade out, switch state, fade in.
+/// Call `update` every frame and `render` last, ideally from `Game::render_overlay`.
+#[derive(Clone, Debug)]
+pub struct Transition {
+    color: Vec4,
+    from: f32,
+    to: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self::new(Vec4::new(0.0, 0.0, 0.0, 1.0))
+    }
+}
+
+impl Transition {
+    /// Fade through `color`, starting fully transparent
+    pub fn new(color: Vec4) -> Self {
+        Self {
+            color,
+            from: 0.0,
+            to: 0.0,
+            duration: 0.0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Cover the screen over `duration` seconds
+    pub fn start_fade_out(&mut self, duration: f32) {
+        self.start(0.0, 1.0, duration);
+    }
+
+    /// Uncover the screen over `duration` seconds
+    pub fn start_fade_in(&mut self, duration: f32) {
+        self.start(1.0, 0.0, duration);
+    }
+
+    fn start(&mut self, from: f32, to: f32, duration: f32) {
+        self.from = from;
+        self.to = to;
+        self.duration = duration.max(0.0);
+        self.elapsed = 0.0;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+    }
+
+    /// Fade progress in 0..=1, 1 once the current fade has finished
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            self.elapsed / self.duration
+        }
+    }
+
+    /// Cover alpha (0 clear, 1 fully covered), eased in and out
+    pub fn alpha(&self) -> f32 {
+        let t = self.progress();
+        let eased = t * t * (3.0 - 2.0 * t);
+        self.from + (self.to - self.from) * eased
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Whether the screen is fully covered, the point to switch scenes during a fade out
+    pub fn is_covered(&self) -> bool {
+        self.alpha() >= 1.0
+    }
+
+    /// Draw the cover quad over the whole screen. Draws nothing while fully clear.
+    pub fn render(&self, renderer: &mut Renderer) {
+        let alpha = self.alpha() * self.color.w;
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let previous_space = renderer.draw_space();
+        let previous_key = renderer.sort_key();
+        renderer.begin_ui();
+        renderer.set_sort_key(f32::INFINITY);
+
+        let size = renderer.screen_size();
+        let center = size * 0.5;
+        let color = Vec4::new(self.color.x, self.color.y, self.color.z, alpha);
+        renderer.draw_quad(&Quad::new(center.x, center.y, size.x, size.y, color));
+
+        renderer.set_sort_key(previous_key);
+        renderer.set_draw_space(previous_space);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fade_out_reaches_full_cover() {
+        let mut transition = Transition::default();
+        transition.start_fade_out(0.5);
+        assert_eq!(transition.alpha(), 0.0);
+
+        transition.update(0.25);
+        assert!((transition.alpha() - 0.5).abs() < 1e-6);
+        assert!(!transition.is_covered());
+
+        transition.update(0.25);
+        assert_eq!(transition.alpha(), 1.0);
+        assert!(transition.is_covered());
+        assert!(transition.is_done());
+    }
+
+    #[test]
+    fn fade_in_clears_the_screen() {
+        let mut transition = Transition::default();
+        transition.start_fade_in(0.5);
+        assert_eq!(transition.alpha(), 1.0);
+
+        transition.update(1.0);
+        assert_eq!(transition.alpha(), 0.0);
+        assert!(transition.is_done());
+    }
+}